@@ -0,0 +1,334 @@
+/// Access pattern hints for a memory map, see [`advise`] and [`advise_range`].
+///
+/// The hints are forwarded to `madvise` on unix platforms, hints which are not supported
+/// by the platform are ignored.
+///
+/// [`advise`]: trait.MmapFileExt.html#tymethod.advise
+/// [`advise_range`]: trait.MmapFileExt.html#tymethod.advise_range
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Advice {
+    /// No special treatment, this is the default.
+    Normal,
+    /// Expect page references in sequential order, so pages can be aggressively read ahead
+    /// and freed soon after they are accessed.
+    Sequential,
+    /// Expect page references in random order, so read ahead is less useful.
+    Random,
+    /// Expect access in the near future, so it might be a good idea to read some pages ahead.
+    WillNeed,
+    /// Enable transparent huge pages for the range.
+    ///
+    /// This hint only has effect on Linux.
+    HugePage,
+}
+
+/// Hints for a memory map which may change its contents, see [`unchecked_advise`]
+/// and [`unchecked_advise_range`].
+///
+/// On private mappings (e.g. copy-on-write or anonymous memory), the pages in the range may
+/// read back as zeros afterwards, so these hints are only accepted by the unsafe methods.
+///
+/// [`unchecked_advise`]: trait.MmapFileExt.html#method.unchecked_advise
+/// [`unchecked_advise_range`]: trait.MmapFileExt.html#tymethod.unchecked_advise_range
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum UncheckedAdvice {
+    /// Do not expect access in the near future, the pages can be released from memory.
+    ///
+    /// For private mappings (e.g. copy-on-write), the modifications in the range are discarded.
+    DontNeed,
+    /// The pages in the range are no longer needed and can be freed lazily.
+    ///
+    /// This hint only has effect on Linux (since Linux 4.5), and only on private mappings.
+    Free,
+}
+
+/// The huge pages backing a memory map, see [`Options::huge_pages`].
+///
+/// [`Options::huge_pages`]: struct.Options.html#method.huge_pages
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum HugePageSize {
+    /// Transparent huge pages (`MADV_HUGEPAGE`), the kernel backs the mapping with huge pages
+    /// when it can, and falls back to normal pages otherwise.
+    Transparent,
+    /// 2 MiB pages from the hugetlb pool (`MAP_HUGETLB | MAP_HUGE_2MB`).
+    Size2M,
+    /// 1 GiB pages from the hugetlb pool (`MAP_HUGETLB | MAP_HUGE_1GB`).
+    Size1G,
+}
+
+impl HugePageSize {
+    /// Returns the size of a huge page in bytes, or `None` for transparent huge pages,
+    /// whose size is decided by the kernel.
+    pub fn page_size(&self) -> Option<usize> {
+        match self {
+            HugePageSize::Transparent => None,
+            HugePageSize::Size2M => Some(2 << 20),
+            HugePageSize::Size1G => Some(1 << 30),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Advice {
+    /// Returns the `madvise` advice for the current platform, or `None` if it is not supported.
+    pub(crate) fn to_raw(self) -> Option<memmapix::Advice> {
+        use memmapix::Advice as Raw;
+
+        match self {
+            Advice::Normal => Some(Raw::Normal),
+            Advice::Sequential => Some(Raw::Sequential),
+            Advice::Random => Some(Raw::Random),
+            Advice::WillNeed => Some(Raw::WillNeed),
+            #[cfg(target_os = "linux")]
+            Advice::HugePage => Some(Raw::LinuxHugepage),
+            #[cfg(not(target_os = "linux"))]
+            Advice::HugePage => None,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl UncheckedAdvice {
+    /// Returns the `madvise` advice for the current platform, or `None` if it is not supported.
+    pub(crate) fn to_raw(self) -> Option<memmapix::Advice> {
+        use memmapix::Advice as Raw;
+
+        match self {
+            #[cfg(target_os = "linux")]
+            UncheckedAdvice::DontNeed => Some(Raw::LinuxDontNeed),
+            #[cfg(not(target_os = "linux"))]
+            UncheckedAdvice::DontNeed => Some(Raw::DontNeed),
+            #[cfg(target_os = "linux")]
+            UncheckedAdvice::Free => Some(Raw::LinuxFree),
+            #[cfg(not(target_os = "linux"))]
+            UncheckedAdvice::Free => None,
+        }
+    }
+}
+
+/// Returns an error if `[offset, offset + len)` is out of a mmap of `mmap_len` bytes.
+#[inline]
+pub(crate) fn check_range(offset: usize, len: usize, mmap_len: usize) -> crate::error::Result<()> {
+    match offset.checked_add(len) {
+        Some(end) if end <= mmap_len => Ok(()),
+        _ => Err(crate::error::Error::from(
+            crate::error::ErrorKind::OutOfBound(offset.saturating_add(len), mmap_len),
+        )),
+    }
+}
+
+/// Returns the size of a memory page.
+#[inline]
+pub(crate) fn page_size() -> usize {
+    #[cfg(unix)]
+    {
+        rustix::param::page_size()
+    }
+    // there is no cheap way to query the page size without extra dependencies,
+    // all the tier-1 windows targets use 4 KiB pages.
+    #[cfg(not(unix))]
+    {
+        4096
+    }
+}
+
+/// Returns the number of pages spanned by `buf`.
+#[inline]
+pub(crate) fn pages_of(buf: &[u8]) -> usize {
+    if buf.is_empty() {
+        return 0;
+    }
+    let page_size = page_size();
+    let start = buf.as_ptr() as usize;
+    let span = start % page_size + buf.len();
+    (span + page_size - 1) / page_size
+}
+
+/// Returns `(resident, total)` pages spanned by `buf`, which must point into a memory map.
+#[cfg(unix)]
+pub(crate) fn residency(buf: &[u8]) -> std::io::Result<(usize, usize)> {
+    let pages = pages_of(buf);
+    if pages == 0 {
+        return Ok((0, 0));
+    }
+    let page_size = page_size();
+    let start = buf.as_ptr() as usize;
+    let aligned = start - start % page_size;
+    let mut vec = vec![0u8; pages];
+    // Safety: the pages in [aligned, aligned + pages * page_size) are all part of the mapping
+    // which `buf` points into, and `vec` has one byte per page.
+    let rc = unsafe {
+        libc::mincore(
+            aligned as *mut libc::c_void,
+            pages * page_size,
+            vec.as_mut_ptr() as _,
+        )
+    };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok((vec.iter().filter(|v| **v & 1 == 1).count(), pages))
+}
+
+/// Returns `(resident, total)` pages spanned by `buf`, which must point into a memory map.
+#[cfg(not(unix))]
+pub(crate) fn residency(_buf: &[u8]) -> std::io::Result<(usize, usize)> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "page residency is only supported on unix platforms",
+    ))
+}
+
+/// Returns the ratio of `resident` to `total` pages, an empty range is considered fully resident.
+#[inline]
+pub(crate) fn ratio(resident: usize, total: usize) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        resident as f64 / total as f64
+    }
+}
+
+/// Locks the pages spanned by `buf` in memory, which must point into a memory map.
+///
+/// If `on_fault` is true, the pages are locked as they are faulted in (`MLOCK_ONFAULT`),
+/// instead of being populated right now.
+#[cfg(unix)]
+pub(crate) fn lock_memory(buf: &[u8], on_fault: bool) -> std::io::Result<()> {
+    let pages = pages_of(buf);
+    if pages == 0 {
+        return Ok(());
+    }
+    let page_size = page_size();
+    let start = buf.as_ptr() as usize;
+    let aligned = (start - start % page_size) as *mut std::ffi::c_void;
+    // Safety: the pages in [aligned, aligned + pages * page_size) are all part of the mapping
+    // which `buf` points into.
+    unsafe {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if on_fault {
+            return rustix::mm::mlock_with(
+                aligned,
+                pages * page_size,
+                rustix::mm::MlockFlags::ONFAULT,
+            )
+            .map_err(Into::into);
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        let _ = on_fault;
+        rustix::mm::mlock(aligned, pages * page_size).map_err(Into::into)
+    }
+}
+
+/// Unlocks the pages spanned by `buf`, which must point into a memory map.
+#[cfg(unix)]
+pub(crate) fn unlock_memory(buf: &[u8]) -> std::io::Result<()> {
+    let pages = pages_of(buf);
+    if pages == 0 {
+        return Ok(());
+    }
+    let page_size = page_size();
+    let start = buf.as_ptr() as usize;
+    let aligned = (start - start % page_size) as *mut std::ffi::c_void;
+    // Safety: the pages in [aligned, aligned + pages * page_size) are all part of the mapping
+    // which `buf` points into.
+    unsafe { rustix::mm::munlock(aligned, pages * page_size).map_err(Into::into) }
+}
+
+/// Locks the pages spanned by `buf` in memory, which must point into a memory map.
+#[cfg(not(unix))]
+pub(crate) fn lock_memory(_buf: &[u8], _on_fault: bool) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "memory locking is only supported on unix platforms",
+    ))
+}
+
+/// Unlocks the pages spanned by `buf`, which must point into a memory map.
+#[cfg(not(unix))]
+pub(crate) fn unlock_memory(_buf: &[u8]) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "memory locking is only supported on unix platforms",
+    ))
+}
+
+/// Enables transparent huge pages (`MADV_HUGEPAGE`) for the pages spanned by `buf`,
+/// which must point into a memory map.
+#[cfg(target_os = "linux")]
+pub(crate) fn transparent_huge_pages(buf: &[u8]) -> std::io::Result<()> {
+    let pages = pages_of(buf);
+    if pages == 0 {
+        return Ok(());
+    }
+    let start = buf.as_ptr() as usize;
+    let aligned = (start - start % page_size()) as *mut std::ffi::c_void;
+    // Safety: the pages in [aligned, aligned + pages * page_size) are all part of the mapping
+    // which `buf` points into.
+    unsafe {
+        rustix::mm::madvise(
+            aligned,
+            pages * page_size(),
+            rustix::mm::Advice::LinuxHugepage,
+        )
+    }
+    .map_err(Into::into)
+}
+
+/// Applies the huge pages to `buf`, which must point into a memory map of `file`.
+///
+/// Transparent huge pages are enabled with `madvise`. The hugetlb pages can only back a file
+/// on a `hugetlbfs` mount with the same page size, so the file system of `file` is checked.
+#[cfg(target_os = "linux")]
+pub(crate) fn apply_huge_pages<T: std::os::unix::io::AsRawFd>(
+    file: &T,
+    buf: &[u8],
+    size: HugePageSize,
+) -> std::io::Result<()> {
+    use std::io::{Error, ErrorKind};
+
+    const HUGETLBFS_MAGIC: u32 = 0x9584_58f6;
+
+    let page_size = match size.page_size() {
+        Some(page_size) => page_size,
+        None => return transparent_huge_pages(buf),
+    };
+
+    let mut stat = std::mem::MaybeUninit::<libc::statfs>::uninit();
+    // Safety: `stat` is a valid buffer for `fstatfs`, which is initialized on success.
+    let stat = unsafe {
+        if libc::fstatfs(file.as_raw_fd(), stat.as_mut_ptr()) != 0 {
+            return Err(Error::last_os_error());
+        }
+        stat.assume_init()
+    };
+    if stat.f_type as u32 != HUGETLBFS_MAGIC {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            "hugetlb pages require the file to be on a hugetlbfs mount",
+        ));
+    }
+    if stat.f_bsize as usize != page_size {
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!(
+                "the hugetlbfs mount uses {} bytes pages instead of {} bytes pages",
+                stat.f_bsize, page_size
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Applies the huge pages to `buf`, which must point into a memory map of `file`.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn apply_huge_pages<T>(
+    _file: &T,
+    _buf: &[u8],
+    _size: HugePageSize,
+) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "huge pages are only supported on Linux",
+    ))
+}
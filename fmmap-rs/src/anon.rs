@@ -0,0 +1,4 @@
+cfg_sync!(
+    mod sync_impl;
+    pub use sync_impl::{AnonMmapFile, AnonMmapFileMut};
+);
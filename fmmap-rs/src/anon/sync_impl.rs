@@ -0,0 +1,551 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::metadata::MemoryMetaData;
+use crate::options::Options;
+use crate::{HugePageSize, MetaData, MmapFileExt, MmapFileMutExt};
+use rustix::mm::{MapFlags, MprotectFlags, ProtFlags};
+use std::ffi::c_void;
+use std::path::Path;
+use std::ptr;
+use std::time::SystemTime;
+
+/// The flags of an anonymous memory map, which are kept to map a larger region on growth.
+#[derive(Copy, Clone)]
+struct AnonFlags {
+    shared: bool,
+    no_reserve: bool,
+    huge_pages: Option<HugePageSize>,
+    /// Whether the whole mapping is locked in memory, see `Options::lock_in_memory`.
+    locked: bool,
+}
+
+impl AnonFlags {
+    /// The size the length of the mapping is aligned to.
+    fn page_size(&self) -> usize {
+        self.huge_pages
+            .and_then(|size| size.page_size())
+            .unwrap_or_else(crate::advice::page_size)
+    }
+}
+
+/// An anonymous memory mapping (`MAP_ANONYMOUS`), which is unmapped on drop.
+struct AnonMap {
+    ptr: *mut u8,
+    cap: usize,
+    /// The page size of the mapping, which is the huge page size for `MAP_HUGETLB` mappings.
+    page_size: usize,
+}
+
+// Safety: the mapping is exclusively owned by `AnonMap`, like a `Vec` owns its buffer.
+unsafe impl Send for AnonMap {}
+unsafe impl Sync for AnonMap {}
+
+impl AnonMap {
+    /// Maps at least `len` bytes (and at least one page) of zeroed memory.
+    fn new(len: usize, flags: AnonFlags) -> Result<Self> {
+        let page_size = flags.page_size();
+        let cap = len
+            .max(1)
+            .checked_add(page_size - 1)
+            .map(|len| len - len % page_size)
+            .ok_or_else(|| Error::from(ErrorKind::OutOfBound(len, isize::MAX as usize)))?;
+
+        let mut map_flags = if flags.shared {
+            MapFlags::SHARED
+        } else {
+            MapFlags::PRIVATE
+        };
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if flags.no_reserve {
+            map_flags |= MapFlags::NORESERVE;
+        }
+        match flags.huge_pages {
+            None => {}
+            #[cfg(target_os = "linux")]
+            Some(HugePageSize::Transparent) => {}
+            #[cfg(target_os = "linux")]
+            Some(HugePageSize::Size2M) => map_flags |= MapFlags::HUGETLB | MapFlags::HUGE_2MB,
+            #[cfg(target_os = "linux")]
+            Some(HugePageSize::Size1G) => map_flags |= MapFlags::HUGETLB | MapFlags::HUGE_1GB,
+            #[cfg(not(target_os = "linux"))]
+            Some(_) => {
+                return Err(Error::new_with_message(
+                    ErrorKind::HugePagesFailed,
+                    "huge pages are only supported on Linux",
+                ))
+            }
+        }
+
+        // Safety: a new mapping is created, no existing memory is affected.
+        let ptr = unsafe {
+            rustix::mm::mmap_anonymous(
+                ptr::null_mut(),
+                cap,
+                ProtFlags::READ | ProtFlags::WRITE,
+                map_flags,
+            )
+        }
+        .map_err(|e| match flags.huge_pages {
+            // the hugetlb pool is exhausted or not configured
+            Some(HugePageSize::Size2M) | Some(HugePageSize::Size1G) => {
+                Error::new(ErrorKind::HugePagesFailed, std::io::Error::from(e))
+            }
+            _ => Error::new(ErrorKind::MmapFailed, std::io::Error::from(e)),
+        })?;
+        let map = Self {
+            ptr: ptr.cast(),
+            cap,
+            page_size,
+        };
+
+        #[cfg(target_os = "linux")]
+        if let Some(HugePageSize::Transparent) = flags.huge_pages {
+            crate::advice::transparent_huge_pages(map.slice(cap))
+                .map_err(|e| Error::new(ErrorKind::HugePagesFailed, e))?;
+        }
+
+        if flags.locked {
+            crate::advice::lock_memory(map.slice(cap), false)
+                .map_err(|e| Error::new(ErrorKind::MlockFailed, e))?;
+        }
+        Ok(map)
+    }
+
+    #[inline]
+    fn slice(&self, len: usize) -> &[u8] {
+        debug_assert!(len <= self.cap);
+        // Safety: the first `cap` bytes are mapped and readable.
+        unsafe { std::slice::from_raw_parts(self.ptr, len) }
+    }
+
+    #[inline]
+    fn slice_mut(&mut self, len: usize) -> &mut [u8] {
+        debug_assert!(len <= self.cap);
+        // Safety: the first `cap` bytes are mapped and writable until the map is protected,
+        // which consumes the writable owner.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, len) }
+    }
+
+    /// Advises the pages spanned by `[offset, offset + len)`.
+    fn advise(&self, advice: rustix::mm::Advice, offset: usize, len: usize) -> Result<()> {
+        let page_size = self.page_size;
+        let start = offset - offset % page_size;
+        let len = len + offset % page_size;
+        // Safety: `[start, start + len)` lies within the mapping, and `start` is page aligned.
+        unsafe { rustix::mm::madvise(self.ptr.add(start).cast::<c_void>(), len, advice) }
+            .map_err(|e| Error::new(ErrorKind::IO, std::io::Error::from(e)))
+    }
+
+    /// Zeroes `[start, end)`.
+    ///
+    /// The bytes in `[end, cap)` must be zeros already, so the whole pages in the range
+    /// can be handed back to the kernel instead of being written, unless the mapping is
+    /// locked in memory, the kernel refuses to drop locked pages.
+    fn zero(&mut self, start: usize, end: usize, flags: AnonFlags) -> Result<()> {
+        let page_size = self.page_size;
+        let aligned = (start + page_size - 1) / page_size * page_size;
+        if aligned >= end || flags.locked {
+            self.slice_mut(end)[start..].fill(0);
+            return Ok(());
+        }
+        self.slice_mut(aligned)[start..].fill(0);
+
+        #[cfg(target_os = "linux")]
+        {
+            // shared anonymous memory is backed by shmem, whose pages must be punched out
+            let advice = if flags.shared {
+                rustix::mm::Advice::LinuxRemove
+            } else {
+                rustix::mm::Advice::LinuxDontNeed
+            };
+            self.advise(advice, aligned, end - aligned)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            self.slice_mut(end)[aligned..].fill(0);
+            Ok(())
+        }
+    }
+
+    fn protect(&self, flags: MprotectFlags) -> Result<()> {
+        // Safety: the whole mapping is owned by `self`.
+        unsafe { rustix::mm::mprotect(self.ptr.cast::<c_void>(), self.cap, flags) }
+            .map_err(|e| Error::new(ErrorKind::IO, std::io::Error::from(e)))
+    }
+}
+
+impl Drop for AnonMap {
+    fn drop(&mut self) {
+        // Safety: the mapping is owned by `self` and never used again.
+        let _ = unsafe { rustix::mm::munmap(self.ptr.cast::<c_void>(), self.cap) };
+    }
+}
+
+macro_rules! impl_anon_mmap_file_ext {
+    ($name: ident) => {
+        impl MmapFileExt for $name {
+            #[inline]
+            fn len(&self) -> usize {
+                self.len
+            }
+
+            #[inline]
+            fn as_slice(&self) -> &[u8] {
+                self.map.slice(self.len)
+            }
+
+            /// Anonymous memory has no path, returns an empty path.
+            #[inline]
+            fn path(&self) -> &Path {
+                Path::new("")
+            }
+
+            fn metadata(&self) -> Result<MetaData> {
+                Ok(
+                    MetaData::memory(MemoryMetaData::new(self.len as u64, self.create_at))
+                        .with_huge_pages(self.flags.huge_pages),
+                )
+            }
+
+            #[inline]
+            fn is_exec(&self) -> bool {
+                self.exec
+            }
+
+            noop_file_lock!();
+
+            noop_range_lock!();
+
+            #[inline]
+            fn advise(&self, advice: crate::Advice) -> Result<()> {
+                self.advise_range(advice, 0, self.len)
+            }
+
+            fn advise_range(&self, advice: crate::Advice, offset: usize, len: usize) -> Result<()> {
+                crate::advice::check_range(offset, len, self.len)?;
+                match (advice.to_raw(), len > 0) {
+                    (Some(advice), true) => self.map.advise(advice, offset, len),
+                    _ => Ok(()),
+                }
+            }
+
+            unsafe fn unchecked_advise_range(
+                &self,
+                advice: crate::UncheckedAdvice,
+                offset: usize,
+                len: usize,
+            ) -> Result<()> {
+                crate::advice::check_range(offset, len, self.len)?;
+                match (advice.to_raw(), len > 0) {
+                    (Some(advice), true) => self.map.advise(advice, offset, len),
+                    _ => Ok(()),
+                }
+            }
+
+            fn resident_pages(&self) -> Result<usize> {
+                crate::advice::residency(self.as_slice())
+                    .map(|(resident, _)| resident)
+                    .map_err(|e| Error::new(ErrorKind::IO, e))
+            }
+
+            fn residency_ratio(&self, offset: usize, len: usize) -> Result<f64> {
+                let buf = offset
+                    .checked_add(len)
+                    .and_then(|end| self.as_slice().get(offset..end))
+                    .ok_or_else(|| {
+                        Error::from(ErrorKind::OutOfBound(offset.saturating_add(len), self.len))
+                    })?;
+                crate::advice::residency(buf)
+                    .map(|(resident, total)| crate::advice::ratio(resident, total))
+                    .map_err(|e| Error::new(ErrorKind::IO, e))
+            }
+        }
+    };
+}
+
+/// AnonMmapFile contains a read-only anonymous memory map,
+/// which is created by [`AnonMmapFileMut::freeze`].
+///
+/// [`AnonMmapFileMut::freeze`]: struct.AnonMmapFileMut.html#method.freeze
+pub struct AnonMmapFile {
+    map: AnonMap,
+    len: usize,
+    flags: AnonFlags,
+    exec: bool,
+    create_at: SystemTime,
+}
+
+impl_anon_mmap_file_ext!(AnonMmapFile);
+
+/// AnonMmapFileMut contains a writable anonymous memory map (`MAP_ANONYMOUS`),
+/// which is not backed by any file.
+///
+/// Unlike [`MemoryMmapFileMut`], the memory is allocated lazily by the kernel when it is
+/// first touched, and supports [`unchecked_advise`], e.g. [`UncheckedAdvice::DontNeed`] hands the pages back
+/// to the kernel. The mapping is page aligned, so [`truncate`] only remaps when the length grows
+/// beyond the mapped pages.
+///
+/// [`MemoryMmapFileMut`]: struct.MemoryMmapFileMut.html
+/// [`unchecked_advise`]: trait.MmapFileExt.html#method.unchecked_advise
+/// [`UncheckedAdvice::DontNeed`]: fmmap::UncheckedAdvice::DontNeed
+/// [`truncate`]: trait.MmapFileMutExt.html#tymethod.truncate
+pub struct AnonMmapFileMut {
+    map: AnonMap,
+    len: usize,
+    flags: AnonFlags,
+    exec: bool,
+    create_at: SystemTime,
+}
+
+impl_anon_mmap_file_ext!(AnonMmapFileMut);
+
+impl MmapFileMutExt for AnonMmapFileMut {
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.map.slice_mut(self.len)
+    }
+
+    #[inline]
+    fn is_cow(&self) -> bool {
+        false
+    }
+
+    noop_flush!();
+
+    /// Grows or shrinks the anonymous memory map, the new bytes are zeros.
+    ///
+    /// Growing within the mapped pages only changes the length, otherwise a larger region is
+    /// mapped (and locked in memory again if the map is locked by `Options::lock_in_memory`) and
+    /// the data is copied, so a shared mapping is no longer shared with the child processes forked
+    /// before. The whole pages cut off by shrinking are handed back to the kernel, unless the map
+    /// is locked in memory.
+    fn truncate(&mut self, max_sz: u64) -> Result<()> {
+        let new_len = max_sz as usize;
+        if new_len > self.map.cap {
+            let mut map = AnonMap::new(new_len, self.flags)?;
+            map.slice_mut(self.len).copy_from_slice(self.as_slice());
+            self.map = map;
+        } else if new_len < self.len {
+            self.map.zero(new_len, self.len, self.flags)?;
+        }
+        self.len = new_len;
+        Ok(())
+    }
+
+    #[inline]
+    fn drop_remove(self) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn close_with_truncate(self, _max_sz: i64) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl AnonMmapFileMut {
+    /// Create a private anonymous memory map of `len` zeroed bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::{MmapFileExt, MmapFileMutExt};
+    /// use fmmap::raw::AnonMmapFileMut;
+    ///
+    /// let mut file = AnonMmapFileMut::new(100).unwrap();
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// assert_eq!(file.bytes(0, 12).unwrap(), "some data...".as_bytes());
+    /// ```
+    pub fn new(len: usize) -> Result<Self> {
+        Self::with_options(len, Options::new())
+    }
+
+    /// Create an anonymous memory map of `len` zeroed bytes with [`Options`].
+    ///
+    /// Only [`Options::map_shared`], [`Options::no_reserve`], [`Options::huge_pages`]
+    /// and [`Options::lock_in_memory`] have effect on anonymous memory maps.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::{MmapFileExt, Options};
+    /// use fmmap::raw::AnonMmapFileMut;
+    ///
+    /// let file = AnonMmapFileMut::with_options(1 << 20, Options::new().no_reserve()).unwrap();
+    /// assert_eq!(file.len(), 1 << 20);
+    /// ```
+    ///
+    /// [`Options`]: fmmap::Options
+    /// [`Options::map_shared`]: fmmap::Options::map_shared
+    /// [`Options::no_reserve`]: fmmap::Options::no_reserve
+    /// [`Options::huge_pages`]: fmmap::Options::huge_pages
+    /// [`Options::lock_in_memory`]: fmmap::Options::lock_in_memory
+    pub fn with_options(len: usize, opts: Options) -> Result<Self> {
+        let flags = AnonFlags {
+            shared: opts.map_shared,
+            no_reserve: opts.no_reserve,
+            huge_pages: opts.huge_pages,
+            locked: opts.lock_in_memory,
+        };
+        Ok(Self {
+            map: AnonMap::new(len, flags)?,
+            len,
+            flags,
+            exec: false,
+            create_at: SystemTime::now(),
+        })
+    }
+
+    /// Returns whether the memory map is shared with the child processes (`MAP_SHARED`).
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        self.flags.shared
+    }
+
+    /// Make the anonymous memory map read-only (`mprotect`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::{MmapFileExt, MmapFileMutExt};
+    /// use fmmap::raw::AnonMmapFileMut;
+    ///
+    /// let mut file = AnonMmapFileMut::new(12).unwrap();
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// let file = file.freeze().unwrap();
+    /// assert_eq!(file.as_slice(), "some data...".as_bytes());
+    /// ```
+    pub fn freeze(self) -> Result<AnonMmapFile> {
+        self.protect(MprotectFlags::READ, false)
+    }
+
+    /// Transition the anonymous memory map to be readable and executable (`mprotect`).
+    pub fn freeze_exec(self) -> Result<AnonMmapFile> {
+        self.protect(MprotectFlags::READ | MprotectFlags::EXEC, true)
+    }
+
+    fn protect(self, flags: MprotectFlags, exec: bool) -> Result<AnonMmapFile> {
+        self.map.protect(flags)?;
+        Ok(AnonMmapFile {
+            map: self.map,
+            len: self.len,
+            flags: self.flags,
+            exec,
+            create_at: self.create_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Advice, MetaDataExt, UncheckedAdvice};
+
+    #[test]
+    fn test_anon_truncate() {
+        let page_size = crate::advice::page_size();
+        let mut file = AnonMmapFileMut::new(10).unwrap();
+        assert_eq!(file.len(), 10);
+        assert!(!file.is_shared());
+        file.write_all(&[1; 10], 0).unwrap();
+
+        // grow within the mapped page
+        file.truncate(100).unwrap();
+        assert_eq!(&file.as_slice()[..10], &[1; 10]);
+        assert_eq!(&file.as_slice()[10..], &[0; 90][..]);
+
+        // grow beyond the mapped page
+        let len = page_size * 3 + 10;
+        file.truncate(len as u64).unwrap();
+        assert_eq!(&file.as_slice()[..10], &[1; 10]);
+        file.as_mut_slice().fill(2);
+
+        // shrink, the cut off bytes read as zeros when growing again
+        file.truncate(5).unwrap();
+        assert_eq!(file.as_slice(), &[2; 5]);
+        file.truncate(len as u64).unwrap();
+        assert_eq!(&file.as_slice()[..5], &[2; 5]);
+        assert!(file.as_slice()[5..].iter().all(|b| *b == 0));
+
+        file.truncate(0).unwrap();
+        assert!(file.is_empty());
+    }
+
+    #[test]
+    fn test_anon_shared() {
+        let page_size = crate::advice::page_size();
+        let opts = Options::new().map_shared().no_reserve();
+        let mut file = AnonMmapFileMut::with_options(page_size * 2, opts).unwrap();
+        assert!(file.is_shared());
+        file.as_mut_slice().fill(1);
+        file.truncate(10).unwrap();
+        file.truncate((page_size * 2) as u64).unwrap();
+        assert_eq!(&file.as_slice()[..10], &[1; 10]);
+        assert!(file.as_slice()[10..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_anon_lock_in_memory_truncate() {
+        let page_size = crate::advice::page_size();
+        for opts in [Options::new(), Options::new().map_shared()] {
+            let mut file =
+                AnonMmapFileMut::with_options(page_size * 4, opts.lock_in_memory()).unwrap();
+            file.as_mut_slice().fill(1);
+
+            // the lock is applied again to the remapped region, which faults in the new pages
+            file.truncate((page_size * 64) as u64).unwrap();
+            assert_eq!(file.resident_pages().unwrap(), 64);
+
+            // the locked pages are zeroed instead of being dropped
+            file.truncate(10).unwrap();
+            assert_eq!(file.len(), 10);
+            file.truncate((page_size * 64) as u64).unwrap();
+            assert_eq!(&file.as_slice()[..10], &[1; 10]);
+            assert!(file.as_slice()[10..].iter().all(|b| *b == 0));
+        }
+    }
+
+    #[test]
+    fn test_anon_advise() {
+        let page_size = crate::advice::page_size();
+        let mut file = AnonMmapFileMut::new(page_size * 2).unwrap();
+        file.as_mut_slice().fill(1);
+        file.advise(Advice::Sequential).unwrap();
+        file.advise_range(Advice::WillNeed, 10, 20).unwrap();
+        file.advise_range(Advice::Random, page_size * 2, 1)
+            .unwrap_err();
+        assert_eq!(file.resident_pages().unwrap(), 2);
+
+        // the pages of a private anonymous mapping read as zeros after `DontNeed` on Linux
+        // Safety: there are no references into the mmap
+        unsafe { file.unchecked_advise_range(UncheckedAdvice::DontNeed, page_size, page_size) }
+            .unwrap();
+        #[cfg(target_os = "linux")]
+        assert!(file.as_slice()[page_size..].iter().all(|b| *b == 0));
+        assert!(file.as_slice()[..page_size].iter().all(|b| *b == 1));
+    }
+
+    #[test]
+    fn test_anon_freeze() {
+        let mut file = AnonMmapFileMut::new(12).unwrap();
+        file.write_all("some data...".as_bytes(), 0).unwrap();
+        let file = file.freeze().unwrap();
+        assert!(!file.is_exec());
+        assert_eq!(file.as_slice(), "some data...".as_bytes());
+        assert_eq!(file.path(), Path::new(""));
+        let meta = file.metadata().unwrap();
+        assert_eq!(meta.len(), 12);
+        assert!(meta.huge_pages().is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_anon_huge_pages() {
+        let opts = Options::new().huge_pages(HugePageSize::Transparent);
+        let mut file = AnonMmapFileMut::with_options(4 << 20, opts).unwrap();
+        file.as_mut_slice()[0] = 1;
+        assert_eq!(
+            file.metadata().unwrap().huge_pages(),
+            Some(HugePageSize::Transparent)
+        );
+    }
+}
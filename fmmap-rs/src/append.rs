@@ -0,0 +1,284 @@
+use crate::error::{Error, ErrorKind, Result};
+
+cfg_sync!(
+    mod sync_impl;
+    pub use sync_impl::AppendableMmapFile;
+);
+
+/// The default number of bytes an appendable mmap file grows by when the next append would overflow.
+pub(crate) const DEFAULT_GROW_SIZE: u64 = 4 << 20;
+
+/// Returns the new capacity for an appendable mmap file which needs at least `need` bytes,
+/// rounded up to a multiple of `grow_size`, or `None` if the rounded capacity overflows.
+#[inline]
+pub(crate) fn grow_to(need: u64, grow_size: u64) -> Option<u64> {
+    if grow_size == 0 {
+        return Some(need);
+    }
+    need.checked_add(grow_size - 1).map(|n| n / grow_size * grow_size)
+}
+
+/// Returns an error if the options map the file from a non-zero offset, the logical length
+/// of an appendable mmap file is relative to the start of the file.
+#[inline]
+pub(crate) fn check_offset(offset: u64) -> Result<()> {
+    if offset != 0 {
+        return Err(Error::new_with_message(
+            ErrorKind::MmapFailed,
+            "appendable mmap file does not support a non-zero offset",
+        ));
+    }
+    Ok(())
+}
+
+cfg_async! {
+    macro_rules! declare_and_impl_async_appendable_mmap_file {
+        ($filename_prefix: literal, $doc_test_runtime: literal, $path_str: literal) => {
+            /// AsyncAppendableMmapFile is an append-only mmap file backed by [`AsyncDiskMmapFileMut`].
+            ///
+            /// It keeps a write cursor, grows the underlying file in chunks of [`grow_size`] bytes
+            /// and remaps when the next append would overflow the mapped region.
+            ///
+            /// # Notes
+            /// The underlying file is larger than the logical length most of the time,
+            /// call [`close`] to truncate the file to the logical length.
+            ///
+            #[doc = concat!("[`AsyncDiskMmapFileMut`]: raw/", $path_str, "/struct.AsyncDiskMmapFileMut.html")]
+            #[doc = concat!("[`grow_size`]: ", $path_str, "/struct.AsyncAppendableMmapFile.html#method.grow_size")]
+            #[doc = concat!("[`close`]: ", $path_str, "/struct.AsyncAppendableMmapFile.html#method.close")]
+            pub struct AsyncAppendableMmapFile<R: AsyncRuntime> {
+                inner: AsyncDiskMmapFileMut<R>,
+                cursor: usize,
+                grow_size: u64,
+            }
+
+            impl<R: AsyncRuntime> AsyncAppendableMmapFile<R> {
+                /// Create a new file and mmap it for appending.
+                ///
+                /// # Example
+                ///
+                #[doc = "```ignore"]
+                #[doc = concat!("use fmmap::", $path_str, "::AsyncAppendableMmapFile;")]
+                #[doc = " # use scopeguard::defer;"]
+                #[doc = ""]
+                #[doc = concat!("# ", $doc_test_runtime, "::block_on(async {")]
+                #[doc = concat!("let mut file = AsyncAppendableMmapFile::create(\"", $filename_prefix, "_appendable_create_test.txt\").await.unwrap();")]
+                #[doc = concat!("# defer!(std::fs::remove_file(\"", $filename_prefix, "_appendable_create_test.txt\").unwrap());")]
+                #[doc = "assert_eq!(file.append(\"some data...\".as_bytes()).await.unwrap(), 0);"]
+                #[doc = "file.close().await.unwrap();"]
+                #[doc = "# })"]
+                #[doc = "```"]
+                pub async fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+                    AsyncDiskMmapFileMut::create(path).await.map(|inner| Self::new(inner, 0))
+                }
+
+                /// Create a new file with [`AsyncOptions`] and mmap it for appending.
+                ///
+                /// The `max_size` field of [`AsyncOptions`] is used as the initial capacity,
+                /// and the `offset` field must be 0.
+                ///
+                #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
+                pub async fn create_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self> {
+                    check_offset(opts.offset)?;
+                    AsyncDiskMmapFileMut::create_with_options(path, opts).await.map(|inner| Self::new(inner, 0))
+                }
+
+                /// Open an existing file and mmap it for appending,
+                /// the write cursor is placed at the end of the file.
+                pub async fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+                    AsyncDiskMmapFileMut::open_exist(path).await.map(|inner| {
+                        let cursor = inner.len();
+                        Self::new(inner, cursor)
+                    })
+                }
+
+                /// Open an existing file with [`AsyncOptions`] and mmap it for appending,
+                /// the write cursor is placed at the end of the mmap.
+                ///
+                /// The `offset` field of [`AsyncOptions`] must be 0.
+                ///
+                #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
+                pub async fn open_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self> {
+                    check_offset(opts.offset)?;
+                    AsyncDiskMmapFileMut::open_exist_with_options(path, opts).await.map(|inner| {
+                        let cursor = inner.len();
+                        Self::new(inner, cursor)
+                    })
+                }
+
+                fn new(inner: AsyncDiskMmapFileMut<R>, cursor: usize) -> Self {
+                    Self {
+                        inner,
+                        cursor,
+                        grow_size: DEFAULT_GROW_SIZE,
+                    }
+                }
+
+                /// Returns the number of bytes the file grows by when the next append would overflow.
+                #[inline]
+                pub fn grow_size(&self) -> u64 {
+                    self.grow_size
+                }
+
+                /// Set the number of bytes the file grows by when the next append would overflow.
+                /// If `grow_size` is 0, the file only grows by the exact number of bytes needed.
+                #[inline]
+                pub fn set_grow_size(&mut self, grow_size: u64) {
+                    self.grow_size = grow_size;
+                }
+
+                /// Returns the logical length (the write cursor) of the file.
+                #[inline]
+                pub fn len(&self) -> usize {
+                    self.cursor
+                }
+
+                /// Returns whether nothing has been appended.
+                #[inline]
+                pub fn is_empty(&self) -> bool {
+                    self.cursor == 0
+                }
+
+                /// Returns the size of the mmap, which is always greater than or equal to [`len`].
+                ///
+                #[doc = concat!("[`len`]: ", $path_str, "/struct.AsyncAppendableMmapFile.html#method.len")]
+                #[inline]
+                pub fn capacity(&self) -> usize {
+                    self.inner.len()
+                }
+
+                /// Move the write cursor, e.g. after recovering the logical length of a file
+                /// which was not closed properly.
+                pub fn set_len(&mut self, len: usize) -> Result<()> {
+                    let cap = self.capacity();
+                    if len > cap {
+                        return Err(Error::from(ErrorKind::OutOfBound(len, cap)));
+                    }
+                    self.cursor = len;
+                    Ok(())
+                }
+
+                /// Returns the path of the inner file.
+                #[inline]
+                pub fn path(&self) -> &Path {
+                    self.inner.path()
+                }
+
+                /// Returns the appended data.
+                #[inline]
+                pub fn as_slice(&self) -> &[u8] {
+                    &self.inner.as_slice()[..self.cursor]
+                }
+
+                /// Make sure at least `additional` bytes can be appended without remapping.
+                pub async fn reserve(&mut self, additional: usize) -> Result<()> {
+                    let cap = self.capacity();
+                    let need = self
+                        .cursor
+                        .checked_add(additional)
+                        .ok_or_else(|| Error::from(ErrorKind::OutOfBound(usize::MAX, cap)))?;
+                    if need <= cap {
+                        return Ok(());
+                    }
+                    let new_cap = grow_to(need as u64, self.grow_size)
+                        .ok_or_else(|| Error::from(ErrorKind::OutOfBound(usize::MAX, cap)))?;
+                    self.inner.truncate(new_cap).await
+                }
+
+                /// Append `src` at the write cursor, grow the file if needed.
+                /// Returns the offset at which `src` was written.
+                pub async fn append(&mut self, src: &[u8]) -> Result<usize> {
+                    self.reserve(src.len()).await?;
+                    let offset = self.cursor;
+                    self.inner.write_all(src, offset)?;
+                    self.cursor += src.len();
+                    Ok(offset)
+                }
+
+                /// Flushes outstanding memory map modifications to disk.
+                #[inline]
+                pub fn flush(&self) -> Result<()> {
+                    self.inner.flush()
+                }
+
+                /// Flush the appended data, truncate the file to the logical length and close it.
+                pub async fn close(self) -> Result<()> {
+                    let cursor = self.cursor as i64;
+                    self.inner.close_with_truncate(cursor).await
+                }
+            }
+        };
+    }
+
+    macro_rules! test_async_appendable_mmap_file {
+        ($filename_prefix: literal, $runtime: ident, $attr: meta) => {
+            #[cfg(test)]
+            mod tests {
+                use crate::error::ErrorKind;
+                use crate::$runtime::{AsyncAppendableMmapFile, AsyncOptions};
+                use scopeguard::defer;
+
+                #[$attr]
+                async fn test_async_appendable() {
+                    let path = concat!($filename_prefix, "_appendable_test.txt");
+                    let mut file = AsyncAppendableMmapFile::create(path).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap());
+                    file.set_grow_size(16);
+                    assert!(file.is_empty());
+                    assert_eq!(file.append(b"hello, ").await.unwrap(), 0);
+                    assert_eq!(file.capacity(), 16);
+                    assert_eq!(file.append(b"appendable mmap file").await.unwrap(), 7);
+                    assert_eq!(file.len(), 27);
+                    assert_eq!(file.capacity(), 32);
+                    assert_eq!(file.as_slice(), b"hello, appendable mmap file");
+                    file.set_len(33).unwrap_err();
+                    file.flush().unwrap();
+                    file.close().await.unwrap();
+                    assert_eq!(std::fs::metadata(path).unwrap().len(), 27);
+
+                    let mut file = AsyncAppendableMmapFile::open(path).await.unwrap();
+                    assert_eq!(file.len(), 27);
+                    assert_eq!(file.append(b"!").await.unwrap(), 27);
+                    file.close().await.unwrap();
+                    assert_eq!(std::fs::read(path).unwrap(), b"hello, appendable mmap file!");
+                }
+
+                #[$attr]
+                async fn test_async_appendable_offset() {
+                    let path = concat!($filename_prefix, "_appendable_offset_test.txt");
+                    let opts = AsyncOptions::new().max_size(100).offset(4096);
+                    let err = AsyncAppendableMmapFile::create_with_options(path, opts).await.err().unwrap();
+                    assert_eq!(err.kind(), ErrorKind::MmapFailed);
+                    assert!(!std::path::Path::new(path).exists());
+
+                    std::fs::write(path, [0; 8192]).unwrap();
+                    defer!(std::fs::remove_file(path).unwrap());
+                    let opts = AsyncOptions::new().offset(4096);
+                    let err = AsyncAppendableMmapFile::open_with_options(path, opts).await.err().unwrap();
+                    assert_eq!(err.kind(), ErrorKind::MmapFailed);
+                    assert_eq!(std::fs::metadata(path).unwrap().len(), 8192);
+                }
+            }
+        };
+    }
+}
+
+cfg_async!(
+    mod async_impl;
+    pub use async_impl::AsyncAppendableMmapFile;
+);
+
+cfg_async_std!(
+    #[cfg(test)]
+    mod async_std_impl;
+);
+
+cfg_smol!(
+    #[cfg(test)]
+    mod smol_impl;
+);
+
+cfg_tokio!(
+    #[cfg(test)]
+    mod tokio_impl;
+);
@@ -0,0 +1,7 @@
+use super::{check_offset, grow_to, DEFAULT_GROW_SIZE};
+use crate::disk::AsyncDiskMmapFileMut;
+use crate::error::{Error, ErrorKind, Result};
+use crate::{AsyncMmapFileExt, AsyncMmapFileMutExt, AsyncOptions, AsyncRuntime};
+use std::path::Path;
+
+declare_and_impl_async_appendable_mmap_file!("tokio_async", "tokio_test", "tokio");
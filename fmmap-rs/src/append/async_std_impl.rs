@@ -0,0 +1 @@
+test_async_appendable_mmap_file!("async_std_async", async_std, async_std::test);
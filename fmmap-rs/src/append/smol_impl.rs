@@ -0,0 +1 @@
+test_async_appendable_mmap_file!("smol_async", smol, smol_potat::test);
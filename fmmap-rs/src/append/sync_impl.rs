@@ -0,0 +1,306 @@
+use crate::append::{check_offset, grow_to, DEFAULT_GROW_SIZE};
+use crate::disk::DiskMmapFileMut;
+use crate::error::{Error, ErrorKind, Result};
+use crate::options::Options;
+use crate::{MmapFileExt, MmapFileMutExt};
+use std::path::Path;
+
+/// AppendableMmapFile is an append-only mmap file backed by [`DiskMmapFileMut`].
+///
+/// It keeps a write cursor, grows the underlying file in chunks of [`grow_size`] bytes
+/// and remaps when the next append would overflow the mapped region.
+///
+/// # Notes
+/// The underlying file is larger than the logical length most of the time,
+/// call [`close`] to truncate the file to the logical length.
+///
+/// [`DiskMmapFileMut`]: raw/struct.DiskMmapFileMut.html
+/// [`grow_size`]: struct.AppendableMmapFile.html#method.grow_size
+/// [`close`]: struct.AppendableMmapFile.html#method.close
+pub struct AppendableMmapFile {
+    inner: DiskMmapFileMut,
+    cursor: usize,
+    grow_size: u64,
+}
+
+impl AppendableMmapFile {
+    /// Create a new file and mmap it for appending.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::AppendableMmapFile;
+    /// # use scopeguard::defer;
+    ///
+    /// let mut file = AppendableMmapFile::create("appendable_create_test.txt").unwrap();
+    /// # defer!(std::fs::remove_file("appendable_create_test.txt").unwrap());
+    /// assert_eq!(file.append("some data...".as_bytes()).unwrap(), 0);
+    /// file.close().unwrap();
+    /// ```
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        DiskMmapFileMut::create(path).map(|inner| Self::new(inner, 0))
+    }
+
+    /// Create a new file with [`Options`] and mmap it for appending.
+    ///
+    /// The `max_size` field of [`Options`] is used as the initial capacity,
+    /// and the `offset` field must be 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::{AppendableMmapFile, Options};
+    /// # use scopeguard::defer;
+    ///
+    /// let opts = Options::new()
+    ///     // preallocate 100 bytes
+    ///     .max_size(100);
+    /// let mut file = AppendableMmapFile::create_with_options("appendable_create_with_options_test.txt", opts).unwrap();
+    /// # defer!(std::fs::remove_file("appendable_create_with_options_test.txt").unwrap());
+    /// assert_eq!(file.capacity(), 100);
+    /// file.append("some data...".as_bytes()).unwrap();
+    /// file.close().unwrap();
+    /// ```
+    ///
+    /// [`Options`]: struct.Options.html
+    pub fn create_with_options<P: AsRef<Path>>(path: P, opts: Options) -> Result<Self> {
+        check_offset(opts.offset)?;
+        DiskMmapFileMut::create_with_options(path, opts).map(|inner| Self::new(inner, 0))
+    }
+
+    /// Open an existing file and mmap it for appending,
+    /// the write cursor is placed at the end of the file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::AppendableMmapFile;
+    /// # use scopeguard::defer;
+    ///
+    /// # std::fs::write("appendable_open_test.txt", "some data...").unwrap();
+    /// # defer!(std::fs::remove_file("appendable_open_test.txt").unwrap());
+    /// let mut file = AppendableMmapFile::open("appendable_open_test.txt").unwrap();
+    /// assert_eq!(file.append("more data...".as_bytes()).unwrap(), "some data...".len());
+    /// file.close().unwrap();
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        DiskMmapFileMut::open_exist(path).map(|inner| {
+            let cursor = inner.len();
+            Self::new(inner, cursor)
+        })
+    }
+
+    /// Open an existing file with [`Options`] and mmap it for appending,
+    /// the write cursor is placed at the end of the mmap.
+    ///
+    /// The `offset` field of [`Options`] must be 0.
+    ///
+    /// [`Options`]: struct.Options.html
+    pub fn open_with_options<P: AsRef<Path>>(path: P, opts: Options) -> Result<Self> {
+        check_offset(opts.offset)?;
+        DiskMmapFileMut::open_exist_with_options(path, opts).map(|inner| {
+            let cursor = inner.len();
+            Self::new(inner, cursor)
+        })
+    }
+
+    fn new(inner: DiskMmapFileMut, cursor: usize) -> Self {
+        Self {
+            inner,
+            cursor,
+            grow_size: DEFAULT_GROW_SIZE,
+        }
+    }
+
+    /// Returns the number of bytes the file grows by when the next append would overflow.
+    #[inline]
+    pub fn grow_size(&self) -> u64 {
+        self.grow_size
+    }
+
+    /// Set the number of bytes the file grows by when the next append would overflow.
+    /// If `grow_size` is 0, the file only grows by the exact number of bytes needed.
+    #[inline]
+    pub fn set_grow_size(&mut self, grow_size: u64) {
+        self.grow_size = grow_size;
+    }
+
+    /// Returns the logical length (the write cursor) of the file.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns whether nothing has been appended.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cursor == 0
+    }
+
+    /// Returns the size of the mmap, which is always greater than or equal to [`len`].
+    ///
+    /// [`len`]: struct.AppendableMmapFile.html#method.len
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Move the write cursor, e.g. after recovering the logical length of a file
+    /// which was not closed properly.
+    pub fn set_len(&mut self, len: usize) -> Result<()> {
+        let cap = self.capacity();
+        if len > cap {
+            return Err(Error::from(ErrorKind::OutOfBound(len, cap)));
+        }
+        self.cursor = len;
+        Ok(())
+    }
+
+    /// Returns the path of the inner file.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        self.inner.path()
+    }
+
+    /// Returns the appended data.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.inner.as_slice()[..self.cursor]
+    }
+
+    /// Make sure at least `additional` bytes can be appended without remapping.
+    pub fn reserve(&mut self, additional: usize) -> Result<()> {
+        let cap = self.capacity();
+        let need = self
+            .cursor
+            .checked_add(additional)
+            .ok_or_else(|| Error::from(ErrorKind::OutOfBound(usize::MAX, cap)))?;
+        if need <= cap {
+            return Ok(());
+        }
+        let new_cap = grow_to(need as u64, self.grow_size)
+            .ok_or_else(|| Error::from(ErrorKind::OutOfBound(usize::MAX, cap)))?;
+        self.inner.truncate(new_cap)
+    }
+
+    /// Append `src` at the write cursor, grow the file if needed.
+    /// Returns the offset at which `src` was written.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::AppendableMmapFile;
+    /// # use scopeguard::defer;
+    ///
+    /// let mut file = AppendableMmapFile::create("appendable_append_test.txt").unwrap();
+    /// # defer!(std::fs::remove_file("appendable_append_test.txt").unwrap());
+    /// file.set_grow_size(16);
+    /// assert_eq!(file.append("some data...".as_bytes()).unwrap(), 0);
+    /// assert_eq!(file.append("more data...".as_bytes()).unwrap(), 12);
+    /// assert_eq!(file.capacity(), 32);
+    /// file.close().unwrap();
+    /// assert_eq!(std::fs::metadata("appendable_append_test.txt").unwrap().len(), 24);
+    /// ```
+    pub fn append(&mut self, src: &[u8]) -> Result<usize> {
+        self.reserve(src.len())?;
+        let offset = self.cursor;
+        self.inner.write_all(src, offset)?;
+        self.cursor += src.len();
+        Ok(offset)
+    }
+
+    /// Flushes outstanding memory map modifications to disk.
+    #[inline]
+    pub fn flush(&self) -> Result<()> {
+        self.inner.flush()
+    }
+
+    /// Flush the appended data, truncate the file to the logical length and close it.
+    pub fn close(self) -> Result<()> {
+        let cursor = self.cursor as i64;
+        self.inner.close_with_truncate(cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scopeguard::defer;
+
+    #[test]
+    fn test_appendable() {
+        let path = "appendable_test.txt";
+        let mut file = AppendableMmapFile::create(path).unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        file.set_grow_size(16);
+        assert!(file.is_empty());
+        assert_eq!(file.append(b"hello, ").unwrap(), 0);
+        assert_eq!(file.capacity(), 16);
+        assert_eq!(file.append(b"appendable mmap file").unwrap(), 7);
+        assert_eq!(file.len(), 27);
+        assert_eq!(file.capacity(), 32);
+        assert_eq!(file.as_slice(), b"hello, appendable mmap file");
+        file.set_len(33).unwrap_err();
+        file.flush().unwrap();
+        file.close().unwrap();
+        assert_eq!(std::fs::metadata(path).unwrap().len(), 27);
+
+        let mut file = AppendableMmapFile::open(path).unwrap();
+        assert_eq!(file.len(), 27);
+        assert_eq!(file.append(b"!").unwrap(), 27);
+        file.close().unwrap();
+        assert_eq!(std::fs::read(path).unwrap(), b"hello, appendable mmap file!");
+    }
+
+    #[test]
+    fn test_appendable_exact_growth() {
+        let path = "appendable_exact_growth_test.txt";
+        let mut file = AppendableMmapFile::create(path).unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        file.set_grow_size(0);
+        file.append(&[1; 10]).unwrap();
+        assert_eq!(file.capacity(), 10);
+        file.reserve(5).unwrap();
+        assert_eq!(file.capacity(), 15);
+        assert_eq!(file.len(), 10);
+        file.close().unwrap();
+        assert_eq!(std::fs::metadata(path).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn test_appendable_grow_overflow() {
+        let path = "appendable_grow_overflow_test.txt";
+        let mut file = AppendableMmapFile::create(path).unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        file.set_grow_size(0);
+        file.append(&[1]).unwrap();
+        file.set_grow_size(u64::MAX);
+        assert_eq!(
+            file.append(&[2]).unwrap_err().kind(),
+            ErrorKind::OutOfBound(usize::MAX, 1)
+        );
+        assert_eq!(file.as_slice(), &[1]);
+        file.close().unwrap();
+        assert_eq!(std::fs::read(path).unwrap(), &[1]);
+    }
+
+    #[test]
+    fn test_appendable_offset() {
+        let path = "appendable_offset_test.txt";
+        let opts = Options::new().max_size(100).offset(4096);
+        assert_eq!(
+            AppendableMmapFile::create_with_options(path, opts).err().unwrap().kind(),
+            ErrorKind::MmapFailed
+        );
+        assert!(!std::path::Path::new(path).exists());
+
+        std::fs::write(path, [0; 8192]).unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        let opts = Options::new().offset(4096);
+        assert_eq!(
+            AppendableMmapFile::open_with_options(path, opts).err().unwrap().kind(),
+            ErrorKind::MmapFailed
+        );
+        assert_eq!(std::fs::metadata(path).unwrap().len(), 8192);
+    }
+}
@@ -0,0 +1 @@
+test_async_appendable_mmap_file!("tokio_async", tokio, tokio::test);
@@ -0,0 +1,97 @@
+cfg_async! {
+    use crate::error::{Error, ErrorKind, Result};
+    use std::ops::{Bound, RangeBounds};
+
+    /// Returns the `(begin, end)` of the range, which must be in `[0, upper_bound]`.
+    pub(crate) fn handle_range<R: RangeBounds<usize>>(range: R, upper_bound: usize) -> Result<(usize, usize)> {
+        let begin = match range.start_bound() {
+            Bound::Included(&n) => Some(n),
+            Bound::Excluded(&n) => n.checked_add(1),
+            Bound::Unbounded => Some(0),
+        }
+        .ok_or_else(|| Error::from(ErrorKind::OutOfBound(usize::MAX, upper_bound)))?;
+
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1),
+            Bound::Excluded(&n) => Some(n),
+            Bound::Unbounded => Some(upper_bound),
+        }
+        .ok_or_else(|| Error::from(ErrorKind::OutOfBound(usize::MAX, upper_bound)))?;
+
+        if begin > end {
+            return Err(Error::from(ErrorKind::InvalidBound(begin, end)));
+        }
+
+        if end > upper_bound {
+            return Err(Error::from(ErrorKind::OutOfBound(end, upper_bound)));
+        }
+
+        Ok((begin, end))
+    }
+
+    macro_rules! test_async_mmap_file_chunks {
+        ($filename_prefix: literal, $runtime: ident, $attr: meta) => {
+            #[cfg(test)]
+            mod tests {
+                use crate::error::ErrorKind;
+                use crate::$runtime::{AsyncMmapFile, AsyncMmapFileMut, AsyncMmapFileMutExt, AsyncOptions};
+                use futures_core::Stream;
+                use futures_util::StreamExt;
+                use scopeguard::defer;
+
+                #[$attr]
+                async fn test_async_mmap_file_chunks() {
+                    let path = concat!($filename_prefix, "_chunks_test.txt");
+                    let mut file = AsyncMmapFileMut::create_with_options(path, AsyncOptions::new().max_size(100)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap());
+                    let data: Vec<u8> = (0..100).collect();
+                    file.write_all(&data, 0).unwrap();
+                    let file = file.freeze().unwrap();
+
+                    let chunks = file.chunks(10..=50, 16).unwrap().prefetch(true);
+                    let _ = format!("{:?}", chunks);
+                    assert_eq!(chunks.size_hint(), (3, Some(3)));
+                    assert_eq!(chunks.remaining(), 41);
+                    let shared = chunks.get_ref().clone();
+                    let chunks: Vec<_> = chunks.map(|chunk| chunk.unwrap()).collect().await;
+                    assert_eq!(chunks.iter().map(|c| c.len()).collect::<Vec<_>>(), vec![16, 16, 9]);
+                    assert_eq!(chunks.concat(), &data[10..51]);
+                    // the chunks keep the mapping alive
+                    assert_eq!(shared.handle_count(), 4);
+                    drop(shared);
+                    assert_eq!(chunks[2].as_ref(), &data[42..51]);
+
+                    let file = AsyncMmapFile::memory_from_vec("test.mem", data.clone());
+                    assert_eq!(file.chunks(.., 100).unwrap().count().await, 1);
+                    let file = AsyncMmapFile::memory_from_vec("test.mem", data.clone());
+                    let chunks = file.chunks(.., usize::MAX).unwrap();
+                    assert_eq!(chunks.size_hint(), (1, Some(1)));
+                    assert_eq!(chunks.count().await, 1);
+                    let file = AsyncMmapFile::memory_from_vec("test.mem", data);
+                    assert_eq!(file.chunks(100.., 1).unwrap().count().await, 0);
+                    let file = AsyncMmapFile::memory_from_vec("test.mem", vec![0; 100]);
+                    let err = file.chunks(50..101, 10).unwrap_err();
+                    assert_eq!(err.kind(), ErrorKind::OutOfBound(101, 100));
+                }
+            }
+        };
+    }
+
+    mod async_impl;
+    pub use async_impl::AsyncMmapFileChunks;
+}
+
+cfg_async_std!(
+    #[cfg(test)]
+    mod async_std_impl;
+);
+
+cfg_smol!(
+    #[cfg(test)]
+    mod smol_impl;
+);
+
+cfg_tokio!(
+    #[cfg(test)]
+    mod tokio_impl;
+);
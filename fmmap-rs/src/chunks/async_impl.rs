@@ -0,0 +1,164 @@
+use super::handle_range;
+use crate::advice::Advice;
+use crate::error::Result;
+use crate::{AsyncMmapFile, AsyncMmapFileExt, AsyncRuntime, AsyncSharedMmapFile};
+use bytes::Bytes;
+use futures_core::Stream;
+use std::fmt::{Debug, Formatter};
+use std::ops::RangeBounds;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// AsyncMmapFileChunks is a [`Stream`] which yields the data of a range of the mmap file
+/// as [`Bytes`] chunks without copying, see [`AsyncMmapFile::chunks`] and [`AsyncSharedMmapFile::chunks`].
+///
+/// Every chunk keeps the mapping alive, so the chunks can outlive the stream,
+/// e.g. be sent as the body of a HTTP response.
+///
+/// [`Stream`]: https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html
+/// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
+/// [`AsyncMmapFile::chunks`]: struct.AsyncMmapFile.html#method.chunks
+/// [`AsyncSharedMmapFile::chunks`]: struct.AsyncSharedMmapFile.html#method.chunks
+pub struct AsyncMmapFileChunks<R: AsyncRuntime> {
+    file: AsyncSharedMmapFile<R>,
+    pos: usize,
+    end: usize,
+    chunk_size: usize,
+    prefetch: bool,
+}
+
+impl<R: AsyncRuntime> AsyncMmapFileChunks<R> {
+    pub(crate) fn new(file: AsyncSharedMmapFile<R>, begin: usize, end: usize, chunk_size: usize) -> Self {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        Self {
+            file,
+            pos: begin,
+            end,
+            chunk_size,
+            prefetch: false,
+        }
+    }
+
+    /// Whether advise the kernel to read ahead the next chunk when a chunk is yielded,
+    /// see [`Advice::WillNeed`]. Default is false.
+    ///
+    /// [`Advice::WillNeed`]: ../enum.Advice.html#variant.WillNeed
+    #[inline]
+    pub fn prefetch(mut self, val: bool) -> Self {
+        self.prefetch = val;
+        self
+    }
+
+    /// Returns the start offset(related to the mmap) of the remaining chunks
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the length of the remaining data
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.end - self.pos
+    }
+
+    /// Returns the underlying shared mmap file.
+    #[inline]
+    pub fn get_ref(&self) -> &AsyncSharedMmapFile<R> {
+        &self.file
+    }
+}
+
+impl<R: AsyncRuntime> AsyncSharedMmapFile<R> {
+    /// Returns an [`AsyncMmapFileChunks`] which yields the data of `range` as [`Bytes`] chunks
+    /// of `chunk_size` bytes without copying, the last chunk may be shorter.
+    ///
+    /// # Errors
+    /// If the range is out of the bound of the mmap, it would return
+    ///  `Err(Error::from(ErrorKind::OutOfBound(end, len)))`.
+    ///
+    /// # Panics
+    /// If `chunk_size` is 0.
+    ///
+    /// [`AsyncMmapFileChunks`]: struct.AsyncMmapFileChunks.html
+    /// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
+    pub fn chunks<B: RangeBounds<usize>>(&self, range: B, chunk_size: usize) -> Result<AsyncMmapFileChunks<R>> {
+        let (begin, end) = handle_range(range, self.len())?;
+        Ok(AsyncMmapFileChunks::new(self.clone(), begin, end, chunk_size))
+    }
+}
+
+impl<R: AsyncRuntime> AsyncMmapFile<R> {
+    /// Consumes the mmap file, returns an [`AsyncMmapFileChunks`] which yields the data of `range`
+    /// as [`Bytes`] chunks of `chunk_size` bytes without copying, the last chunk may be shorter.
+    ///
+    /// The chunks keep the mapping alive, use [`AsyncSharedMmapFile::chunks`] to stream
+    /// a mmap file which is still used elsewhere.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use fmmap::tokio::{AsyncMmapFile, AsyncMmapFileExt};
+    /// use futures_util::StreamExt;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let file = AsyncMmapFile::memory_from_str("test.mem", "some data...");
+    /// let mut chunks = file.chunks(5.., 4).unwrap().prefetch(true);
+    /// assert_eq!(chunks.next().await.unwrap().unwrap().as_ref(), "data".as_bytes());
+    /// assert_eq!(chunks.next().await.unwrap().unwrap().as_ref(), "...".as_bytes());
+    /// assert!(chunks.next().await.is_none());
+    /// # })
+    /// ```
+    ///
+    /// # Errors
+    /// If the range is out of the bound of the mmap, it would return
+    ///  `Err(Error::from(ErrorKind::OutOfBound(end, len)))`.
+    ///
+    /// # Panics
+    /// If `chunk_size` is 0.
+    ///
+    /// [`AsyncMmapFileChunks`]: struct.AsyncMmapFileChunks.html
+    /// [`AsyncSharedMmapFile::chunks`]: struct.AsyncSharedMmapFile.html#method.chunks
+    /// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
+    pub fn chunks<B: RangeBounds<usize>>(self, range: B, chunk_size: usize) -> Result<AsyncMmapFileChunks<R>> {
+        AsyncSharedMmapFile::new(self).chunks(range, chunk_size)
+    }
+}
+
+impl<R: AsyncRuntime> Debug for AsyncMmapFileChunks<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncMmapFileChunks")
+            .field("path", &self.file.path())
+            .field("pos", &self.pos)
+            .field("end", &self.end)
+            .field("chunk_size", &self.chunk_size)
+            .field("prefetch", &self.prefetch)
+            .finish()
+    }
+}
+
+impl<R: AsyncRuntime> Stream for AsyncMmapFileChunks<R> {
+    type Item = Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.pos >= this.end {
+            return Poll::Ready(None);
+        }
+
+        let len = this.chunk_size.min(this.end - this.pos);
+        let chunk = this.file.slice_owned(this.pos, len);
+        this.pos += len;
+        if this.prefetch && this.pos < this.end {
+            // best-effort, the chunk is still readable if the advice fails
+            let _ = this.file.advise_range(Advice::WillNeed, this.pos, this.chunk_size.min(this.end - this.pos));
+        }
+        Poll::Ready(Some(chunk))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // `usize::div_ceil` is not available on the msrv
+        let remaining = self.end - self.pos;
+        let n = remaining / self.chunk_size + usize::from(remaining % self.chunk_size != 0);
+        (n, Some(n))
+    }
+}
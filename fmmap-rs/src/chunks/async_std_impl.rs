@@ -0,0 +1 @@
+test_async_mmap_file_chunks!("async_std_async", async_std, async_std::test);
@@ -0,0 +1 @@
+test_async_mmap_file_chunks!("smol_async", smol, smol_potat::test);
@@ -0,0 +1 @@
+test_async_mmap_file_chunks!("tokio_async", tokio, tokio::test);
@@ -0,0 +1,104 @@
+use crate::error::{Error, ErrorKind, Result};
+use rustix::fs::{Mode, OFlags};
+use std::fs::File;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd};
+use std::path::{Path, PathBuf};
+
+/// An opened directory, the mmap files can be opened and created relative to it (`openat`),
+/// which is race-free even if the directory is renamed, and works when only a directory
+/// handle is available, e.g. in a sandbox.
+///
+/// See [`Options::open_mmap_file_at`] and [`Options::create_mmap_file_mut_at`].
+///
+/// [`Options::open_mmap_file_at`]: struct.Options.html#method.open_mmap_file_at
+/// [`Options::create_mmap_file_mut_at`]: struct.Options.html#method.create_mmap_file_mut_at
+#[derive(Debug)]
+pub struct Dir {
+    fd: OwnedFd,
+    path: Option<PathBuf>,
+}
+
+impl Dir {
+    /// Open the directory at `path`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::Dir;
+    ///
+    /// let dir = Dir::open(".").unwrap();
+    /// dir.sync_all().unwrap();
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let fd = rustix::fs::open(
+            path,
+            OFlags::RDONLY | OFlags::DIRECTORY | OFlags::CLOEXEC,
+            Mode::empty(),
+        )
+        .map_err(|e| Error::new_source_msg(ErrorKind::OpenDirFailed, path.to_string_lossy(), e))?;
+        Ok(Self {
+            fd,
+            path: Some(path.to_path_buf()),
+        })
+    }
+
+    /// Wraps an opened directory `fd`, e.g. a directory handle received over a unix socket.
+    ///
+    /// The path of the directory is unknown, so the files opened relative to it have
+    /// no path, see [`MmapFileExt::file_path`].
+    ///
+    /// [`MmapFileExt::file_path`]: trait.MmapFileExt.html#method.file_path
+    pub fn from_fd(fd: OwnedFd) -> Self {
+        Self { fd, path: None }
+    }
+
+    /// Returns the path of the directory, or `None` if it is created by [`Dir::from_fd`].
+    ///
+    /// [`Dir::from_fd`]: struct.Dir.html#method.from_fd
+    #[inline]
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Sync the directory, which makes the created, linked or removed entries durable.
+    pub fn sync_all(&self) -> Result<()> {
+        rustix::fs::fsync(&self.fd).map_err(|e| Error::new(ErrorKind::SyncDirFailed, e))
+    }
+
+    /// Open the file at `path` relative to the directory with `flags`, the created file has mode `0o666`.
+    pub(crate) fn open_file_at(&self, path: &Path, flags: OFlags) -> Result<File> {
+        rustix::fs::openat(
+            &self.fd,
+            path,
+            flags | OFlags::CLOEXEC,
+            Mode::from_bits_truncate(0o666),
+        )
+        .map(File::from)
+        .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))
+    }
+
+    /// Returns the path of `path` relative to the directory, or `None` if the path of the directory is unknown.
+    #[inline]
+    pub(crate) fn path_hint(&self, path: &Path) -> Option<PathBuf> {
+        self.path.as_ref().map(|dir| dir.join(path))
+    }
+}
+
+impl AsFd for Dir {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for Dir {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl From<OwnedFd> for Dir {
+    fn from(fd: OwnedFd) -> Self {
+        Self::from_fd(fd)
+    }
+}
@@ -35,6 +35,80 @@ macro_rules! remmap {
     };
 }
 
+/// Creates a writable memory map of `file`.
+///
+/// If `reserve` is non-zero, `reserve` bytes of address space are reserved and only the first
+/// `len` bytes are committed, the returned `Some(len)` is the committed length.
+#[cfg(target_os = "linux")]
+fn map_mut_with_reserve<T: std::os::unix::io::AsRawFd>(
+    file: &T,
+    opts: &memmapix::MmapOptions,
+    reserve: usize,
+    len: u64,
+) -> std::io::Result<(memmapix::MmapMut, Option<usize>)> {
+    if reserve == 0 {
+        return unsafe { opts.map_mut(file) }.map(|mmap| (mmap, None));
+    }
+
+    let len = len as usize;
+    let cap = reserve.max(len);
+    unsafe {
+        let mut mmap = memmapix::MmapOptions::new().len(cap).map_mut(file)?;
+        commit_reserved(&mut mmap, file, cap, len)?;
+        Ok((mmap, Some(len)))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn map_mut_with_reserve<T: memmapix::MmapAsRawDesc>(
+    file: T,
+    opts: &memmapix::MmapOptions,
+    _reserve: usize,
+    _len: u64,
+) -> std::io::Result<(memmapix::MmapMut, Option<usize>)> {
+    unsafe { opts.map_mut(file) }.map(|mmap| (mmap, None))
+}
+
+/// Moves the boundary between the file-backed part and the `PROT_NONE` reservation of a
+/// reserved memory map from `old_len` to `new_len`, without moving the mapping.
+///
+/// # Safety
+/// `new_len` must not be greater than the length of `mmap`, and the file must be at least
+/// `new_len` bytes long.
+#[cfg(target_os = "linux")]
+unsafe fn commit_reserved<T: std::os::unix::io::AsRawFd>(
+    mmap: &mut memmapix::MmapMut,
+    file: &T,
+    old_len: usize,
+    new_len: usize,
+) -> std::io::Result<()> {
+    use rustix::mm::{MapFlags, ProtFlags};
+    use std::os::unix::io::BorrowedFd;
+
+    let page_size = rustix::param::page_size();
+    let old_end = (old_len + page_size - 1) / page_size * page_size;
+    let new_end = (new_len + page_size - 1) / page_size * page_size;
+    let base = mmap.as_mut_ptr();
+    if new_end > old_end {
+        rustix::mm::mmap(
+            base.add(old_end).cast(),
+            new_end - old_end,
+            ProtFlags::READ | ProtFlags::WRITE,
+            MapFlags::SHARED | MapFlags::FIXED,
+            BorrowedFd::borrow_raw(file.as_raw_fd()),
+            old_end as u64,
+        )?;
+    } else if new_end < old_end {
+        rustix::mm::mmap_anonymous(
+            base.add(new_end).cast(),
+            old_end - new_end,
+            ProtFlags::empty(),
+            MapFlags::PRIVATE | MapFlags::FIXED | MapFlags::NORESERVE,
+        )?;
+    }
+    Ok(())
+}
+
 macro_rules! impl_flush {
     () => {
         fn flush(&self) -> crate::error::Result<()> {
@@ -65,67 +139,303 @@ macro_rules! impl_flush {
 
 macro_rules! impl_file_lock {
     () => {
+        impl_file_lock!(core::convert::identity);
+    };
+    ($as_file: path) => {
         #[inline]
         fn lock_exclusive(&self) -> crate::error::Result<()> {
-            self.file
+            $as_file(&self.file)
                 .lock_exclusive()
                 .map_err(|e| Error::new(ErrorKind::IO, e))
         }
 
         #[inline]
         fn lock_shared(&self) -> crate::error::Result<()> {
-            self.file
+            $as_file(&self.file)
                 .lock_shared()
                 .map_err(|e| Error::new(ErrorKind::IO, e))
         }
 
         #[inline]
         fn try_lock_exclusive(&self) -> crate::error::Result<()> {
-            self.file
+            $as_file(&self.file)
                 .try_lock_exclusive()
                 .map_err(|e| Error::new(ErrorKind::IO, e))
         }
 
         #[inline]
         fn try_lock_shared(&self) -> crate::error::Result<()> {
-            self.file
+            $as_file(&self.file)
                 .try_lock_shared()
                 .map_err(|e| Error::new(ErrorKind::IO, e))
         }
 
         #[inline]
         fn unlock(&self) -> crate::error::Result<()> {
-            self.file.unlock().map_err(|e| Error::new(ErrorKind::IO, e))
+            $as_file(&self.file)
+                .unlock()
+                .map_err(|e| Error::new(ErrorKind::IO, e))
         }
     };
 }
 
-cfg_sync! {
-    macro_rules! impl_mmap_file_ext_base {
-        () => {
-            fn len(&self) -> usize {
-                self.mmap.len()
+macro_rules! impl_range_lock {
+    () => {
+        #[inline]
+        fn lock_range_shared(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            crate::range_lock::lock_range_guard(
+                &self.file,
+                crate::range_lock::RangeLockType::Shared,
+                offset,
+                len,
+                true,
+            )
+        }
+
+        #[inline]
+        fn lock_range_exclusive(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            crate::range_lock::lock_range_guard(
+                &self.file,
+                crate::range_lock::RangeLockType::Exclusive,
+                offset,
+                len,
+                true,
+            )
+        }
+
+        #[inline]
+        fn try_lock_range_shared(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            crate::range_lock::lock_range_guard(
+                &self.file,
+                crate::range_lock::RangeLockType::Shared,
+                offset,
+                len,
+                false,
+            )
+        }
+
+        #[inline]
+        fn try_lock_range_exclusive(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            crate::range_lock::lock_range_guard(
+                &self.file,
+                crate::range_lock::RangeLockType::Exclusive,
+                offset,
+                len,
+                false,
+            )
+        }
+    };
+}
+
+macro_rules! impl_advise {
+    () => {
+        #[inline]
+        fn advise(&self, advice: crate::Advice) -> crate::error::Result<()> {
+            self.advise_range(advice, 0, self.len())
+        }
+
+        fn advise_range(
+            &self,
+            advice: crate::Advice,
+            offset: usize,
+            len: usize,
+        ) -> crate::error::Result<()> {
+            crate::advice::check_range(offset, len, self.len())?;
+
+            #[cfg(unix)]
+            if let (Some(advice), true) = (advice.to_raw(), len > 0) {
+                self.mmap
+                    .advise_range(advice, offset, len)
+                    .map_err(|e| Error::new(ErrorKind::IO, e))?;
+            }
+            #[cfg(not(unix))]
+            let _ = advice;
+            Ok(())
+        }
+
+        unsafe fn unchecked_advise_range(
+            &self,
+            advice: crate::UncheckedAdvice,
+            offset: usize,
+            len: usize,
+        ) -> crate::error::Result<()> {
+            crate::advice::check_range(offset, len, self.len())?;
+
+            #[cfg(unix)]
+            if let (Some(advice), true) = (advice.to_raw(), len > 0) {
+                self.mmap
+                    .advise_range(advice, offset, len)
+                    .map_err(|e| Error::new(ErrorKind::IO, e))?;
+            }
+            #[cfg(not(unix))]
+            let _ = advice;
+            Ok(())
+        }
+    };
+}
+
+macro_rules! impl_residency {
+    () => {
+        fn resident_pages(&self) -> crate::error::Result<usize> {
+            crate::advice::residency(self.as_slice())
+                .map(|(resident, _)| resident)
+                .map_err(|e| Error::new(ErrorKind::IO, e))
+        }
+
+        fn residency_ratio(&self, offset: usize, len: usize) -> crate::error::Result<f64> {
+            let mmap_len = self.len();
+            let buf = offset
+                .checked_add(len)
+                .and_then(|end| self.as_slice().get(offset..end))
+                .ok_or_else(|| {
+                    Error::from(ErrorKind::OutOfBound(offset.saturating_add(len), mmap_len))
+                })?;
+            crate::advice::residency(buf)
+                .map(|(resident, total)| crate::advice::ratio(resident, total))
+                .map_err(|e| Error::new(ErrorKind::IO, e))
+        }
+    };
+}
+
+macro_rules! impl_lock_memory {
+    () => {
+        /// Locks the range `[offset, offset + len)` of the mmap in memory (`mlock`),
+        /// the pages are faulted in right now and will never be paged out.
+        ///
+        /// The lock is released when the mmap is dropped or remapped (e.g. by `truncate`),
+        /// unless the whole mmap is locked by `Options::lock_in_memory`.
+        /// Failures, e.g. exceeding the `RLIMIT_MEMLOCK` resource limit, are reported as [`ErrorKind::MlockFailed`].
+        ///
+        /// # Notes
+        /// Memory locking is only supported on unix platforms.
+        ///
+        /// [`ErrorKind::MlockFailed`]: fmmap::error::ErrorKind::MlockFailed
+        pub fn lock_memory(&self, offset: usize, len: usize) -> Result<(), Error> {
+            crate::advice::lock_memory(self.memory_range(offset, len)?, false)
+                .map_err(|e| Error::new(ErrorKind::MlockFailed, e))
+        }
+
+        /// Locks the range `[offset, offset + len)` of the mmap in memory (`mlock2` with `MLOCK_ONFAULT`),
+        /// the pages are locked as they are faulted in instead of being populated right now.
+        ///
+        /// Failures are reported as [`ErrorKind::MlockFailed`].
+        ///
+        /// [`ErrorKind::MlockFailed`]: fmmap::error::ErrorKind::MlockFailed
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        pub fn lock_memory_on_fault(&self, offset: usize, len: usize) -> Result<(), Error> {
+            crate::advice::lock_memory(self.memory_range(offset, len)?, true)
+                .map_err(|e| Error::new(ErrorKind::MlockFailed, e))
+        }
+
+        /// Unlocks the range `[offset, offset + len)` of the mmap (`munlock`),
+        /// the pages can be paged out again.
+        ///
+        /// Failures are reported as [`ErrorKind::MlockFailed`].
+        ///
+        /// [`ErrorKind::MlockFailed`]: fmmap::error::ErrorKind::MlockFailed
+        pub fn unlock_memory(&self, offset: usize, len: usize) -> Result<(), Error> {
+            crate::advice::unlock_memory(self.memory_range(offset, len)?)
+                .map_err(|e| Error::new(ErrorKind::MlockFailed, e))
+        }
+
+        fn memory_range(&self, offset: usize, len: usize) -> Result<&[u8], Error> {
+            let mmap_len = self.len();
+            offset
+                .checked_add(len)
+                .and_then(|end| self.as_slice().get(offset..end))
+                .ok_or_else(|| {
+                    Error::from(ErrorKind::OutOfBound(offset.saturating_add(len), mmap_len))
+                })
+        }
+
+        #[inline]
+        fn lock_in_memory_if(mut self, lock: bool) -> Result<Self, Error> {
+            self.locked = lock;
+            self.apply_lock_memory()?;
+            Ok(self)
+        }
+
+        /// Locks the whole mmap in memory again if it is locked by `Options::lock_in_memory`,
+        /// the lock is lost when the file is remapped.
+        fn apply_lock_memory(&self) -> Result<(), Error> {
+            if self.locked {
+                self.lock_memory(0, self.len())?;
             }
+            Ok(())
+        }
+    };
+}
+
+macro_rules! impl_huge_pages {
+    () => {
+        #[inline]
+        fn huge_pages_if(mut self, size: Option<crate::HugePageSize>) -> Result<Self, Error> {
+            self.huge_pages = size;
+            self.apply_huge_pages()?;
+            Ok(self)
+        }
 
-            fn as_slice(&self) -> &[u8] {
-                self.mmap.as_ref()
+        /// Applies the huge pages to the mmap again, the advice is lost when the file is remapped.
+        fn apply_huge_pages(&self) -> Result<(), Error> {
+            match self.huge_pages {
+                Some(size) => crate::advice::apply_huge_pages(&self.file, &self.mmap, size)
+                    .map_err(|e| {
+                        Error::new_source_msg(ErrorKind::HugePagesFailed, self.path_string(), e)
+                    }),
+                None => Ok(()),
             }
+        }
+    };
+}
 
+cfg_sync! {
+    macro_rules! impl_mmap_file_ext_base {
+        () => {
             fn path(&self) -> &Path {
                 self.path.as_path()
             }
 
             fn metadata(&self) -> crate::error::Result<MetaData> {
-                self.file.metadata().map(MetaData::disk).map_err(|e| Error::new(ErrorKind::IO, e))
+                self.file.metadata().map(|meta| MetaData::disk(meta).with_huge_pages(self.huge_pages)).map_err(|e| Error::new(ErrorKind::IO, e))
             }
 
             impl_file_lock!();
+
+            impl_range_lock!();
+
+            impl_advise!();
+
+            impl_residency!();
         };
     }
 
     macro_rules! impl_mmap_file_ext {
         ($name: ident) => {
             impl MmapFileExt for $name {
+                fn len(&self) -> usize {
+                    self.mmap.len()
+                }
+
+                fn as_slice(&self) -> &[u8] {
+                    self.mmap.as_ref()
+                }
+
                 impl_mmap_file_ext_base!();
 
                 /// Whether the mmap is executable.
@@ -140,6 +450,14 @@ cfg_sync! {
     macro_rules! impl_mmap_file_ext_for_mut {
         ($name: ident) => {
             impl MmapFileExt for $name {
+                fn len(&self) -> usize {
+                    self.committed.unwrap_or_else(|| self.mmap.len())
+                }
+
+                fn as_slice(&self) -> &[u8] {
+                    &self.mmap[..self.len()]
+                }
+
                 impl_mmap_file_ext_base!();
 
                 /// Whether the mmap is executable.
@@ -159,7 +477,9 @@ cfg_async! {
     macro_rules! impl_async_mmap_file_ext {
         ($name: ident) => {
             #[async_trait]
-            impl AsyncMmapFileExt for $name {
+            impl<R: AsyncRuntime> AsyncMmapFileExt for $name<R> {
+                type Runtime = R;
+
                 fn len(&self) -> usize {
                     self.mmap.len()
                 }
@@ -174,10 +494,9 @@ cfg_async! {
 
                 #[inline]
                 async fn metadata(&self) -> crate::error::Result<MetaData> {
-                    self.file
-                        .metadata()
+                    R::metadata(&self.file)
                         .await
-                        .map(MetaData::disk)
+                        .map(|meta| MetaData::disk(meta).with_huge_pages(self.huge_pages))
                         .map_err(|e| Error::new(ErrorKind::IO, e))
                 }
 
@@ -187,7 +506,57 @@ cfg_async! {
                     self.exec
                 }
 
-                impl_file_lock!();
+                impl_file_lock!(crate::runtime::as_std_file);
+
+                impl_range_lock!();
+
+                impl_advise!();
+
+                impl_residency!();
+
+                async fn lock_exclusive_offload(&self) -> crate::error::Result<()> {
+                    #[cfg(unix)]
+                    {
+                        let pending = crate::offload::PendingLock::new(crate::range_lock::duplicate_file(&self.file)?);
+                        crate::utils::unblock_in::<R, _, _>(pending.task(true)).await??;
+                        pending.finish();
+                        Ok(())
+                    }
+                    #[cfg(not(unix))]
+                    {
+                        AsyncMmapFileExt::lock_exclusive(self)
+                    }
+                }
+
+                async fn lock_shared_offload(&self) -> crate::error::Result<()> {
+                    #[cfg(unix)]
+                    {
+                        let pending = crate::offload::PendingLock::new(crate::range_lock::duplicate_file(&self.file)?);
+                        crate::utils::unblock_in::<R, _, _>(pending.task(false)).await??;
+                        pending.finish();
+                        Ok(())
+                    }
+                    #[cfg(not(unix))]
+                    {
+                        AsyncMmapFileExt::lock_shared(self)
+                    }
+                }
+
+                async fn read_at_async(&self, dst: &mut [u8], offset: usize) -> crate::error::Result<usize> {
+                    let buf = self.as_slice();
+                    #[cfg(unix)]
+                    if let Some(src) = buf.get(offset..) {
+                        let (start, len) = (src.as_ptr() as usize, src.len().min(dst.len()));
+                        crate::utils::unblock_in::<R, _, _>(move || crate::offload::populate(start, len)).await?;
+                    }
+                    Ok(crate::offload::copy_at(buf, dst, offset))
+                }
+
+                #[cfg(all(feature = "io-uring", target_os = "linux"))]
+                async fn read_into(&self, dst: &mut [u8], offset: usize) -> crate::error::Result<()> {
+                    let fd = std::os::unix::io::AsRawFd::as_raw_fd(&self.file);
+                    crate::uring::read_into(self.as_slice(), fd, dst, offset).await
+                }
             }
         };
     }
@@ -195,13 +564,15 @@ cfg_async! {
     macro_rules! impl_async_mmap_file_ext_for_mut {
         ($name: ident) => {
             #[async_trait]
-            impl AsyncMmapFileExt for $name {
+            impl<R: AsyncRuntime> AsyncMmapFileExt for $name<R> {
+                type Runtime = R;
+
                 fn len(&self) -> usize {
-                    self.mmap.len()
+                    self.committed.unwrap_or_else(|| self.mmap.len())
                 }
 
                 fn as_slice(&self) -> &[u8] {
-                    self.mmap.as_ref()
+                    &self.mmap[..self.len()]
                 }
 
                 fn path(&self) -> &Path {
@@ -210,10 +581,9 @@ cfg_async! {
 
                 #[inline]
                 async fn metadata(&self) -> crate::error::Result<MetaData> {
-                    self.file
-                        .metadata()
+                    R::metadata(&self.file)
                         .await
-                        .map(MetaData::disk)
+                        .map(|meta| MetaData::disk(meta).with_huge_pages(self.huge_pages))
                         .map_err(|e| Error::new(ErrorKind::IO, e))
                 }
 
@@ -223,25 +593,82 @@ cfg_async! {
                     false
                 }
 
-                impl_file_lock!();
+                impl_file_lock!(crate::runtime::as_std_file);
+
+                impl_range_lock!();
+
+                impl_advise!();
+
+                impl_residency!();
+
+                async fn lock_exclusive_offload(&self) -> crate::error::Result<()> {
+                    #[cfg(unix)]
+                    {
+                        let pending = crate::offload::PendingLock::new(crate::range_lock::duplicate_file(&self.file)?);
+                        crate::utils::unblock_in::<R, _, _>(pending.task(true)).await??;
+                        pending.finish();
+                        Ok(())
+                    }
+                    #[cfg(not(unix))]
+                    {
+                        AsyncMmapFileExt::lock_exclusive(self)
+                    }
+                }
+
+                async fn lock_shared_offload(&self) -> crate::error::Result<()> {
+                    #[cfg(unix)]
+                    {
+                        let pending = crate::offload::PendingLock::new(crate::range_lock::duplicate_file(&self.file)?);
+                        crate::utils::unblock_in::<R, _, _>(pending.task(false)).await??;
+                        pending.finish();
+                        Ok(())
+                    }
+                    #[cfg(not(unix))]
+                    {
+                        AsyncMmapFileExt::lock_shared(self)
+                    }
+                }
+
+                async fn read_at_async(&self, dst: &mut [u8], offset: usize) -> crate::error::Result<usize> {
+                    let buf = self.as_slice();
+                    #[cfg(unix)]
+                    if let Some(src) = buf.get(offset..) {
+                        let (start, len) = (src.as_ptr() as usize, src.len().min(dst.len()));
+                        crate::utils::unblock_in::<R, _, _>(move || crate::offload::populate(start, len)).await?;
+                    }
+                    Ok(crate::offload::copy_at(buf, dst, offset))
+                }
+
+                #[cfg(all(feature = "io-uring", target_os = "linux"))]
+                async fn read_into(&self, dst: &mut [u8], offset: usize) -> crate::error::Result<()> {
+                    // the private modifications of a copy-on-write mmap are not visible from the file
+                    if matches!(self.typ, MmapFileMutType::Cow) {
+                        return self.read_exact(dst, offset);
+                    }
+                    let fd = std::os::unix::io::AsRawFd::as_raw_fd(&self.file);
+                    crate::uring::read_into(self.as_slice(), fd, dst, offset).await
+                }
             }
         };
     }
 
     macro_rules! declare_and_impl_async_fmmap_file {
-        ($filename_prefix: literal, $doc_test_runtime: literal, $path_str: literal, $base_file: ty) => {
+        ($filename_prefix: literal, $doc_test_runtime: literal, $path_str: literal) => {
             /// AsyncDiskMmapFile contains an immutable mmap buffer
             /// and a read-only file.
-            pub struct AsyncDiskMmapFile {
+            pub struct AsyncDiskMmapFile<R: AsyncRuntime> {
                 pub(crate) mmap: Mmap,
-                pub(crate) file: $base_file,
+                pub(crate) file: R::File,
                 pub(crate) path: PathBuf,
                 exec: bool,
+                huge_pages: Option<crate::HugePageSize>,
+                /// Whether the whole mmap is locked in memory, see `Options::lock_in_memory`.
+                locked: bool,
             }
 
             impl_async_mmap_file_ext!(AsyncDiskMmapFile);
 
-            impl AsyncDiskMmapFile {
+            impl<R: AsyncRuntime> AsyncDiskMmapFile<R> {
                 /// Open a readable memory map backed by a file
                 ///
                 /// # Examples
@@ -302,7 +729,7 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 /// [AsyncOptions`]: struct.AsyncOptions.html
-                pub async fn open_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self, Error> {
+                pub async fn open_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self, Error> {
                     Self::open_in(path, Some(opts)).await
                 }
 
@@ -366,12 +793,60 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 /// [`AsyncOptions`]: struct.AsyncOptions.html
-                pub async fn open_exec_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self, Error> {
+                pub async fn open_exec_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self, Error> {
                     Self::open_exec_in(path, Some(opts)).await
                 }
 
-                async fn open_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions>) -> Result<Self, Error> {
-                    let file = open_read_only_file_async(&path).await.map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.as_ref().to_string_lossy(), e))?;
+                /// Mmap a file which has already been opened by the caller with [`AsyncOptions`], e.g.
+                /// a file opened with `O_DIRECT`, relative to a directory fd or received over a unix socket.
+                ///
+                /// The file is never opened by `path_hint`, which is only reported by `path` and `file_path`.
+                /// Only the mmap related fields of [`AsyncOptions`] (e.g. `offset`, `len`, `huge_pages`) take effect.
+                ///
+                /// # Examples
+                ///
+                #[doc = "```ignore"]
+                #[doc = concat!("use fmmap::", $path_str, "::{AsyncOptions, AsyncMmapFileExt};")]
+                #[doc = concat!("use fmmap::raw::", $path_str, "::AsyncDiskMmapFile;")]
+                #[doc = concat!("# use fmmap::", $path_str, "::{AsyncMmapFileMut, AsyncMmapFileMutExt};")]
+                #[doc = " # use scopeguard::defer;"]
+                ///
+                #[doc = concat!("# ", $doc_test_runtime, "::block_on(async {")]
+                #[doc = concat!("# let mut file = AsyncMmapFileMut::create(\"", $filename_prefix, "_disk_from_file_test.txt\").await.unwrap();")]
+                #[doc = concat!(" # defer!(std::fs::remove_file(\"", $filename_prefix, "_disk_from_file_test.txt\").unwrap());")]
+                #[doc = concat!("# file.truncate(12).await.unwrap();")]
+                #[doc = concat!("# file.write_all(\"some data...\".as_bytes(), 0).unwrap();")]
+                #[doc = concat!("# file.flush().unwrap();")]
+                #[doc = "# drop(file);"]
+                ///
+                #[doc = concat!("let file = ", $path_str, "::fs::File::open(\"", $filename_prefix, "_disk_from_file_test.txt\").await.unwrap();")]
+                #[doc = "let mut file = AsyncDiskMmapFile::from_file(file, None, AsyncOptions::new()).await.unwrap();"]
+                #[doc = "assert!(file.file_path().is_none());"]
+                #[doc = "let mut buf = vec![0; \"some data...\".len()];"]
+                #[doc = "file.read_exact(buf.as_mut_slice(), 0).unwrap();"]
+                #[doc = "assert_eq!(buf.as_slice(), \"some data...\".as_bytes());"]
+                #[doc = "# })"]
+                #[doc = "```"]
+                ///
+                /// [`AsyncOptions`]: struct.AsyncOptions.html
+                pub async fn from_file(file: R::File, path_hint: Option<PathBuf>, opts: AsyncOptions<R>) -> Result<Self, Error> {
+                    let mmap = unsafe {
+                        opts.mmap_opts.map(&file).map_err(|e| Error::new(ErrorKind::MmapFailed, e))?
+                    };
+                    Self {
+                        mmap,
+                        file,
+                        path: path_hint.unwrap_or_default(),
+                        exec: false,
+                        huge_pages: None,
+                        locked: false,
+                    }
+                    .huge_pages_if(opts.huge_pages)?
+                    .lock_in_memory_if(opts.lock_in_memory)
+                }
+
+                async fn open_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions<R>>) -> Result<Self, Error> {
+                    let file = crate::utils::open_read_only_file_in::<R>(path.as_ref()).await.map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.as_ref().to_string_lossy(), e))?;
 
                     match opts  {
                         None => {
@@ -382,25 +857,31 @@ cfg_async! {
                                 mmap,
                                 file,
                                 path: path.as_ref().to_path_buf(),
-                                exec: false
+                                exec: false,
+                                huge_pages: None,
+                                locked: false,
                             })
                         }
                         Some(opts) => {
                             let mmap = unsafe {
                                 opts.mmap_opts.map(&file).map_err(|e| Error::new(ErrorKind::MmapFailed, e))?
                             };
-                            Ok(Self {
+                            Self {
                                 mmap,
                                 file,
                                 path: path.as_ref().to_path_buf(),
                                 exec: false,
-                            })
+                                huge_pages: None,
+                                locked: false,
+                            }
+                            .huge_pages_if(opts.huge_pages)?
+                            .lock_in_memory_if(opts.lock_in_memory)
                         }
                     }
                 }
 
-                async fn open_exec_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions>) -> Result<Self, Error> {
-                    let file = open_read_only_file_async(&path)
+                async fn open_exec_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions<R>>) -> Result<Self, Error> {
+                    let file = crate::utils::open_read_only_file_in::<R>(path.as_ref())
                         .await
                         .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.as_ref().to_string_lossy(), e))?;
 
@@ -414,21 +895,31 @@ cfg_async! {
                                 file,
                                 path: path.as_ref().to_path_buf(),
                                 exec: true,
+                                huge_pages: None,
+                                locked: false,
                             })
                         }
                         Some(opts) => {
                             let mmap = unsafe {
                                 opts.mmap_opts.map_exec(&file).map_err(|e| Error::new(ErrorKind::MmapFailed, e))?
                             };
-                            Ok(Self {
+                            Self {
                                 mmap,
                                 file,
                                 path: path.as_ref().to_path_buf(),
-                                exec: true
-                            })
+                                exec: true,
+                                huge_pages: None,
+                                locked: false,
+                            }
+                            .huge_pages_if(opts.huge_pages)?
+                            .lock_in_memory_if(opts.lock_in_memory)
                         }
                     }
                 }
+
+                impl_lock_memory!();
+
+                impl_huge_pages!();
             }
         };
     }
@@ -436,9 +927,10 @@ cfg_async! {
     macro_rules! impl_async_mmap_file_mut_ext_for_mut {
         ($filename_prefix: literal, $doc_test_runtime: literal, $path_str: literal) => {
             #[async_trait]
-            impl AsyncMmapFileMutExt for AsyncDiskMmapFileMut {
+            impl<R: AsyncRuntime> AsyncMmapFileMutExt for AsyncDiskMmapFileMut<R> {
                 fn as_mut_slice(&mut self) -> &mut [u8] {
-                    self.mmap.as_mut()
+                    let len = self.len();
+                    &mut self.mmap[..len]
                 }
 
                 fn is_cow(&self) -> bool {
@@ -447,6 +939,25 @@ cfg_async! {
 
                 impl_flush!();
 
+                async fn flush_offload(&self) -> crate::error::Result<()> {
+                    self.flush_range_offload(0, self.mmap.len()).await
+                }
+
+                async fn flush_range_offload(&self, offset: usize, len: usize) -> crate::error::Result<()> {
+                    #[cfg(unix)]
+                    {
+                        let start = crate::offload::range_addr(&self.mmap, offset, len)?;
+                        let path = self.path_string();
+                        crate::utils::unblock_in::<R, _, _>(move || crate::offload::msync(start, len))
+                            .await?
+                            .map_err(|e| Error::new_source_msg(ErrorKind::FlushFailed, path, e))
+                    }
+                    #[cfg(not(unix))]
+                    {
+                        self.flush_range(offset, len)
+                    }
+                }
+
                 #[cfg(not(target_os = "linux"))]
                 async fn truncate(&mut self, max_sz: u64) -> Result<(), Error> {
                     if self.is_cow() {
@@ -454,7 +965,7 @@ cfg_async! {
                     }
 
                     // sync data
-                    let meta = self.file.metadata().await.map_err(|e| Error::new(ErrorKind::IO, e))?;
+                    let meta = R::metadata(&self.file).await.map_err(|e| Error::new(ErrorKind::IO, e))?;
                     if meta.len() > 0 {
                         self.flush()?;
                     }
@@ -464,7 +975,7 @@ cfg_async! {
                         drop_in_place(&mut self.mmap);
 
                         // truncate
-                        self.file.set_len(max_sz).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, self.path_lossy(), e))?;
+                        R::set_len(&self.file, max_sz).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, self.path_lossy(), e))?;
 
                         // remap
                         let mmap = remmap(self.path(), &self.file, self.opts.as_ref(), self.typ)?;
@@ -472,6 +983,10 @@ cfg_async! {
                         write(&mut self.mmap, mmap);
                     }
 
+                    self.apply_huge_pages()?;
+
+                    self.apply_lock_memory()?;
+
                     Ok(())
                 }
 
@@ -481,14 +996,42 @@ cfg_async! {
                         return Err(Error::new_with_message(ErrorKind::TruncationFailed, "cannot truncate a copy-on-write mmap file"));
                     }
 
+                    if let Some(committed) = self.committed {
+                        let cap = self.mmap.len();
+                        if max_sz > cap as u64 {
+                            return Err(Error::new_with_message(ErrorKind::TruncationFailed, "cannot grow a reserved mmap file beyond its reservation"));
+                        }
+
+                        let max_sz = max_sz as usize;
+                        if max_sz < committed {
+                            // release the truncated pages before shrinking the file
+                            unsafe { commit_reserved(&mut self.mmap, &self.file, committed, max_sz) }
+                                .map_err(|e| Error::new_source_msg(ErrorKind::RemmapFailed, self.path_lossy(), e))?;
+                            self.committed = Some(max_sz);
+                        }
+
+                        R::set_len(&self.file, max_sz as u64).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, self.path_lossy(), e))?;
+
+                        if max_sz > committed {
+                            unsafe { commit_reserved(&mut self.mmap, &self.file, committed, max_sz) }
+                                .map_err(|e| Error::new_source_msg(ErrorKind::RemmapFailed, self.path_lossy(), e))?;
+                            self.committed = Some(max_sz);
+                        }
+                        self.apply_huge_pages()?;
+                        self.apply_lock_memory()?;
+                        return Ok(());
+                    }
+
                     // sync data
                     self.flush()?;
 
                     // truncate
-                    self.file.set_len(max_sz).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, self.path_lossy(), e))?;
+                    R::set_len(&self.file, max_sz).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, self.path_lossy(), e))?;
 
                     // remap
                     self.mmap = remmap(self.path(), &self.file, self.opts.as_ref(), self.typ)?;
+                    self.apply_huge_pages()?;
+                    self.apply_lock_memory()?;
 
                     Ok(())
                 }
@@ -517,9 +1060,11 @@ cfg_async! {
                 async fn drop_remove(mut self) -> crate::error::Result<()> {
                     let path = self.path;
                     drop(self.mmap);
-                    self.file.set_len(0).await.map_err(|e| Error::new(ErrorKind::IO, e))?;
+                    R::set_len(&self.file, 0).await.map_err(|e| Error::new(ErrorKind::IO, e))?;
                     drop(self.file);
-                    remove_file(path).await.map_err(|e| Error::new(ErrorKind::IO, e))?;
+                    if !path.as_os_str().is_empty() {
+                        R::remove_file(path).await.map_err(|e| Error::new(ErrorKind::IO, e))?;
+                    }
                     Ok(())
                 }
 
@@ -550,7 +1095,7 @@ cfg_async! {
                     #[cfg(not(target_os = "linux"))]
                     {
                         // sync data
-                        let meta = self.file.metadata().await.map_err(|e| Error::new(ErrorKind::IO, e))?;
+                        let meta = R::metadata(&self.file).await.map_err(|e| Error::new(ErrorKind::IO, e))?;
                         if meta.len() > 0 {
                             self.flush()?;
                         }
@@ -562,8 +1107,10 @@ cfg_async! {
 
                     drop(self.mmap);
                     if max_sz >= 0 {
-                        self.file.set_len(max_sz as u64).await.map_err(|e| Error::new(ErrorKind::IO, e))?;
-                        sync_parent_async(&self.path).await?;
+                        R::set_len(&self.file, max_sz as u64).await.map_err(|e| Error::new(ErrorKind::IO, e))?;
+                        if !self.path.as_os_str().is_empty() {
+                            crate::utils::sync_parent_in::<R>(&self.path).await?;
+                        }
                     }
                     Ok(())
                 }
@@ -572,22 +1119,27 @@ cfg_async! {
     }
 
     macro_rules! declare_and_impl_async_fmmap_file_mut {
-        ($filename_prefix: literal, $doc_test_runtime: literal, $path_str: literal, $base_file: ty, $immutable_file: ident) => {
+        ($filename_prefix: literal, $doc_test_runtime: literal, $path_str: literal, $immutable_file: ident) => {
             /// AsyncDiskMmapFileMut contains a mutable mmap buffer
             /// and a writable file.
-            pub struct AsyncDiskMmapFileMut {
+            pub struct AsyncDiskMmapFileMut<R: AsyncRuntime> {
                 pub(crate) mmap: MmapMut,
-                pub(crate) file: $base_file,
+                pub(crate) file: R::File,
                 pub(crate) path: PathBuf,
                 opts: Option<MmapOptions>,
                 typ: MmapFileMutType,
+                /// The committed length if the mmap is a reserved address range, see `Options::reserve`.
+                committed: Option<usize>,
+                huge_pages: Option<crate::HugePageSize>,
+                /// Whether the whole mmap is locked in memory, see `Options::lock_in_memory`.
+                locked: bool,
             }
 
             impl_async_mmap_file_ext_for_mut!(AsyncDiskMmapFileMut);
 
             impl_async_mmap_file_mut_ext_for_mut!($filename_prefix, $doc_test_runtime, $path_str);
 
-            impl AsyncDiskMmapFileMut {
+            impl<R: AsyncRuntime> AsyncDiskMmapFileMut<R> {
                 /// Create a new file and mmap this file
                 ///
                 /// # Notes
@@ -638,7 +1190,7 @@ cfg_async! {
                 #[doc = "```"]
                 #[doc = ""]
                 #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
-                pub async fn create_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self, Error> {
+                pub async fn create_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self, Error> {
                     Self::create_in(path, Some(opts)).await
                 }
 
@@ -814,7 +1366,7 @@ cfg_async! {
                 #[doc = "```"]
                 #[doc = ""]
                 #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
-                pub async fn open_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self, Error> {
+                pub async fn open_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self, Error> {
                     Self::open_in(path, Some(opts)).await
                 }
 
@@ -909,7 +1461,7 @@ cfg_async! {
                 #[doc = "```"]
                 #[doc = ""]
                 #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
-                pub async fn open_exist_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self, Error> {
+                pub async fn open_exist_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self, Error> {
                     Self::open_exist_in(path, Some(opts)).await
                 }
 
@@ -1008,10 +1560,67 @@ cfg_async! {
                 #[doc = "```"]
                 #[doc = ""]
                 #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
-                pub async fn open_cow_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self, Error> {
+                pub async fn open_cow_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self, Error> {
                     Self::open_cow_in(path, Some(opts)).await
                 }
 
+                /// Mmap a writable file which has already been opened by the caller with [`AsyncOptions`], e.g.
+                /// a file opened with `O_TMPFILE`, relative to a directory fd or received over a unix socket.
+                ///
+                /// The file is never opened by `path_hint`, which is only reported by `path` and `file_path`.
+                /// If the file is empty, it is truncated to `max_size` of [`AsyncOptions`], but the parent directory
+                /// is not synced. Without a `path_hint`, `drop_remove` only truncates the file.
+                ///
+                /// # Examples
+                ///
+                #[doc = "```ignore"]
+                #[doc = concat!("use fmmap::", $path_str, "::{AsyncOptions, AsyncMmapFileExt, AsyncMmapFileMutExt};")]
+                #[doc = concat!("use fmmap::raw::", $path_str, "::AsyncDiskMmapFileMut;")]
+                #[doc = "# use scopeguard::defer;"]
+                #[doc = ""]
+                #[doc = concat!("# ", $doc_test_runtime, "::block_on(async {")]
+                #[doc = concat!("let file = ", $path_str, "::fs::OpenOptions::new()")]
+                #[doc = "    .read(true)"]
+                #[doc = "    .write(true)"]
+                #[doc = "    .create(true)"]
+                #[doc = concat!("    .open(\"", $filename_prefix, "_disk_from_file_mut_test.txt\")")]
+                #[doc = "    .await"]
+                #[doc = "    .unwrap();"]
+                #[doc = concat!("# defer!(std::fs::remove_file(\"", $filename_prefix, "_disk_from_file_mut_test.txt\").unwrap());")]
+                #[doc = "let opts = AsyncOptions::new().max_size(100);"]
+                #[doc = concat!("let mut file = AsyncDiskMmapFileMut::from_file(file, Some(\"", $filename_prefix, "_disk_from_file_mut_test.txt\".into()), opts).await.unwrap();")]
+                #[doc = "assert_eq!(file.len(), 100);"]
+                #[doc = "file.write_all(\"some data...\".as_bytes(), 0).unwrap();"]
+                #[doc = "file.flush().unwrap();"]
+                #[doc = "# })"]
+                #[doc = "```"]
+                #[doc = ""]
+                #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
+                pub async fn from_file(file: R::File, path_hint: Option<PathBuf>, opts: AsyncOptions<R>) -> Result<Self, Error> {
+                    let path = path_hint.unwrap_or_default();
+                    let meta = R::metadata(&file).await?;
+                    let file_sz = meta.len();
+                    if file_sz == 0 && opts.max_size > 0 {
+                        R::set_len(&file, opts.max_size).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, path.to_string_lossy(), e))?;
+                    }
+
+                    let file_sz = if file_sz == 0 { opts.max_size } else { file_sz };
+                    let (mmap, committed) = map_mut_with_reserve(&file, &opts.mmap_opts, opts.reserve, file_sz)
+                        .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?;
+                    Self {
+                        mmap,
+                        file,
+                        path,
+                        opts: Some(opts.mmap_opts),
+                        typ: MmapFileMutType::Normal,
+                        committed,
+                        huge_pages: None,
+                        locked: false,
+                    }
+                    .huge_pages_if(opts.huge_pages)?
+                    .lock_in_memory_if(opts.lock_in_memory)
+                }
+
                 /// Returns an immutable version of this memory mapped buffer.
                 /// If the memory map is file-backed, the file must have been opened with read permissions.
                 ///
@@ -1037,13 +1646,28 @@ cfg_async! {
                 #[doc = "file.freeze().unwrap();"]
                 #[doc = "# })"]
                 #[doc = "```"]
-                pub fn freeze(self) -> Result<$immutable_file, Error> {
-                    Ok($immutable_file {
-                        mmap: self.mmap.make_read_only().map_err(|e| Error::new(ErrorKind::IO, e))?,
+                pub fn freeze(self) -> Result<$immutable_file<R>, Error> {
+                    let mmap = match self.committed {
+                        // a reserved mmap also covers the reservation, so map the file again
+                        Some(_) => unsafe { Mmap::map(&self.file) },
+                        None => self.mmap.make_read_only(),
+                    }
+                    .map_err(|e| Error::new(ErrorKind::IO, e))?;
+                    let remapped = self.committed.is_some();
+                    let file = $immutable_file {
+                        mmap,
                         file: self.file,
                         path: self.path,
                         exec: false,
-                    })
+                        huge_pages: self.huge_pages,
+                        locked: self.locked,
+                    };
+                    // the huge pages and the memory lock are lost when the file is mapped again
+                    if remapped {
+                        file.apply_huge_pages()?;
+                        file.apply_lock_memory()?;
+                    }
+                    Ok(file)
                 }
 
                 /// Transition the memory map to be readable and executable.
@@ -1070,13 +1694,28 @@ cfg_async! {
                 #[doc = "file.freeze_exec().unwrap();"]
                 #[doc = "# })"]
                 #[doc = "```"]
-                pub fn freeze_exec(self) -> Result<$immutable_file, Error> {
-                    Ok($immutable_file {
-                        mmap: self.mmap.make_exec().map_err(|e| Error::new(ErrorKind::IO, e))?,
+                pub fn freeze_exec(self) -> Result<$immutable_file<R>, Error> {
+                    let mmap = match self.committed {
+                        // a reserved mmap also covers the reservation, so map the file again
+                        Some(_) => unsafe { MmapOptions::new().map_exec(&self.file) },
+                        None => self.mmap.make_exec(),
+                    }
+                    .map_err(|e| Error::new(ErrorKind::IO, e))?;
+                    let remapped = self.committed.is_some();
+                    let file = $immutable_file {
+                        mmap,
                         file: self.file,
                         path: self.path,
-                        exec: true
-                    })
+                        exec: true,
+                        huge_pages: self.huge_pages,
+                        locked: self.locked,
+                    };
+                    // the huge pages and the memory lock are lost when the file is mapped again
+                    if remapped {
+                        file.apply_huge_pages()?;
+                        file.apply_lock_memory()?;
+                    }
+                    Ok(file)
                 }
             }
         };
@@ -1084,9 +1723,9 @@ cfg_async! {
 
     macro_rules! impl_async_fmmap_file_mut_private {
         ($name: ident) => {
-            impl $name {
-                async fn create_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions>) -> Result<Self, Error> {
-                    let file = create_file_async(&path)
+            impl<R: AsyncRuntime> $name<R> {
+                async fn create_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions<R>>) -> Result<Self, Error> {
+                    let file = crate::utils::create_file_in::<R>(path.as_ref())
                         .await
                         .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.as_ref().to_string_lossy(), e))?;
 
@@ -1100,32 +1739,40 @@ cfg_async! {
                                 path: path.as_ref().to_path_buf(),
                                 opts: None,
                                 typ: MmapFileMutType::Normal,
+                                committed: None,
+                                huge_pages: None,
+                                locked: false,
                             })
                         }
                         Some(opts) => {
                             if opts.max_size > 0 {
-                                file.set_len(opts.max_size).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, path.as_ref().to_string_lossy(), e))?;
-                                sync_parent_async(&path).await?;
+                                R::set_len(&file, opts.max_size).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, path.as_ref().to_string_lossy(), e))?;
+                                crate::utils::sync_parent_in::<R>(path.as_ref()).await?;
                             }
 
-                            let opts_bk = opts.mmap_opts.clone();
-                            let mmap = unsafe { opts.mmap_opts.map_mut(&file).map_err(|e| Error::new(ErrorKind::MmapFailed, e))? };
+                            let (mmap, committed) = map_mut_with_reserve(&file, &opts.mmap_opts, opts.reserve, opts.max_size)
+                                .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?;
 
-                            Ok(Self {
+                            Self {
                                 mmap,
                                 file,
                                 path: path.as_ref().to_path_buf(),
-                                opts: Some(opts_bk),
+                                opts: Some(opts.mmap_opts),
                                 typ: MmapFileMutType::Normal,
-                            })
+                                committed,
+                                huge_pages: None,
+                                locked: false,
+                            }
+                            .huge_pages_if(opts.huge_pages)?
+                            .lock_in_memory_if(opts.lock_in_memory)
                         }
                     }
                 }
 
-                async fn open_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions>) -> Result<Self, Error> {
+                async fn open_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions<R>>) -> Result<Self, Error> {
                     match opts {
                         None => {
-                            let file = open_or_create_file_async(&path)
+                            let file = crate::utils::open_or_create_file_in::<R>(path.as_ref())
                                 .await
                                 .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.as_ref().to_string_lossy(), e))?;
 
@@ -1136,36 +1783,43 @@ cfg_async! {
                                 path: path.as_ref().to_path_buf(),
                                 opts: None,
                                 typ: MmapFileMutType::Normal,
+                                committed: None,
+                                huge_pages: None,
+                                locked: false,
                             })
                         }
                         Some(mut opts) => {
-                            let file = opts.file_opts.create(true).open(&path)
+                            let file = R::open(path.as_ref().to_path_buf(), opts.file_opts.create(true).clone())
                                 .await
                                 .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.as_ref().to_string_lossy(), e))?;
-                            let meta = file.metadata().await?;
+                            let meta = R::metadata(&file).await?;
                             let file_sz = meta.len();
                             if file_sz == 0 && opts.max_size > 0 {
-                                file.set_len(opts.max_size).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, path.as_ref().to_string_lossy(), e))?;
-                                sync_parent_async(&path).await?;
+                                R::set_len(&file, opts.max_size).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, path.as_ref().to_string_lossy(), e))?;
+                                crate::utils::sync_parent_in::<R>(path.as_ref()).await?;
                             }
 
-                            let opts_bk = opts.mmap_opts.clone();
-                            let mmap = unsafe {
-                                opts.mmap_opts.map_mut(&file).map_err(|e| Error::new(ErrorKind::MmapFailed, e))?
-                            };
-                            Ok(Self {
+                            let file_sz = if file_sz == 0 { opts.max_size } else { file_sz };
+                            let (mmap, committed) = map_mut_with_reserve(&file, &opts.mmap_opts, opts.reserve, file_sz)
+                                .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?;
+                            Self {
                                 mmap,
                                 file,
                                 path: path.as_ref().to_path_buf(),
-                                opts: Some(opts_bk),
+                                opts: Some(opts.mmap_opts),
                                 typ: MmapFileMutType::Normal,
-                            })
+                                committed,
+                                huge_pages: None,
+                                locked: false,
+                            }
+                            .huge_pages_if(opts.huge_pages)?
+                            .lock_in_memory_if(opts.lock_in_memory)
                         }
                     }
                 }
 
-                async fn open_exist_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions>) -> Result<Self, Error> {
-                    let file = open_exist_file_with_append_async(&path)
+                async fn open_exist_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions<R>>) -> Result<Self, Error> {
+                    let file = crate::utils::open_exist_file_with_append_in::<R>(path.as_ref())
                         .await
                         .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.as_ref().to_string_lossy(), e))?;
 
@@ -1178,33 +1832,40 @@ cfg_async! {
                                 path: path.as_ref().to_path_buf(),
                                 opts: None,
                                 typ: MmapFileMutType::Normal,
+                                committed: None,
+                                huge_pages: None,
+                                locked: false,
                             })
                         }
                         Some(opts) => {
-                            let meta = file.metadata().await?;
+                            let meta = R::metadata(&file).await?;
                             let file_sz = meta.len();
                             if file_sz == 0 && opts.max_size > 0 {
-                                file.set_len(opts.max_size).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, path.as_ref().to_string_lossy(), e))?;
-                                sync_parent_async(&path).await?;
+                                R::set_len(&file, opts.max_size).await.map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, path.as_ref().to_string_lossy(), e))?;
+                                crate::utils::sync_parent_in::<R>(path.as_ref()).await?;
                             }
 
-                            let opts_bk = opts.mmap_opts.clone();
-                            let mmap = unsafe {
-                                opts.mmap_opts.map_mut(&file)? };
+                            let file_sz = if file_sz == 0 { opts.max_size } else { file_sz };
+                            let (mmap, committed) = map_mut_with_reserve(&file, &opts.mmap_opts, opts.reserve, file_sz)?;
 
-                            Ok(Self {
+                            Self {
                                 mmap,
                                 file,
                                 path: path.as_ref().to_path_buf(),
-                                opts: Some(opts_bk),
+                                opts: Some(opts.mmap_opts),
                                 typ: MmapFileMutType::Normal,
-                            })
+                                committed,
+                                huge_pages: None,
+                                locked: false,
+                            }
+                            .huge_pages_if(opts.huge_pages)?
+                            .lock_in_memory_if(opts.lock_in_memory)
                         }
                     }
                 }
 
-                async fn open_cow_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions>) -> Result<Self, Error> {
-                    let file = open_exist_file_with_append_async(&path)
+                async fn open_cow_in<P: AsRef<Path>>(path: P, opts: Option<AsyncOptions<R>>) -> Result<Self, Error> {
+                    let file = crate::utils::open_exist_file_with_append_in::<R>(path.as_ref())
                         .await
                         .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.as_ref().to_string_lossy(), e))?;
 
@@ -1217,6 +1878,9 @@ cfg_async! {
                                 path: path.as_ref().to_path_buf(),
                                 opts: None,
                                 typ: MmapFileMutType::Cow,
+                                committed: None,
+                                huge_pages: None,
+                                locked: false,
                             })
                         }
                         Some(opts) => {
@@ -1224,29 +1888,46 @@ cfg_async! {
                             let mmap = unsafe {
                                 opts.mmap_opts.map_copy(&file)? };
 
-                            Ok(Self {
+                            Self {
                                 mmap,
                                 file,
                                 path: path.as_ref().to_path_buf(),
                                 opts: Some(opts_bk),
                                 typ: MmapFileMutType::Cow,
-                            })
+                                committed: None,
+                                huge_pages: None,
+                                locked: false,
+                            }
+                            .huge_pages_if(opts.huge_pages)?
+                            .lock_in_memory_if(opts.lock_in_memory)
                         }
                     }
                 }
+
+                impl_lock_memory!();
+
+                impl_huge_pages!();
             }
         };
     }
 }
 
+cfg_async! {
+    mod async_impl;
+    pub use async_impl::{AsyncDiskMmapFile, AsyncDiskMmapFileMut};
+}
+
 cfg_tokio! {
-    pub(crate) mod tokio_impl;
+    #[cfg(test)]
+    mod tokio_impl;
 }
 
 cfg_smol! {
-    pub(crate) mod smol_impl;
+    #[cfg(test)]
+    mod smol_impl;
 }
 
 cfg_async_std! {
-    pub(crate) mod async_std_impl;
+    #[cfg(test)]
+    mod async_std_impl;
 }
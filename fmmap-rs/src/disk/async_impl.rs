@@ -0,0 +1,19 @@
+#[cfg(target_os = "linux")]
+use crate::disk::commit_reserved;
+use crate::disk::{map_mut_with_reserve, MmapFileMutType};
+use crate::error::{Error, ErrorKind};
+use crate::{AsyncMmapFileExt, AsyncMmapFileMutExt, AsyncOptions, AsyncRuntime, MetaData};
+use async_trait::async_trait;
+use fs4::FileExt;
+use memmapix::{Mmap, MmapAsRawDesc, MmapMut, MmapOptions};
+use std::path::{Path, PathBuf};
+#[cfg(not(target_os = "linux"))]
+use std::ptr::{drop_in_place, write};
+
+remmap!(Path);
+
+declare_and_impl_async_fmmap_file!("tokio_async", "tokio_test", "tokio");
+
+declare_and_impl_async_fmmap_file_mut!("tokio_async", "tokio_test", "tokio", AsyncDiskMmapFile);
+
+impl_async_fmmap_file_mut_private!(AsyncDiskMmapFileMut);
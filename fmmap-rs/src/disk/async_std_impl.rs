@@ -1,32 +1,6 @@
 use crate::async_std::{AsyncMmapFileExt, AsyncMmapFileMutExt, AsyncOptions};
-use crate::disk::MmapFileMutType;
-use crate::error::{Error, ErrorKind};
-use crate::utils::async_std::{
-    create_file_async, open_exist_file_with_append_async, open_or_create_file_async,
-    open_read_only_file_async, sync_parent_async,
-};
-use crate::MetaData;
-use async_std::fs::{remove_file, File};
-use async_std::path::{Path, PathBuf};
-use async_trait::async_trait;
-use fs4::async_std::AsyncFileExt;
-use memmapix::{Mmap, MmapAsRawDesc, MmapMut, MmapOptions};
-#[cfg(not(target_os = "linux"))]
-use std::ptr::{drop_in_place, write};
-
-remmap!(Path);
-
-declare_and_impl_async_fmmap_file!("async_std_async", "async_std::task", "async_std", File);
-
-declare_and_impl_async_fmmap_file_mut!(
-    "async_std_async",
-    "async_std::task",
-    "async_std",
-    File,
-    AsyncDiskMmapFile
-);
-
-impl_async_fmmap_file_mut_private!(AsyncDiskMmapFileMut);
+use crate::raw::async_std::{AsyncDiskMmapFile, AsyncDiskMmapFileMut};
+use async_std::fs::File;
 
 impl_async_tests!(
     "std_async_disk",
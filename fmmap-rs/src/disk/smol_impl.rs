@@ -1,26 +1,6 @@
-use crate::disk::MmapFileMutType;
-use crate::error::{Error, ErrorKind};
+use crate::raw::smol::{AsyncDiskMmapFile, AsyncDiskMmapFileMut};
 use crate::smol::{AsyncMmapFileExt, AsyncMmapFileMutExt, AsyncOptions};
-use crate::utils::smol::{
-    create_file_async, open_exist_file_with_append_async, open_or_create_file_async,
-    open_read_only_file_async, sync_parent_async,
-};
-use crate::MetaData;
-use async_trait::async_trait;
-use fs4::smol::AsyncFileExt;
-use memmapix::{Mmap, MmapAsRawDesc, MmapMut, MmapOptions};
-use smol::fs::{remove_file, File};
-use std::path::{Path, PathBuf};
-#[cfg(not(target_os = "linux"))]
-use std::ptr::{drop_in_place, write};
-
-remmap!(Path);
-
-declare_and_impl_async_fmmap_file!("smol_async", "smol", "smol", File);
-
-declare_and_impl_async_fmmap_file_mut!("smol_async", "smol", "smol", File, AsyncDiskMmapFile);
-
-impl_async_fmmap_file_mut_private!(AsyncDiskMmapFileMut);
+use smol::fs::File;
 
 impl_async_tests!(
     "smol_async_disk",
@@ -1,4 +1,6 @@
-use crate::disk::MmapFileMutType;
+#[cfg(target_os = "linux")]
+use crate::disk::commit_reserved;
+use crate::disk::{map_mut_with_reserve, MmapFileMutType};
 use crate::error::{Error, ErrorKind};
 use crate::options::Options;
 use crate::utils::{
@@ -21,6 +23,9 @@ pub struct DiskMmapFile {
     pub(crate) file: File,
     pub(crate) path: PathBuf,
     exec: bool,
+    huge_pages: Option<crate::HugePageSize>,
+    /// Whether the whole mmap is locked in memory, see `Options::lock_in_memory`.
+    locked: bool,
 }
 
 impl_mmap_file_ext!(DiskMmapFile);
@@ -165,6 +170,8 @@ impl DiskMmapFile {
                     file,
                     path: path.as_ref().to_path_buf(),
                     exec: false,
+                    huge_pages: None,
+                    locked: false,
                 })
             }
             Some(opts) => {
@@ -173,12 +180,16 @@ impl DiskMmapFile {
                         .map(&file)
                         .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?
                 };
-                Ok(Self {
+                Self {
                     mmap,
                     file,
                     path: path.as_ref().to_path_buf(),
                     exec: false,
-                })
+                    huge_pages: None,
+                    locked: false,
+                }
+                .huge_pages_if(opts.huge_pages)?
+                .lock_in_memory_if(opts.lock_in_memory)
             }
         }
     }
@@ -200,6 +211,8 @@ impl DiskMmapFile {
                     file,
                     path: path.as_ref().to_path_buf(),
                     exec: true,
+                    huge_pages: None,
+                    locked: false,
                 })
             }
             Some(opts) => {
@@ -208,15 +221,84 @@ impl DiskMmapFile {
                         .map_exec(&file)
                         .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?
                 };
-                Ok(Self {
+                Self {
                     mmap,
                     file,
                     path: path.as_ref().to_path_buf(),
                     exec: true,
-                })
+                    huge_pages: None,
+                    locked: false,
+                }
+                .huge_pages_if(opts.huge_pages)?
+                .lock_in_memory_if(opts.lock_in_memory)
             }
         }
     }
+
+    /// Mmap a file which has already been opened by the caller with [`Options`], e.g.
+    /// a file opened with `O_DIRECT`, relative to a directory fd or received over a unix socket.
+    ///
+    /// The file is never opened by `path_hint`, which is only reported by [`path`] and [`file_path`].
+    /// Only the mmap related fields of [`Options`] (e.g. `offset`, `len`, `huge_pages`) take effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmmap::{Options, MmapFileExt};
+    /// use fmmap::raw::DiskMmapFile;
+    /// use std::fs::File;
+    /// use std::io::Write;
+    /// # use scopeguard::defer;
+    ///
+    /// # let mut file = File::create("disk_from_file_test.txt").unwrap();
+    /// # defer!(std::fs::remove_file("disk_from_file_test.txt").unwrap());
+    /// # file.write_all("some data...".as_bytes()).unwrap();
+    /// # drop(file);
+    ///
+    /// let file = File::open("disk_from_file_test.txt").unwrap();
+    /// let mut file = DiskMmapFile::from_file(file, None, Options::new()).unwrap();
+    /// assert!(file.file_path().is_none());
+    /// let mut buf = vec![0; "some data...".len()];
+    /// file.read_exact(buf.as_mut_slice(), 0);
+    /// assert_eq!(buf.as_slice(), "some data...".as_bytes());
+    /// ```
+    ///
+    /// [`Options`]: struct.Options.html
+    /// [`path`]: trait.MmapFileExt.html#tymethod.path
+    /// [`file_path`]: trait.MmapFileExt.html#method.file_path
+    pub fn from_file(file: File, path_hint: Option<PathBuf>, opts: Options) -> Result<Self, Error> {
+        let mmap = unsafe {
+            opts.mmap_opts
+                .map(&file)
+                .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?
+        };
+        Self {
+            mmap,
+            file,
+            path: path_hint.unwrap_or_default(),
+            exec: false,
+            huge_pages: None,
+            locked: false,
+        }
+        .huge_pages_if(opts.huge_pages)?
+        .lock_in_memory_if(opts.lock_in_memory)
+    }
+
+    /// Wraps `mmap`, which is a memory map of `file` created by the caller.
+    pub(crate) fn from_mmap(mmap: Mmap, file: File, path: PathBuf) -> Self {
+        Self {
+            mmap,
+            file,
+            path,
+            exec: false,
+            huge_pages: None,
+            locked: false,
+        }
+    }
+
+    impl_lock_memory!();
+
+    impl_huge_pages!();
 }
 
 /// DiskMmapFile contains a mutable mmap buffer
@@ -227,13 +309,19 @@ pub struct DiskMmapFileMut {
     pub(crate) path: PathBuf,
     opts: Option<MmapOptions>,
     typ: MmapFileMutType,
+    /// The committed length if the mmap is a reserved address range, see `Options::reserve`.
+    committed: Option<usize>,
+    huge_pages: Option<crate::HugePageSize>,
+    /// Whether the whole mmap is locked in memory, see `Options::lock_in_memory`.
+    locked: bool,
 }
 
 impl_mmap_file_ext_for_mut!(DiskMmapFileMut);
 
 impl MmapFileMutExt for DiskMmapFileMut {
     fn as_mut_slice(&mut self) -> &mut [u8] {
-        self.mmap.as_mut()
+        let len = self.len();
+        &mut self.mmap[..len]
     }
 
     fn is_cow(&self) -> bool {
@@ -275,6 +363,10 @@ impl MmapFileMutExt for DiskMmapFileMut {
             write(&mut self.mmap, mmap);
         }
 
+        self.apply_huge_pages()?;
+
+        self.apply_lock_memory()?;
+
         Ok(())
     }
 
@@ -287,6 +379,39 @@ impl MmapFileMutExt for DiskMmapFileMut {
             ));
         }
 
+        if let Some(committed) = self.committed {
+            let cap = self.mmap.len();
+            if max_sz > cap as u64 {
+                return Err(Error::new_with_message(
+                    ErrorKind::TruncationFailed,
+                    "cannot grow a reserved mmap file beyond its reservation",
+                ));
+            }
+
+            let max_sz = max_sz as usize;
+            if max_sz < committed {
+                // release the truncated pages before shrinking the file
+                unsafe { commit_reserved(&mut self.mmap, &self.file, committed, max_sz) }.map_err(
+                    |e| Error::new_source_msg(ErrorKind::RemmapFailed, self.path_string(), e),
+                )?;
+                self.committed = Some(max_sz);
+            }
+
+            self.file.set_len(max_sz as u64).map_err(|e| {
+                Error::new_source_msg(ErrorKind::TruncationFailed, self.path_string(), e)
+            })?;
+
+            if max_sz > committed {
+                unsafe { commit_reserved(&mut self.mmap, &self.file, committed, max_sz) }.map_err(
+                    |e| Error::new_source_msg(ErrorKind::RemmapFailed, self.path_string(), e),
+                )?;
+                self.committed = Some(max_sz);
+            }
+            self.apply_huge_pages()?;
+            self.apply_lock_memory()?;
+            return Ok(());
+        }
+
         // sync data
         self.flush()?;
 
@@ -297,6 +422,8 @@ impl MmapFileMutExt for DiskMmapFileMut {
 
         // remap
         self.mmap = remmap(self.path(), &self.file, self.opts.as_ref(), self.typ)?;
+        self.apply_huge_pages()?;
+        self.apply_lock_memory()?;
 
         Ok(())
     }
@@ -327,6 +454,9 @@ impl MmapFileMutExt for DiskMmapFileMut {
             .set_len(0)
             .map_err(|e| Error::new(ErrorKind::IO, e))?;
         drop(self.file);
+        if path.as_os_str().is_empty() {
+            return Ok(());
+        }
         remove_file(path).map_err(|e| Error::new(ErrorKind::IO, e))
     }
 
@@ -367,6 +497,9 @@ impl MmapFileMutExt for DiskMmapFileMut {
             self.file
                 .set_len(max_sz as u64)
                 .map_err(|e| Error::new(ErrorKind::IO, e))?;
+            if self.path.as_os_str().is_empty() {
+                return Ok(());
+            }
             let abs = self
                 .path
                 .canonicalize()
@@ -409,6 +542,9 @@ impl MmapFileMutExt for DiskMmapFileMut {
             self.file
                 .set_len(max_sz as u64)
                 .map_err(|e| Error::new(ErrorKind::IO, e))?;
+            if self.path.as_os_str().is_empty() {
+                return Ok(());
+            }
             let abs = self
                 .path
                 .canonicalize()
@@ -852,15 +988,27 @@ impl DiskMmapFileMut {
     /// file.freeze().unwrap();
     /// ```
     pub fn freeze(self) -> Result<DiskMmapFile, Error> {
-        Ok(DiskMmapFile {
-            mmap: self
-                .mmap
-                .make_read_only()
-                .map_err(|e| Error::new(ErrorKind::IO, e))?,
+        let mmap = match self.committed {
+            // a reserved mmap also covers the reservation, so map the file again
+            Some(_) => unsafe { Mmap::map(&self.file) },
+            None => self.mmap.make_read_only(),
+        }
+        .map_err(|e| Error::new(ErrorKind::IO, e))?;
+        let remapped = self.committed.is_some();
+        let file = DiskMmapFile {
+            mmap,
             file: self.file,
             path: self.path,
             exec: false,
-        })
+            huge_pages: self.huge_pages,
+            locked: self.locked,
+        };
+        // the huge pages and the memory lock are lost when the file is mapped again
+        if remapped {
+            file.apply_huge_pages()?;
+            file.apply_lock_memory()?;
+        }
+        Ok(file)
     }
 
     /// Transition the memory map to be readable and executable.
@@ -886,15 +1034,27 @@ impl DiskMmapFileMut {
     /// file.freeze_exec().unwrap();
     /// ```
     pub fn freeze_exec(self) -> Result<DiskMmapFile, Error> {
-        Ok(DiskMmapFile {
-            mmap: self
-                .mmap
-                .make_exec()
-                .map_err(|e| Error::new(ErrorKind::IO, e))?,
+        let mmap = match self.committed {
+            // a reserved mmap also covers the reservation, so map the file again
+            Some(_) => unsafe { MmapOptions::new().map_exec(&self.file) },
+            None => self.mmap.make_exec(),
+        }
+        .map_err(|e| Error::new(ErrorKind::IO, e))?;
+        let remapped = self.committed.is_some();
+        let file = DiskMmapFile {
+            mmap,
             file: self.file,
             path: self.path,
             exec: true,
-        })
+            huge_pages: self.huge_pages,
+            locked: self.locked,
+        };
+        // the huge pages and the memory lock are lost when the file is mapped again
+        if remapped {
+            file.apply_huge_pages()?;
+            file.apply_lock_memory()?;
+        }
+        Ok(file)
     }
 
     fn create_in<P: AsRef<Path>>(path: P, opts: Option<Options>) -> Result<Self, Error> {
@@ -914,6 +1074,9 @@ impl DiskMmapFileMut {
                     path: path.as_ref().to_path_buf(),
                     opts: None,
                     typ: MmapFileMutType::Normal,
+                    committed: None,
+                    huge_pages: None,
+                    locked: false,
                 })
             }
             Some(opts) => {
@@ -928,20 +1091,22 @@ impl DiskMmapFileMut {
                     sync_parent(&path)?;
                 }
 
-                let opts_bk = opts.mmap_opts.clone();
-                let mmap = unsafe {
-                    opts.mmap_opts
-                        .map_mut(&file)
-                        .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?
-                };
+                let (mmap, committed) =
+                    map_mut_with_reserve(&file, &opts.mmap_opts, opts.reserve, opts.max_size)
+                        .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?;
 
-                Ok(Self {
+                Self {
                     mmap,
                     file,
                     path: path.as_ref().to_path_buf(),
-                    opts: Some(opts_bk),
+                    opts: Some(opts.mmap_opts),
                     typ: MmapFileMutType::Normal,
-                })
+                    committed,
+                    huge_pages: None,
+                    locked: false,
+                }
+                .huge_pages_if(opts.huge_pages)?
+                .lock_in_memory_if(opts.lock_in_memory)
             }
         }
     }
@@ -961,6 +1126,9 @@ impl DiskMmapFileMut {
                     path: path.as_ref().to_path_buf(),
                     opts: None,
                     typ: MmapFileMutType::Normal,
+                    committed: None,
+                    huge_pages: None,
+                    locked: false,
                 })
             }
             Some(mut opts) => {
@@ -980,19 +1148,22 @@ impl DiskMmapFileMut {
                     sync_parent(&path)?;
                 }
 
-                let opts_bk = opts.mmap_opts.clone();
-                let mmap = unsafe {
-                    opts.mmap_opts
-                        .map_mut(&file)
-                        .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?
-                };
-                Ok(Self {
+                let file_sz = if file_sz == 0 { opts.max_size } else { file_sz };
+                let (mmap, committed) =
+                    map_mut_with_reserve(&file, &opts.mmap_opts, opts.reserve, file_sz)
+                        .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?;
+                Self {
                     mmap,
                     file,
                     path: path.as_ref().to_path_buf(),
-                    opts: Some(opts_bk),
+                    opts: Some(opts.mmap_opts),
                     typ: MmapFileMutType::Normal,
-                })
+                    committed,
+                    huge_pages: None,
+                    locked: false,
+                }
+                .huge_pages_if(opts.huge_pages)?
+                .lock_in_memory_if(opts.lock_in_memory)
             }
         }
     }
@@ -1011,6 +1182,9 @@ impl DiskMmapFileMut {
                     path: path.as_ref().to_path_buf(),
                     opts: None,
                     typ: MmapFileMutType::Normal,
+                    committed: None,
+                    huge_pages: None,
+                    locked: false,
                 })
             }
             Some(opts) => {
@@ -1026,16 +1200,22 @@ impl DiskMmapFileMut {
                     })?;
                     sync_parent(&path)?;
                 }
-                let opts_bk = opts.mmap_opts.clone();
-                let mmap = unsafe { opts.mmap_opts.map_mut(&file)? };
+                let file_sz = if file_sz == 0 { opts.max_size } else { file_sz };
+                let (mmap, committed) =
+                    map_mut_with_reserve(&file, &opts.mmap_opts, opts.reserve, file_sz)?;
 
-                Ok(Self {
+                Self {
                     mmap,
                     file,
                     path: path.as_ref().to_path_buf(),
-                    opts: Some(opts_bk),
+                    opts: Some(opts.mmap_opts),
                     typ: MmapFileMutType::Normal,
-                })
+                    committed,
+                    huge_pages: None,
+                    locked: false,
+                }
+                .huge_pages_if(opts.huge_pages)?
+                .lock_in_memory_if(opts.lock_in_memory)
             }
         }
     }
@@ -1054,22 +1234,94 @@ impl DiskMmapFileMut {
                     path: path.as_ref().to_path_buf(),
                     opts: None,
                     typ: MmapFileMutType::Cow,
+                    committed: None,
+                    huge_pages: None,
+                    locked: false,
                 })
             }
             Some(opts) => {
                 let opts_bk = opts.mmap_opts.clone();
                 let mmap = unsafe { opts.mmap_opts.map_copy(&file)? };
 
-                Ok(Self {
+                Self {
                     mmap,
                     file,
                     path: path.as_ref().to_path_buf(),
                     opts: Some(opts_bk),
                     typ: MmapFileMutType::Cow,
-                })
+                    committed: None,
+                    huge_pages: None,
+                    locked: false,
+                }
+                .huge_pages_if(opts.huge_pages)?
+                .lock_in_memory_if(opts.lock_in_memory)
             }
         }
     }
+
+    /// Mmap a writable file which has already been opened by the caller with [`Options`], e.g.
+    /// a file opened with `O_TMPFILE`, relative to a directory fd or received over a unix socket.
+    ///
+    /// The file is never opened by `path_hint`, which is only reported by [`path`] and [`file_path`].
+    /// If the file is empty, it is truncated to `max_size` of [`Options`], but the parent directory
+    /// is not synced. Without a `path_hint`, [`drop_remove`] only truncates the file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmmap::{Options, MmapFileExt, MmapFileMutExt};
+    /// use fmmap::raw::DiskMmapFileMut;
+    /// use std::fs::OpenOptions;
+    /// # use scopeguard::defer;
+    ///
+    /// let file = OpenOptions::new()
+    ///     .read(true)
+    ///     .write(true)
+    ///     .create(true)
+    ///     .open("disk_from_file_mut_test.txt")
+    ///     .unwrap();
+    /// # defer!(std::fs::remove_file("disk_from_file_mut_test.txt").unwrap());
+    /// let opts = Options::new().max_size(100);
+    /// let mut file = DiskMmapFileMut::from_file(file, Some("disk_from_file_mut_test.txt".into()), opts).unwrap();
+    /// assert_eq!(file.len(), 100);
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// file.flush().unwrap();
+    /// ```
+    ///
+    /// [`Options`]: struct.Options.html
+    /// [`path`]: trait.MmapFileExt.html#tymethod.path
+    /// [`file_path`]: trait.MmapFileExt.html#method.file_path
+    /// [`drop_remove`]: trait.MmapFileMutExt.html#tymethod.drop_remove
+    pub fn from_file(file: File, path_hint: Option<PathBuf>, opts: Options) -> Result<Self, Error> {
+        let path = path_hint.unwrap_or_default();
+        let meta = file.metadata()?;
+        let file_sz = meta.len();
+        if file_sz == 0 && opts.max_size > 0 {
+            file.set_len(opts.max_size).map_err(|e| {
+                Error::new_source_msg(ErrorKind::TruncationFailed, path.to_string_lossy(), e)
+            })?;
+        }
+
+        let file_sz = if file_sz == 0 { opts.max_size } else { file_sz };
+        let (mmap, committed) = map_mut_with_reserve(&file, &opts.mmap_opts, opts.reserve, file_sz)
+            .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?;
+        Self {
+            mmap,
+            file,
+            path,
+            opts: Some(opts.mmap_opts),
+            typ: MmapFileMutType::Normal,
+            committed,
+            huge_pages: None,
+            locked: false,
+        }
+        .huge_pages_if(opts.huge_pages)?
+        .lock_in_memory_if(opts.lock_in_memory)
+    }
+
+    impl_lock_memory!();
+
+    impl_huge_pages!();
 }
 
 impl_sync_tests!("disk", DiskMmapFile, DiskMmapFileMut);
@@ -1088,3 +1340,135 @@ fn test_close_with_truncate_on_empty_file() {
             .len()
     );
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_reserve() {
+    let path = "disk_reserve_test.txt";
+    let opts = Options::new().max_size(12).reserve(1 << 20);
+    let mut file = DiskMmapFileMut::create_with_options(path, opts).unwrap();
+    scopeguard::defer!(std::fs::remove_file(path).unwrap());
+    assert_eq!(file.len(), 12);
+    file.write_all("some data...".as_bytes(), 0).unwrap();
+    let ptr = file.as_slice().as_ptr();
+
+    file.truncate(3 * 4096 + 1).unwrap();
+    assert_eq!(file.len(), 3 * 4096 + 1);
+    assert_eq!(file.as_slice().as_ptr(), ptr);
+    assert_eq!(file.bytes(0, 12).unwrap(), "some data...".as_bytes());
+    file.write_u8(1, 3 * 4096).unwrap();
+    file.flush().unwrap();
+
+    file.truncate(4).unwrap();
+    assert_eq!(file.as_slice().as_ptr(), ptr);
+    assert_eq!(file.as_slice(), "some".as_bytes());
+    file.truncate((1 << 20) + 1).unwrap_err();
+    assert_eq!(file.file.metadata().unwrap().len(), 4);
+
+    let file = file.freeze().unwrap();
+    assert_eq!(file.as_slice(), "some".as_bytes());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_lock_memory() {
+    let path = "disk_lock_memory_test.txt";
+    let opts = Options::new().max_size(100).lock_in_memory();
+    let file = DiskMmapFileMut::create_with_options(path, opts).unwrap();
+    scopeguard::defer!(std::fs::remove_file(path).unwrap());
+    file.unlock_memory(0, 100).unwrap();
+    file.lock_memory(10, 20).unwrap();
+    #[cfg(target_os = "linux")]
+    file.lock_memory_on_fault(0, 100).unwrap();
+    file.unlock_memory(0, 100).unwrap();
+    assert_eq!(
+        file.lock_memory(90, 20).unwrap_err().kind(),
+        ErrorKind::OutOfBound(110, 100)
+    );
+
+    let file = file.freeze().unwrap();
+    file.lock_memory(0, 100).unwrap();
+    file.unlock_memory(0, 100).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_lock_in_memory_after_truncate() {
+    let path = "disk_lock_in_memory_truncate_test.txt";
+    let page_size = crate::advice::page_size();
+    let opts = Options::new().max_size(100).lock_in_memory();
+    let mut file = DiskMmapFileMut::create_with_options(path, opts).unwrap();
+    scopeguard::defer!(std::fs::remove_file(path).unwrap());
+
+    // the lock is applied again to the remapped file, which faults in the new pages
+    file.truncate((page_size * 4) as u64).unwrap();
+    assert_eq!(file.resident_pages().unwrap(), 4);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_huge_pages() {
+    use crate::HugePageSize;
+
+    let path = "disk_huge_pages_test.txt";
+    let opts = Options::new().max_size(100).huge_pages(HugePageSize::Size2M);
+    let err = DiskMmapFileMut::create_with_options(path, opts).err().unwrap();
+    scopeguard::defer!(std::fs::remove_file(path).unwrap());
+    assert_eq!(err.kind(), ErrorKind::HugePagesFailed);
+
+    // transparent huge pages may be disabled by the kernel
+    let opts = Options::new()
+        .read(true)
+        .write(true)
+        .huge_pages(HugePageSize::Transparent);
+    match DiskMmapFileMut::open_with_options(path, opts) {
+        Ok(mut file) => {
+            let meta = file.metadata().unwrap();
+            assert_eq!(meta.huge_pages(), Some(HugePageSize::Transparent));
+            file.truncate(4 << 20).unwrap();
+            let file = file.freeze().unwrap();
+            let meta = file.metadata().unwrap();
+            assert_eq!(meta.huge_pages(), Some(HugePageSize::Transparent));
+        }
+        Err(err) => assert_eq!(err.kind(), ErrorKind::HugePagesFailed),
+    }
+
+    let file = DiskMmapFile::open(path).unwrap();
+    assert_eq!(file.metadata().unwrap().huge_pages(), None);
+}
+
+#[test]
+fn test_from_file() {
+    let path = "disk_from_file_test.txt";
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .unwrap();
+    scopeguard::defer!(std::fs::remove_file(path).unwrap());
+    let mut file = DiskMmapFileMut::from_file(file, None, Options::new().max_size(12)).unwrap();
+    assert_eq!(file.len(), 12);
+    assert_eq!(file.path(), Path::new(""));
+    assert!(file.file_path().is_none());
+    file.write_all("some data...".as_bytes(), 0).unwrap();
+    file.flush().unwrap();
+    drop(file);
+
+    let file = File::open(path).unwrap();
+    let opts = Options::new().offset(5);
+    let file = DiskMmapFile::from_file(file, Some(PathBuf::from(path)), opts).unwrap();
+    assert_eq!(file.as_slice(), "data...".as_bytes());
+    assert_eq!(file.file_path(), Some(Path::new(path)));
+
+    // without a path hint, the file is only truncated
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .unwrap();
+    let file = DiskMmapFileMut::from_file(file, None, Options::new()).unwrap();
+    file.drop_remove().unwrap();
+    assert_eq!(std::fs::metadata(path).unwrap().len(), 0);
+}
@@ -1,32 +1,8 @@
-use crate::disk::MmapFileMutType;
-use crate::error::{Error, ErrorKind};
+use crate::error::ErrorKind;
+use crate::raw::tokio::{AsyncDiskMmapFile, AsyncDiskMmapFileMut};
 use crate::tokio::{AsyncMmapFileExt, AsyncMmapFileMutExt, AsyncOptions};
-use crate::utils::tokio::{
-    create_file_async, open_exist_file_with_append_async, open_or_create_file_async,
-    open_read_only_file_async, sync_parent_async,
-};
-use crate::MetaData;
-use async_trait::async_trait;
-use fs4::tokio::AsyncFileExt;
-use memmapix::{Mmap, MmapAsRawDesc, MmapMut, MmapOptions};
-use std::path::{Path, PathBuf};
-#[cfg(not(target_os = "linux"))]
-use std::ptr::{drop_in_place, write};
-use tokio::fs::{remove_file, File};
-
-remmap!(Path);
-
-declare_and_impl_async_fmmap_file!("tokio_async", "tokio_test", "tokio", File);
-
-declare_and_impl_async_fmmap_file_mut!(
-    "tokio_async",
-    "tokio_test",
-    "tokio",
-    File,
-    AsyncDiskMmapFile
-);
-
-impl_async_fmmap_file_mut_private!(AsyncDiskMmapFileMut);
+use std::path::Path;
+use tokio::fs::File;
 
 impl_async_tests!(
     "tokio_async_disk",
@@ -60,4 +36,133 @@ mod test {
                 .len()
         );
     }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_reserve() {
+        let path = "tokio_async_disk_reserve_test.txt";
+        let opts = AsyncOptions::new().max_size(12).reserve(1 << 20);
+        let mut file = AsyncDiskMmapFileMut::create_with_options(path, opts)
+            .await
+            .unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        file.write_all("some data...".as_bytes(), 0).unwrap();
+        let ptr = file.as_slice().as_ptr();
+
+        file.truncate(3 * 4096 + 1).await.unwrap();
+        assert_eq!(file.len(), 3 * 4096 + 1);
+        assert_eq!(file.as_slice().as_ptr(), ptr);
+        assert_eq!(file.bytes(0, 12).unwrap(), "some data...".as_bytes());
+
+        file.truncate(4).await.unwrap();
+        assert_eq!(file.as_slice().as_ptr(), ptr);
+        assert_eq!(file.as_slice(), "some".as_bytes());
+        file.truncate((1 << 20) + 1).await.unwrap_err();
+
+        let file = file.freeze().unwrap();
+        assert_eq!(file.as_slice(), "some".as_bytes());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_lock_memory() {
+        let path = "tokio_async_disk_lock_memory_test.txt";
+        let opts = AsyncOptions::new().max_size(100).lock_in_memory();
+        let file = AsyncDiskMmapFileMut::create_with_options(path, opts)
+            .await
+            .unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        file.lock_memory(10, 20).unwrap();
+        file.unlock_memory(0, 100).unwrap();
+        file.lock_memory(90, 20).unwrap_err();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_huge_pages() {
+        use crate::HugePageSize;
+
+        let path = "tokio_async_disk_huge_pages_test.txt";
+        let opts = AsyncOptions::new()
+            .max_size(100)
+            .huge_pages(HugePageSize::Size1G);
+        let err = AsyncDiskMmapFileMut::create_with_options(path, opts)
+            .await
+            .err()
+            .unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        assert_eq!(err.kind(), ErrorKind::HugePagesFailed);
+
+        let opts = AsyncOptions::new()
+            .read(true)
+            .write(true)
+            .huge_pages(HugePageSize::Transparent);
+        match AsyncDiskMmapFileMut::open_with_options(path, opts).await {
+            Ok(mut file) => {
+                file.truncate(4 << 20).await.unwrap();
+                let meta = file.metadata().await.unwrap();
+                assert_eq!(meta.huge_pages(), Some(HugePageSize::Transparent));
+            }
+            Err(err) => assert_eq!(err.kind(), ErrorKind::HugePagesFailed),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_from_file() {
+        let path = "tokio_async_disk_from_file_test.txt";
+        let file = tokio::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .await
+            .unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        let opts = AsyncOptions::new().max_size(12);
+        let mut file = AsyncDiskMmapFileMut::from_file(file, None, opts)
+            .await
+            .unwrap();
+        assert!(file.file_path().is_none());
+        file.write_all("some data...".as_bytes(), 0).unwrap();
+        file.close_with_truncate(5).await.unwrap();
+
+        let file = File::open(path).await.unwrap();
+        let file = AsyncDiskMmapFile::from_file(file, Some(path.into()), AsyncOptions::new())
+            .await
+            .unwrap();
+        assert_eq!(file.as_slice(), "some ".as_bytes());
+        assert_eq!(file.file_path(), Some(Path::new(path)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_lock_offload_cancelled() {
+        let path = "tokio_async_disk_lock_offload_cancelled_test.txt";
+        let opts = AsyncOptions::new().max_size(8);
+        let file1 = AsyncDiskMmapFileMut::create_with_options(path, opts)
+            .await
+            .unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        let file2 = AsyncDiskMmapFile::open(path).await.unwrap();
+
+        file1.lock_exclusive().unwrap();
+        let timeout = std::time::Duration::from_millis(20);
+        tokio::time::timeout(timeout, file2.lock_shared_offload())
+            .await
+            .unwrap_err();
+        file1.unlock().unwrap();
+
+        // the blocking task releases the lock once it acquires it
+        let mut locked = false;
+        for _ in 0..100 {
+            if file1.try_lock_exclusive().is_ok() {
+                locked = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(locked);
+        file1.unlock().unwrap();
+    }
 }
@@ -6,14 +6,27 @@ cfg_sync!(
 cfg_async! {
     macro_rules! declare_and_impl_async_empty_mmap_file {
         () => {
-            #[derive(Default, Clone)]
-            pub struct AsyncEmptyMmapFile {
+            #[derive(Clone)]
+            pub struct AsyncEmptyMmapFile<R: AsyncRuntime> {
                 inner: [u8; 0],
                 path: PathBuf,
+                _runtime: PhantomData<fn() -> R>,
+            }
+
+            impl<R: AsyncRuntime> Default for AsyncEmptyMmapFile<R> {
+                fn default() -> Self {
+                    Self {
+                        inner: [],
+                        path: PathBuf::new(),
+                        _runtime: PhantomData,
+                    }
+                }
             }
 
             #[async_trait]
-            impl AsyncMmapFileExt for AsyncEmptyMmapFile {
+            impl<R: AsyncRuntime> AsyncMmapFileExt for AsyncEmptyMmapFile<R> {
+                type Runtime = R;
+
                 #[inline]
                 fn len(&self) -> usize {
                     0
@@ -65,17 +78,38 @@ cfg_async! {
                 }
 
                 #[inline]
-                fn reader(&self, _offset: usize) -> Result<AsyncMmapFileReader> {
+                fn reader(&self, _offset: usize) -> Result<AsyncMmapFileReader<'_>> {
                     Err(Error::from(ErrorKind::InvokeEmptyMmap))
                 }
 
                 #[inline]
-                fn range_reader(&self, _offset: usize, _len: usize) -> Result<AsyncMmapFileReader> {
+                fn range_reader(&self, _offset: usize, _len: usize) -> Result<AsyncMmapFileReader<'_>> {
                     Err(Error::from(ErrorKind::InvokeEmptyMmap))
                 }
 
                 noop_file_lock!();
 
+                noop_range_lock!();
+
+                noop_advise!();
+
+                memory_residency!();
+
+                #[inline]
+                async fn lock_exclusive_offload(&self) -> crate::error::Result<()> {
+                    Ok(())
+                }
+
+                #[inline]
+                async fn lock_shared_offload(&self) -> crate::error::Result<()> {
+                    Ok(())
+                }
+
+                #[inline]
+                async fn read_at_async(&self, dst: &mut [u8], offset: usize) -> crate::error::Result<usize> {
+                    Ok(crate::offload::copy_at(self.as_slice(), dst, offset))
+                }
+
                 #[inline]
                 fn read_exact(&self, _dst: &mut [u8], _offset: usize) -> Result<()> {
                     Err(Error::from(ErrorKind::InvokeEmptyMmap))
@@ -93,7 +127,7 @@ cfg_async! {
             }
 
             #[async_trait]
-            impl AsyncMmapFileMutExt for AsyncEmptyMmapFile {
+            impl<R: AsyncRuntime> AsyncMmapFileMutExt for AsyncEmptyMmapFile<R> {
                 #[inline]
                 fn as_mut_slice(&mut self) -> &mut [u8] {
                     &mut self.inner
@@ -114,6 +148,16 @@ cfg_async! {
 
                 noop_flush!();
 
+                #[inline]
+                async fn flush_offload(&self) -> crate::error::Result<()> {
+                    Ok(())
+                }
+
+                #[inline]
+                async fn flush_range_offload(&self, _offset: usize, _len: usize) -> crate::error::Result<()> {
+                    Ok(())
+                }
+
                 #[inline]
                 async fn truncate(&mut self, _max_sz: u64) -> Result<()> {
                     Ok(())
@@ -130,12 +174,12 @@ cfg_async! {
                 }
 
                 #[inline]
-                fn writer(&mut self, _offset: usize) -> Result<AsyncMmapFileWriter> {
+                fn writer(&mut self, _offset: usize) -> Result<AsyncMmapFileWriter<'_>> {
                     Err(Error::from(ErrorKind::InvokeEmptyMmap))
                 }
 
                 #[inline]
-                fn range_writer(&mut self, _offset: usize, _len: usize) -> Result<AsyncMmapFileWriter> {
+                fn range_writer(&mut self, _offset: usize, _len: usize) -> Result<AsyncMmapFileWriter<'_>> {
                     Err(Error::from(ErrorKind::InvokeEmptyMmap))
                 }
 
@@ -151,14 +195,14 @@ cfg_async! {
     }
 
     macro_rules! test_empty_mmap_file {
-        ($attr: meta) => {
+        ($attr: meta, $runtime: ty) => {
             #[cfg(test)]
             mod tests {
                 use super::*;
 
                 #[$attr]
                 async fn test_async_empty() {
-                    let mut file = AsyncEmptyMmapFile::default();
+                    let mut file = AsyncEmptyMmapFile::<$runtime>::default();
                     file.slice(0, 0);
                     file.as_slice();
                     file.as_mut_slice();
@@ -179,7 +223,21 @@ cfg_async! {
                     file.flush_async().unwrap();
                     file.flush_range(0, 0).unwrap();
                     file.flush_async_range(0, 0).unwrap();
+                    file.flush_offload().await.unwrap();
+                    file.flush_range_offload(0, 0).await.unwrap();
+                    file.lock_exclusive_offload().await.unwrap();
+                    file.lock_shared_offload().await.unwrap();
+                    file.lock_range_shared(0, 0).unwrap().unlock().unwrap();
+                    file.try_lock_range_exclusive(0, 0).unwrap();
+                    file.advise(crate::Advice::Sequential).unwrap();
+                    file.advise_range(crate::Advice::WillNeed, 0, 0).unwrap();
+                    file.prefetch(0, 0).unwrap();
+                    assert_eq!(file.resident_pages().unwrap(), 0);
+                    assert_eq!(file.residency_ratio(0, 0).unwrap(), 1.0);
                     let mut buf = [0; 10];
+                    assert_eq!(file.read_at_async(&mut buf, 0).await.unwrap(), 0);
+                    #[cfg(all(feature = "io-uring", target_os = "linux"))]
+                    file.read_into(&mut buf, 0).await.unwrap_err();
                     file.reader(0).unwrap_err();
                     file.range_reader(0, 0).unwrap_err();
                     file.read_i8(0).unwrap_err();
@@ -199,14 +257,22 @@ cfg_async! {
     }
 }
 
+cfg_async!(
+    mod async_impl;
+    pub(crate) use async_impl::AsyncEmptyMmapFile;
+);
+
 cfg_async_std!(
-    pub(crate) mod async_std_impl;
+    #[cfg(test)]
+    mod async_std_impl;
 );
 
 cfg_smol!(
-    pub(crate) mod smol_impl;
+    #[cfg(test)]
+    mod smol_impl;
 );
 
 cfg_tokio!(
-    pub(crate) mod tokio_impl;
+    #[cfg(test)]
+    mod tokio_impl;
 );
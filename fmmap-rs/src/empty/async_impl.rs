@@ -0,0 +1,10 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::metadata::EmptyMetaData;
+use crate::{
+    AsyncMmapFileExt, AsyncMmapFileMutExt, AsyncMmapFileReader, AsyncMmapFileWriter,
+    AsyncRuntime, MetaData,
+};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+declare_and_impl_async_empty_mmap_file!();
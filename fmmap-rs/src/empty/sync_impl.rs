@@ -61,6 +61,12 @@ impl MmapFileExt for EmptyMmapFile {
 
     noop_file_lock!();
 
+    noop_range_lock!();
+
+    noop_advise!();
+
+    memory_residency!();
+
     fn read_exact(&self, _dst: &mut [u8], _offset: usize) -> Result<()> {
         Err(Error::from(ErrorKind::InvokeEmptyMmap))
     }
@@ -156,6 +162,13 @@ mod tests {
         file.flush_async().unwrap();
         file.flush_range(0, 0).unwrap();
         file.flush_async_range(0, 0).unwrap();
+        file.lock_range_shared(0, 0).unwrap().unlock().unwrap();
+        file.try_lock_range_exclusive(0, 0).unwrap();
+        file.advise(crate::Advice::Sequential).unwrap();
+        file.advise_range(crate::Advice::WillNeed, 0, 0).unwrap();
+        file.prefetch(0, 0).unwrap();
+        assert_eq!(file.resident_pages().unwrap(), 0);
+        assert_eq!(file.residency_ratio(0, 0).unwrap(), 1.0);
         let mut buf = [0; 10];
         file.reader(0).unwrap_err();
         file.range_reader(0, 0).unwrap_err();
@@ -49,6 +49,20 @@ pub enum ErrorKind {
     #[display("remmap failed")]
     RemmapFailed,
 
+    /// mlock or munlock failed, e.g. the `RLIMIT_MEMLOCK` resource limit is exceeded
+    #[display("mlock failed")]
+    MlockFailed,
+
+    /// the huge pages cannot be used, e.g. transparent huge pages are disabled,
+    /// or the file is not on a hugetlbfs mount
+    #[display("huge pages are not available")]
+    HugePagesFailed,
+
+    /// adding the seals to a memfd failed, e.g. the memfd is sealed with `F_SEAL_SEAL`,
+    /// or the mmap file is not backed by a memfd
+    #[display("seal failed")]
+    SealFailed,
+
     /// invalid range
     #[display("range start must not be greater than end: {0} <= {1}")]
     InvalidBound(usize, usize),
@@ -57,6 +71,21 @@ pub enum ErrorKind {
     #[display("range end out of bounds: {0} <= {1}")]
     OutOfBound(usize, usize),
 
+    /// invalid LEB128 variable-length integer, e.g. it overflows a 64-bit integer
+    #[display("invalid varint")]
+    InvalidVarint,
+
+    /// corrupted record, e.g. the checksum mismatches
+    #[display("corrupted record at offset {offset}")]
+    Corrupted {
+        /// the offset of the corrupted record
+        offset: usize,
+    },
+
+    /// misaligned access
+    #[display("offset {0} is not aligned to {1} bytes")]
+    Misaligned(usize, usize),
+
     /// call on an empty mmap file
     #[display("call on an empty mmap file")]
     InvokeEmptyMmap,
@@ -118,8 +118,8 @@ extern crate enum_dispatch;
 macro_rules! cfg_async_std {
     ($($item:item)*) => {
         $(
-            #[cfg(all(feature = "async-std", feature = "async-trait"))]
-            #[cfg_attr(docsrs, doc(cfg(all(feature = "async-std", feature = "async-trait"))))]
+            #[cfg(all(feature = "async-std", feature = "async"))]
+            #[cfg_attr(docsrs, doc(cfg(all(feature = "async-std", feature = "async"))))]
             $item
         )*
     }
@@ -128,8 +128,8 @@ macro_rules! cfg_async_std {
 macro_rules! cfg_smol {
     ($($item:item)*) => {
         $(
-            #[cfg(all(feature = "smol", feature = "async-trait"))]
-            #[cfg_attr(docsrs, doc(cfg(all(feature = "smol", feature = "async-trait"))))]
+            #[cfg(all(feature = "smol", feature = "async"))]
+            #[cfg_attr(docsrs, doc(cfg(all(feature = "smol", feature = "async"))))]
             $item
         )*
     }
@@ -138,8 +138,8 @@ macro_rules! cfg_smol {
 macro_rules! cfg_tokio {
     ($($item:item)*) => {
         $(
-            #[cfg(all(feature = "tokio", feature = "async-trait"))]
-            #[cfg_attr(docsrs, doc(cfg(all(feature = "tokio", feature = "async-trait"))))]
+            #[cfg(all(feature = "tokio", feature = "async"))]
+            #[cfg_attr(docsrs, doc(cfg(all(feature = "tokio", feature = "async"))))]
             $item
         )*
     }
@@ -158,8 +158,8 @@ macro_rules! cfg_sync {
 macro_rules! cfg_async {
     ($($item:item)*) => {
         $(
-            #[cfg(all(any(feature = "smol", feature = "async-std", feature = "tokio"), feature = "async-trait"))]
-            #[cfg_attr(docsrs, doc(cfg(all(any(feature = "smol", feature = "async-std", feature = "tokio"), feature = "async-trait"))))]
+            #[cfg(feature = "async")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
             $item
         )*
     }
@@ -254,6 +254,95 @@ macro_rules! noop_file_lock {
     };
 }
 
+macro_rules! noop_range_lock {
+    () => {
+        #[inline]
+        fn lock_range_shared(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            Ok(crate::RangeLockGuard::noop(offset, len))
+        }
+
+        #[inline]
+        fn lock_range_exclusive(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            Ok(crate::RangeLockGuard::noop(offset, len))
+        }
+
+        #[inline]
+        fn try_lock_range_shared(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            Ok(crate::RangeLockGuard::noop(offset, len))
+        }
+
+        #[inline]
+        fn try_lock_range_exclusive(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            Ok(crate::RangeLockGuard::noop(offset, len))
+        }
+    };
+}
+
+macro_rules! noop_advise {
+    () => {
+        #[inline]
+        fn advise(&self, _advice: crate::Advice) -> crate::error::Result<()> {
+            Ok(())
+        }
+
+        #[inline]
+        fn advise_range(
+            &self,
+            _advice: crate::Advice,
+            _offset: usize,
+            _len: usize,
+        ) -> crate::error::Result<()> {
+            Ok(())
+        }
+
+        #[inline]
+        unsafe fn unchecked_advise_range(
+            &self,
+            _advice: crate::UncheckedAdvice,
+            _offset: usize,
+            _len: usize,
+        ) -> crate::error::Result<()> {
+            Ok(())
+        }
+    };
+}
+
+macro_rules! memory_residency {
+    () => {
+        #[inline]
+        fn resident_pages(&self) -> crate::error::Result<usize> {
+            Ok(crate::advice::pages_of(self.as_slice()))
+        }
+
+        #[inline]
+        fn residency_ratio(&self, offset: usize, len: usize) -> crate::error::Result<f64> {
+            let mmap_len = self.len();
+            match offset.checked_add(len) {
+                Some(end) if end <= mmap_len => Ok(1.0),
+                _ => Err(crate::error::Error::from(
+                    crate::error::ErrorKind::OutOfBound(offset.saturating_add(len), mmap_len),
+                )),
+            }
+        }
+    };
+}
+
 cfg_sync! {
     macro_rules! impl_sync_tests {
         ($filename_prefix: literal, $mmap_file: ident, $mmap_file_mut: ident) => {
@@ -275,6 +364,131 @@ cfg_sync! {
                     file1.flush_async().unwrap();
                 }
 
+                #[test]
+                fn test_read_short() {
+                    let path = concat!($filename_prefix, "_read_short.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, Options::new().max_size(10)).unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    file1.write_all(b"0123456789", 0).unwrap();
+                    let mut buf = [0xff; 8];
+                    assert_eq!(file1.read(&mut buf, 6), 4);
+                    assert_eq!(&buf, b"6789\xff\xff\xff\xff");
+                    assert_eq!(file1.read(&mut buf, 10), 0);
+                    assert_eq!(file1.read(&mut buf, 11), 0);
+                }
+
+                #[test]
+                fn test_advise() {
+                    let path = concat!($filename_prefix, "_advise.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, Options::new().max_size(100)).unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    file1.write_all(vec![1; 100].as_slice(), 0).unwrap();
+                    file1.advise(crate::Advice::Sequential).unwrap();
+                    file1.advise_range(crate::Advice::WillNeed, 10, 20).unwrap();
+                    file1.advise_range(crate::Advice::Random, 90, 20).unwrap_err();
+                    drop(file1);
+
+                    let file2 = <$mmap_file>::open(path).unwrap();
+                    file2.advise(crate::Advice::Random).unwrap();
+                    // Safety: there are no references into the mmap, and the shared file pages are kept
+                    unsafe { file2.unchecked_advise_range(crate::UncheckedAdvice::DontNeed, 0, 100) }.unwrap();
+                }
+
+                #[test]
+                fn test_residency() {
+                    let path = concat!($filename_prefix, "_residency.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, Options::new().max_size(100)).unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    file1.write_all(vec![1; 100].as_slice(), 0).unwrap();
+                    file1.prefetch(0, 100).unwrap();
+                    file1.prefetch(90, 20).unwrap_err();
+                    assert!(file1.resident_pages().unwrap() >= 1);
+                    assert_eq!(file1.residency_ratio(0, 100).unwrap(), 1.0);
+                    assert_eq!(file1.residency_ratio(10, 0).unwrap(), 1.0);
+                    file1.residency_ratio(90, 20).unwrap_err();
+                }
+
+                #[cfg(unix)]
+                #[test]
+                fn test_lock_range() {
+                    let path = concat!($filename_prefix, "_lock_range.txt");
+                    let file1 = <$mmap_file_mut>::create_with_options(path, Options::new().max_size(100)).unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    let file2 = <$mmap_file_mut>::open(path).unwrap();
+
+                    let guard = file1.lock_range_exclusive(0, 10).unwrap();
+                    assert_eq!((guard.offset(), guard.range_len()), (0, 10));
+                    #[cfg(target_os = "linux")]
+                    file2.try_lock_range_shared(5, 10).unwrap_err();
+                    file2.try_lock_range_exclusive(10, 10).unwrap().unlock().unwrap();
+                    drop(guard);
+                    drop(file2.try_lock_range_exclusive(0, 10).unwrap());
+
+                    let guard1 = file1.lock_range_shared(0, 0).unwrap();
+                    let guard2 = file2.lock_range_shared(0, 50).unwrap();
+                    #[cfg(target_os = "linux")]
+                    file1.try_lock_range_exclusive(40, 10).unwrap_err();
+                    #[cfg(target_os = "linux")]
+                    file2.try_lock_range_exclusive(60, 10).unwrap_err();
+                    drop(guard1);
+                    drop(file2.try_lock_range_exclusive(60, 10).unwrap());
+                    guard2.unlock().unwrap();
+
+                    // the overlapping guards of the same file are independent
+                    let guard1 = file1.lock_range_shared(0, 20).unwrap();
+                    let guard2 = file1.lock_range_shared(10, 20).unwrap();
+                    drop(guard1);
+                    #[cfg(target_os = "linux")]
+                    file2.try_lock_range_exclusive(10, 10).unwrap_err();
+                    drop(file2.try_lock_range_exclusive(0, 10).unwrap());
+                    drop(guard2);
+                    drop(file2.try_lock_range_exclusive(10, 10).unwrap());
+                }
+
+                #[test]
+                fn test_atomic() {
+                    use std::sync::atomic::Ordering;
+
+                    let path = concat!($filename_prefix, "_atomic.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, Options::new().max_size(64)).unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    let a = file1.atomic_u32(4).unwrap();
+                    a.store(1, Ordering::SeqCst);
+                    assert_eq!(a.compare_exchange(1, 2, Ordering::SeqCst, Ordering::SeqCst), Ok(1));
+                    assert_eq!(file1.atomic_u32(4).unwrap().load(Ordering::SeqCst), 2);
+                    assert_eq!(file1.atomic_u32(2).unwrap_err().kind(), crate::error::ErrorKind::Misaligned(2, 4));
+                    assert_eq!(file1.atomic_u32(64).unwrap_err().kind(), crate::error::ErrorKind::EOF);
+                    file1.atomic_u64(8).unwrap().fetch_add(10, Ordering::SeqCst);
+                    assert_eq!(file1.atomic_u64(4).unwrap_err().kind(), crate::error::ErrorKind::Misaligned(4, 8));
+                    assert_eq!(file1.atomic_u64(60).unwrap_err().kind(), crate::error::ErrorKind::EOF);
+                    file1.flush().unwrap();
+                    drop(file1);
+
+                    let file2 = <$mmap_file>::open(path).unwrap();
+                    assert_eq!(file2.bytes(4, 4).unwrap(), 2u32.to_ne_bytes());
+                    assert_eq!(file2.bytes(8, 8).unwrap(), 10u64.to_ne_bytes());
+                }
+
+                #[cfg(feature = "bytemuck")]
+                #[test]
+                fn test_view() {
+                    let path = concat!($filename_prefix, "_view.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, Options::new().max_size(64)).unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    *file1.view_mut::<u64>(8).unwrap() = 0x0102_0304_0506_0708;
+                    file1.view_slice_mut::<u32>(16, 4).unwrap().copy_from_slice(&[1, 2, 3, 4]);
+                    assert_eq!(*file1.view::<u64>(8).unwrap(), 0x0102_0304_0506_0708);
+                    assert_eq!(file1.view_slice::<u32>(16, 4).unwrap(), &[1, 2, 3, 4]);
+                    assert_eq!(file1.view::<u32>(2).unwrap_err().kind(), crate::error::ErrorKind::Misaligned(2, 4));
+                    assert_eq!(file1.view_slice::<u32>(56, 3).unwrap_err().kind(), crate::error::ErrorKind::EOF);
+                    file1.flush().unwrap();
+                    drop(file1);
+
+                    let file2 = <$mmap_file>::open(path).unwrap();
+                    assert_eq!(file2.view::<u64>(8).unwrap(), &0x0102_0304_0506_0708);
+                    assert_eq!(file2.view_slice::<u32>(16, 4).unwrap(), &[1, 2, 3, 4]);
+                }
+
                 #[test]
                 fn test_lock_shared() {
                     let path = concat!($filename_prefix, "_lock_shared.txt");
@@ -723,6 +937,183 @@ cfg_async! {
                     file1.flush_async().unwrap();
                 }
 
+                #[$runtime]
+                async fn test_read_short() {
+                    let path = concat!($filename_prefix, "_read_short.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, AsyncOptions::new().max_size(10)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    file1.write_all(b"0123456789", 0).unwrap();
+                    let mut buf = [0xff; 8];
+                    assert_eq!(file1.read(&mut buf, 6), 4);
+                    assert_eq!(&buf, b"6789\xff\xff\xff\xff");
+                    assert_eq!(file1.read(&mut buf, 10), 0);
+                    assert_eq!(file1.read(&mut buf, 11), 0);
+                }
+
+                #[$runtime]
+                async fn test_advise() {
+                    let path = concat!($filename_prefix, "_advise.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, AsyncOptions::new().max_size(100)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    file1.write_all(vec![1; 100].as_slice(), 0).unwrap();
+                    file1.advise(crate::Advice::Sequential).unwrap();
+                    file1.advise_range(crate::Advice::WillNeed, 10, 20).unwrap();
+                    file1.advise_range(crate::Advice::Random, 90, 20).unwrap_err();
+                    drop(file1);
+
+                    let file2 = <$mmap_file>::open(path).await.unwrap();
+                    file2.advise(crate::Advice::Random).unwrap();
+                    // Safety: there are no references into the mmap, and the shared file pages are kept
+                    unsafe { file2.unchecked_advise_range(crate::UncheckedAdvice::DontNeed, 0, 100) }.unwrap();
+                }
+
+                #[$runtime]
+                async fn test_residency() {
+                    let path = concat!($filename_prefix, "_residency.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, AsyncOptions::new().max_size(100)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    file1.write_all(vec![1; 100].as_slice(), 0).unwrap();
+                    file1.prefetch(0, 100).unwrap();
+                    file1.prefetch(90, 20).unwrap_err();
+                    assert!(file1.resident_pages().unwrap() >= 1);
+                    assert_eq!(file1.residency_ratio(0, 100).unwrap(), 1.0);
+                    assert_eq!(file1.residency_ratio(10, 0).unwrap(), 1.0);
+                    file1.residency_ratio(90, 20).unwrap_err();
+                }
+
+                #[$runtime]
+                async fn test_offload() {
+                    let path = concat!($filename_prefix, "_offload.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, AsyncOptions::new().max_size(100)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    file1.write_all(vec![1; 100].as_slice(), 0).unwrap();
+                    file1.flush_range_offload(0, 10).await.unwrap();
+                    file1.flush_range_offload(90, 20).await.unwrap_err();
+                    file1.flush_offload().await.unwrap();
+
+                    let mut buf = [0; 20];
+                    assert_eq!(file1.read_at_async(&mut buf, 10).await.unwrap(), 20);
+                    assert_eq!(buf, [1; 20]);
+                    assert_eq!(file1.read_at_async(&mut buf, 90).await.unwrap(), 10);
+                    assert_eq!(file1.read_at_async(&mut buf, 100).await.unwrap(), 0);
+
+                    let file2 = <$mmap_file>::open(path).await.unwrap();
+                    file1.lock_exclusive_offload().await.unwrap();
+                    file2.try_lock_shared().unwrap_err();
+                    file1.unlock().unwrap();
+                    file2.lock_shared_offload().await.unwrap();
+                    file1.try_lock_exclusive().unwrap_err();
+                    file2.unlock().unwrap();
+                }
+
+                #[cfg(all(feature = "io-uring", target_os = "linux"))]
+                #[$runtime]
+                async fn test_read_into() {
+                    use std::os::unix::io::AsRawFd;
+
+                    let path = concat!($filename_prefix, "_read_into.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, AsyncOptions::new().max_size(8192)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    let data = (0..8192).map(|i| i as u8).collect::<Vec<_>>();
+                    file1.write_all(&data, 0).unwrap();
+                    file1.flush().unwrap();
+                    let mut buf = vec![0; 100];
+                    file1.read_into(&mut buf, 4000).await.unwrap();
+                    assert_eq!(buf, &data[4000..4100]);
+                    drop(file1);
+
+                    // evicts the pages from the page cache, so the data is read by io_uring
+                    let file = std::fs::File::open(path).unwrap();
+                    unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+                    let file2 = <$mmap_file>::open(path).await.unwrap();
+                    let mut buf = vec![0; 5000];
+                    file2.read_into(&mut buf, 3000).await.unwrap();
+                    assert_eq!(buf, &data[3000..8000]);
+                    file2.read_into(&mut buf, 4000).await.unwrap_err();
+                    file2.read_into(&mut [], 8192).await.unwrap();
+                }
+
+                #[cfg(unix)]
+                #[$runtime]
+                async fn test_lock_range() {
+                    let path = concat!($filename_prefix, "_lock_range.txt");
+                    let file1 = <$mmap_file_mut>::create_with_options(path, AsyncOptions::new().max_size(100)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    let file2 = <$mmap_file_mut>::open(path).await.unwrap();
+
+                    let guard = file1.lock_range_exclusive(0, 10).unwrap();
+                    assert_eq!((guard.offset(), guard.range_len()), (0, 10));
+                    #[cfg(target_os = "linux")]
+                    file2.try_lock_range_shared(5, 10).unwrap_err();
+                    file2.try_lock_range_exclusive(10, 10).unwrap().unlock().unwrap();
+                    drop(guard);
+                    drop(file2.try_lock_range_exclusive(0, 10).unwrap());
+
+                    let guard1 = file1.lock_range_shared(0, 0).unwrap();
+                    let guard2 = file2.lock_range_shared(0, 50).unwrap();
+                    #[cfg(target_os = "linux")]
+                    file1.try_lock_range_exclusive(40, 10).unwrap_err();
+                    #[cfg(target_os = "linux")]
+                    file2.try_lock_range_exclusive(60, 10).unwrap_err();
+                    drop(guard1);
+                    drop(file2.try_lock_range_exclusive(60, 10).unwrap());
+                    guard2.unlock().unwrap();
+
+                    // the overlapping guards of the same file are independent
+                    let guard1 = file1.lock_range_shared(0, 20).unwrap();
+                    let guard2 = file1.lock_range_shared(10, 20).unwrap();
+                    drop(guard1);
+                    #[cfg(target_os = "linux")]
+                    file2.try_lock_range_exclusive(10, 10).unwrap_err();
+                    drop(file2.try_lock_range_exclusive(0, 10).unwrap());
+                    drop(guard2);
+                    drop(file2.try_lock_range_exclusive(10, 10).unwrap());
+                }
+
+                #[$runtime]
+                async fn test_atomic() {
+                    use std::sync::atomic::Ordering;
+
+                    let path = concat!($filename_prefix, "_atomic.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, AsyncOptions::new().max_size(64)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    let a = file1.atomic_u32(4).unwrap();
+                    a.store(1, Ordering::SeqCst);
+                    assert_eq!(a.compare_exchange(1, 2, Ordering::SeqCst, Ordering::SeqCst), Ok(1));
+                    assert_eq!(file1.atomic_u32(4).unwrap().load(Ordering::SeqCst), 2);
+                    assert_eq!(file1.atomic_u32(2).unwrap_err().kind(), crate::error::ErrorKind::Misaligned(2, 4));
+                    assert_eq!(file1.atomic_u32(64).unwrap_err().kind(), crate::error::ErrorKind::EOF);
+                    file1.atomic_u64(8).unwrap().fetch_add(10, Ordering::SeqCst);
+                    assert_eq!(file1.atomic_u64(4).unwrap_err().kind(), crate::error::ErrorKind::Misaligned(4, 8));
+                    assert_eq!(file1.atomic_u64(60).unwrap_err().kind(), crate::error::ErrorKind::EOF);
+                    file1.flush().unwrap();
+                    drop(file1);
+
+                    let file2 = <$mmap_file>::open(path).await.unwrap();
+                    assert_eq!(file2.bytes(4, 4).unwrap(), 2u32.to_ne_bytes());
+                    assert_eq!(file2.bytes(8, 8).unwrap(), 10u64.to_ne_bytes());
+                }
+
+                #[cfg(feature = "bytemuck")]
+                #[$runtime]
+                async fn test_view() {
+                    let path = concat!($filename_prefix, "_view.txt");
+                    let mut file1 = <$mmap_file_mut>::create_with_options(path, AsyncOptions::new().max_size(64)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap(););
+                    *file1.view_mut::<u64>(8).unwrap() = 0x0102_0304_0506_0708;
+                    file1.view_slice_mut::<u32>(16, 4).unwrap().copy_from_slice(&[1, 2, 3, 4]);
+                    assert_eq!(*file1.view::<u64>(8).unwrap(), 0x0102_0304_0506_0708);
+                    assert_eq!(file1.view_slice::<u32>(16, 4).unwrap(), &[1, 2, 3, 4]);
+                    assert_eq!(file1.view::<u32>(2).unwrap_err().kind(), crate::error::ErrorKind::Misaligned(2, 4));
+                    assert_eq!(file1.view_slice::<u32>(56, 3).unwrap_err().kind(), crate::error::ErrorKind::EOF);
+                    file1.flush().unwrap();
+                    drop(file1);
+
+                    let file2 = <$mmap_file>::open(path).await.unwrap();
+                    assert_eq!(file2.view::<u64>(8).unwrap(), &0x0102_0304_0506_0708);
+                    assert_eq!(file2.view_slice::<u32>(16, 4).unwrap(), &[1, 2, 3, 4]);
+                }
+
                 #[$runtime]
                 async fn test_lock_shared() {
                     let path = concat!($filename_prefix, "_lock_shared.txt");
@@ -1149,34 +1540,83 @@ cfg_async! {
     }
 }
 
+mod advice;
+pub use advice::{Advice, HugePageSize, UncheckedAdvice};
+#[cfg(unix)]
+mod anon;
+mod append;
+mod chunks;
+#[cfg(all(feature = "sync", unix))]
+mod dir;
 mod disk;
 mod empty;
 /// Errors in this crate
 pub mod error;
+mod lock_guard;
+mod memfd;
+pub use memfd::SealFlags;
 mod memory;
 mod metadata;
 pub use metadata::{MetaData, MetaDataExt};
 mod mmap_file;
+cfg_async!(
+    mod offload;
+);
 #[allow(dead_code)]
 mod options;
+mod range_lock;
+#[cfg(all(feature = "io-uring", target_os = "linux", feature = "async"))]
+mod uring;
+pub use range_lock::RangeLockGuard;
 mod reader;
+#[cfg(feature = "record")]
+mod record;
+mod runtime;
+cfg_async!(
+    pub use runtime::AsyncRuntime;
+);
+mod shared;
 #[cfg(test)]
 pub mod tests;
 /// File I/O utils function
 pub mod utils;
+mod varint;
+pub use varint::LengthPrefix;
+mod view;
+#[cfg(feature = "bytemuck")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+pub use bytemuck;
 mod writer;
 
 cfg_sync!(
     /// std based mmap file
     pub mod sync {
+        pub use crate::append::AppendableMmapFile;
+        #[cfg(unix)]
+        #[cfg_attr(docsrs, doc(cfg(unix)))]
+        pub use crate::dir::Dir;
+        pub use crate::lock_guard::{FileLockGuard, FileLockGuardMut};
         pub use crate::mmap_file::{MmapFile, MmapFileExt, MmapFileMut, MmapFileMutExt};
         pub use crate::options::Options;
-        pub use crate::reader::{MmapFileReader, MmapFileReaderExt};
-        pub use crate::writer::{MmapFileWriter, MmapFileWriterExt};
+        pub use crate::reader::{MmapFileReader, MmapFileReaderExt, OwnedMmapFileReader};
+        #[cfg(feature = "record")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "record")))]
+        pub use crate::record::{RecordReader, RecordWriter};
+        pub use crate::shared::SharedMmapFile;
+        pub use crate::writer::{MmapFileWriter, MmapFileWriterExt, OwnedMmapFileWriter};
     }
 
-    pub use reader::{MmapFileReader, MmapFileReaderExt};
-    pub use writer::{MmapFileWriter, MmapFileWriterExt};
+    pub use append::AppendableMmapFile;
+    #[cfg(unix)]
+    #[cfg_attr(docsrs, doc(cfg(unix)))]
+    pub use dir::Dir;
+    pub use lock_guard::{FileLockGuard, FileLockGuardMut};
+    pub use reader::{MmapFileReader, MmapFileReaderExt, OwnedMmapFileReader};
+    #[cfg(feature = "record")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "record")))]
+    pub use record::{RecordReader, RecordWriter};
+    pub use shared::SharedMmapFile;
+    pub use writer::{MmapFileWriter, MmapFileWriterExt, OwnedMmapFileWriter};
     pub use mmap_file::{MmapFileExt, MmapFileMutExt, MmapFile, MmapFileMut};
     pub use options::Options;
 );
@@ -1186,39 +1626,98 @@ cfg_async!(
     extern crate async_trait;
 );
 
+cfg_async!(
+    pub use append::AsyncAppendableMmapFile;
+    pub use chunks::AsyncMmapFileChunks;
+    pub use lock_guard::{AsyncFileLockGuard, AsyncFileLockGuardMut};
+    pub use mmap_file::{AsyncMmapFile, AsyncMmapFileExt, AsyncMmapFileMut, AsyncMmapFileMutExt};
+    pub use options::AsyncOptions;
+    pub use reader::{AsyncMmapFileReader, AsyncMmapFileReaderExt, AsyncOwnedMmapFileReader};
+    pub use shared::AsyncSharedMmapFile;
+    pub use writer::{AsyncMmapFileWriter, AsyncMmapFileWriterExt, AsyncOwnedMmapFileWriter};
+);
+
 cfg_async_std!(
     /// async_std based mmap file
     pub mod async_std {
-        pub use crate::mmap_file::async_std_impl::{
-            AsyncMmapFile, AsyncMmapFileExt, AsyncMmapFileMut, AsyncMmapFileMutExt,
-        };
-        pub use crate::options::async_std_impl::AsyncOptions;
-        pub use crate::reader::async_std_impl::AsyncMmapFileReader;
-        pub use crate::writer::async_std_impl::AsyncMmapFileWriter;
+        pub use crate::lock_guard::{AsyncFileLockGuard, AsyncFileLockGuardMut};
+        pub use crate::mmap_file::{AsyncMmapFileExt, AsyncMmapFileMutExt};
+        pub use crate::reader::{AsyncMmapFileReader, AsyncMmapFileReaderExt};
+        pub use crate::runtime::async_std_impl::AsyncStdRuntime;
+        pub use crate::writer::{AsyncMmapFileWriter, AsyncMmapFileWriterExt};
+
+        /// async_std based appendable mmap file, see [`AsyncAppendableMmapFile`](crate::AsyncAppendableMmapFile).
+        pub type AsyncAppendableMmapFile = crate::AsyncAppendableMmapFile<AsyncStdRuntime>;
+        /// async_std based mmap file chunks, see [`AsyncMmapFileChunks`](crate::AsyncMmapFileChunks).
+        pub type AsyncMmapFileChunks = crate::AsyncMmapFileChunks<AsyncStdRuntime>;
+        /// async_std based read-only mmap file, see [`AsyncMmapFile`](crate::AsyncMmapFile).
+        pub type AsyncMmapFile = crate::AsyncMmapFile<AsyncStdRuntime>;
+        /// async_std based writable mmap file, see [`AsyncMmapFileMut`](crate::AsyncMmapFileMut).
+        pub type AsyncMmapFileMut = crate::AsyncMmapFileMut<AsyncStdRuntime>;
+        /// async_std based options, see [`AsyncOptions`](crate::AsyncOptions).
+        pub type AsyncOptions = crate::AsyncOptions<AsyncStdRuntime>;
+        /// async_std based owned reader, see [`AsyncOwnedMmapFileReader`](crate::AsyncOwnedMmapFileReader).
+        pub type AsyncOwnedMmapFileReader = crate::AsyncOwnedMmapFileReader<AsyncStdRuntime>;
+        /// async_std based owned writer, see [`AsyncOwnedMmapFileWriter`](crate::AsyncOwnedMmapFileWriter).
+        pub type AsyncOwnedMmapFileWriter = crate::AsyncOwnedMmapFileWriter<AsyncStdRuntime>;
+        /// async_std based shared mmap file, see [`AsyncSharedMmapFile`](crate::AsyncSharedMmapFile).
+        pub type AsyncSharedMmapFile = crate::AsyncSharedMmapFile<AsyncStdRuntime>;
     }
 );
 
 cfg_smol!(
     /// smol based mmap file
     pub mod smol {
-        pub use crate::mmap_file::smol_impl::{
-            AsyncMmapFile, AsyncMmapFileExt, AsyncMmapFileMut, AsyncMmapFileMutExt,
-        };
-        pub use crate::options::smol_impl::AsyncOptions;
-        pub use crate::reader::smol_impl::AsyncMmapFileReader;
-        pub use crate::writer::smol_impl::AsyncMmapFileWriter;
+        pub use crate::lock_guard::{AsyncFileLockGuard, AsyncFileLockGuardMut};
+        pub use crate::mmap_file::{AsyncMmapFileExt, AsyncMmapFileMutExt};
+        pub use crate::reader::{AsyncMmapFileReader, AsyncMmapFileReaderExt};
+        pub use crate::runtime::smol_impl::SmolRuntime;
+        pub use crate::writer::{AsyncMmapFileWriter, AsyncMmapFileWriterExt};
+
+        /// smol based appendable mmap file, see [`AsyncAppendableMmapFile`](crate::AsyncAppendableMmapFile).
+        pub type AsyncAppendableMmapFile = crate::AsyncAppendableMmapFile<SmolRuntime>;
+        /// smol based mmap file chunks, see [`AsyncMmapFileChunks`](crate::AsyncMmapFileChunks).
+        pub type AsyncMmapFileChunks = crate::AsyncMmapFileChunks<SmolRuntime>;
+        /// smol based read-only mmap file, see [`AsyncMmapFile`](crate::AsyncMmapFile).
+        pub type AsyncMmapFile = crate::AsyncMmapFile<SmolRuntime>;
+        /// smol based writable mmap file, see [`AsyncMmapFileMut`](crate::AsyncMmapFileMut).
+        pub type AsyncMmapFileMut = crate::AsyncMmapFileMut<SmolRuntime>;
+        /// smol based options, see [`AsyncOptions`](crate::AsyncOptions).
+        pub type AsyncOptions = crate::AsyncOptions<SmolRuntime>;
+        /// smol based owned reader, see [`AsyncOwnedMmapFileReader`](crate::AsyncOwnedMmapFileReader).
+        pub type AsyncOwnedMmapFileReader = crate::AsyncOwnedMmapFileReader<SmolRuntime>;
+        /// smol based owned writer, see [`AsyncOwnedMmapFileWriter`](crate::AsyncOwnedMmapFileWriter).
+        pub type AsyncOwnedMmapFileWriter = crate::AsyncOwnedMmapFileWriter<SmolRuntime>;
+        /// smol based shared mmap file, see [`AsyncSharedMmapFile`](crate::AsyncSharedMmapFile).
+        pub type AsyncSharedMmapFile = crate::AsyncSharedMmapFile<SmolRuntime>;
     }
 );
 
 cfg_tokio!(
     /// tokio based mmap file
     pub mod tokio {
-        pub use crate::mmap_file::tokio_impl::{
-            AsyncMmapFile, AsyncMmapFileExt, AsyncMmapFileMut, AsyncMmapFileMutExt,
-        };
-        pub use crate::options::tokio_impl::AsyncOptions;
-        pub use crate::reader::tokio_impl::AsyncMmapFileReader;
-        pub use crate::writer::tokio_impl::AsyncMmapFileWriter;
+        pub use crate::lock_guard::{AsyncFileLockGuard, AsyncFileLockGuardMut};
+        pub use crate::mmap_file::{AsyncMmapFileExt, AsyncMmapFileMutExt};
+        pub use crate::reader::{AsyncMmapFileReader, AsyncMmapFileReaderExt};
+        pub use crate::runtime::tokio_impl::TokioRuntime;
+        pub use crate::writer::{AsyncMmapFileWriter, AsyncMmapFileWriterExt};
+
+        /// tokio based appendable mmap file, see [`AsyncAppendableMmapFile`](crate::AsyncAppendableMmapFile).
+        pub type AsyncAppendableMmapFile = crate::AsyncAppendableMmapFile<TokioRuntime>;
+        /// tokio based mmap file chunks, see [`AsyncMmapFileChunks`](crate::AsyncMmapFileChunks).
+        pub type AsyncMmapFileChunks = crate::AsyncMmapFileChunks<TokioRuntime>;
+        /// tokio based read-only mmap file, see [`AsyncMmapFile`](crate::AsyncMmapFile).
+        pub type AsyncMmapFile = crate::AsyncMmapFile<TokioRuntime>;
+        /// tokio based writable mmap file, see [`AsyncMmapFileMut`](crate::AsyncMmapFileMut).
+        pub type AsyncMmapFileMut = crate::AsyncMmapFileMut<TokioRuntime>;
+        /// tokio based options, see [`AsyncOptions`](crate::AsyncOptions).
+        pub type AsyncOptions = crate::AsyncOptions<TokioRuntime>;
+        /// tokio based owned reader, see [`AsyncOwnedMmapFileReader`](crate::AsyncOwnedMmapFileReader).
+        pub type AsyncOwnedMmapFileReader = crate::AsyncOwnedMmapFileReader<TokioRuntime>;
+        /// tokio based owned writer, see [`AsyncOwnedMmapFileWriter`](crate::AsyncOwnedMmapFileWriter).
+        pub type AsyncOwnedMmapFileWriter = crate::AsyncOwnedMmapFileWriter<TokioRuntime>;
+        /// tokio based shared mmap file, see [`AsyncSharedMmapFile`](crate::AsyncSharedMmapFile).
+        pub type AsyncSharedMmapFile = crate::AsyncSharedMmapFile<TokioRuntime>;
     }
 );
 
@@ -1232,13 +1731,30 @@ pub mod raw {
         /// [`MmapFile`]: struct.MmapFile.html
         /// [`MmapFileMut`]: struct.MmapFileMut.html
         pub mod sync {
+            #[cfg(unix)]
+            #[cfg_attr(docsrs, doc(cfg(unix)))]
+            pub use crate::anon::{AnonMmapFile, AnonMmapFileMut};
             pub use crate::disk::{DiskMmapFile, DiskMmapFileMut};
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+            pub use crate::memfd::{MemfdMmapFile, MemfdMmapFileMut};
             pub use crate::memory::{MemoryMmapFile, MemoryMmapFileMut};
         }
+        #[cfg(unix)]
+        #[cfg_attr(docsrs, doc(cfg(unix)))]
+        pub use crate::anon::{AnonMmapFile, AnonMmapFileMut};
         pub use crate::disk::{DiskMmapFile, DiskMmapFileMut};
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        pub use crate::memfd::{MemfdMmapFile, MemfdMmapFileMut};
         pub use crate::memory::{MemoryMmapFile, MemoryMmapFileMut};
     );
 
+    cfg_async!(
+        pub use crate::disk::{AsyncDiskMmapFile, AsyncDiskMmapFileMut};
+        pub use crate::memory::{AsyncMemoryMmapFile, AsyncMemoryMmapFileMut};
+    );
+
     cfg_async_std!(
         /// async_std based raw mmap file
         ///
@@ -1247,8 +1763,16 @@ pub mod raw {
         /// [`AsyncMmapFile`]: async_std/struct.AsyncMmapFile.html
         /// [`AsyncMmapFileMut`]: async_std/struct.AsyncMmapFileMut.html
         pub mod async_std {
-            pub use crate::disk::async_std_impl::{AsyncDiskMmapFile, AsyncDiskMmapFileMut};
-            pub use crate::memory::async_std_impl::{AsyncMemoryMmapFile, AsyncMemoryMmapFileMut};
+            use crate::async_std::AsyncStdRuntime;
+
+            /// async_std based disk mmap file, see [`AsyncDiskMmapFile`](crate::raw::AsyncDiskMmapFile).
+            pub type AsyncDiskMmapFile = crate::raw::AsyncDiskMmapFile<AsyncStdRuntime>;
+            /// async_std based writable disk mmap file, see [`AsyncDiskMmapFileMut`](crate::raw::AsyncDiskMmapFileMut).
+            pub type AsyncDiskMmapFileMut = crate::raw::AsyncDiskMmapFileMut<AsyncStdRuntime>;
+            /// async_std based in-memory mmap file, see [`AsyncMemoryMmapFile`](crate::raw::AsyncMemoryMmapFile).
+            pub type AsyncMemoryMmapFile = crate::raw::AsyncMemoryMmapFile<AsyncStdRuntime>;
+            /// async_std based writable in-memory mmap file, see [`AsyncMemoryMmapFileMut`](crate::raw::AsyncMemoryMmapFileMut).
+            pub type AsyncMemoryMmapFileMut = crate::raw::AsyncMemoryMmapFileMut<AsyncStdRuntime>;
         }
     );
 
@@ -1257,11 +1781,19 @@ pub mod raw {
         ///
         /// Inner components of [`AsyncMmapFile`], [`AsyncMmapFileMut`]
         ///
-        /// [`AsyncMmapFile`]: async_std/struct.AsyncMmapFile.html
-        /// [`AsyncMmapFileMut`]: async_std/struct.AsyncMmapFileMut.html
+        /// [`AsyncMmapFile`]: smol/struct.AsyncMmapFile.html
+        /// [`AsyncMmapFileMut`]: smol/struct.AsyncMmapFileMut.html
         pub mod smol {
-            pub use crate::disk::smol_impl::{AsyncDiskMmapFile, AsyncDiskMmapFileMut};
-            pub use crate::memory::smol_impl::{AsyncMemoryMmapFile, AsyncMemoryMmapFileMut};
+            use crate::smol::SmolRuntime;
+
+            /// smol based disk mmap file, see [`AsyncDiskMmapFile`](crate::raw::AsyncDiskMmapFile).
+            pub type AsyncDiskMmapFile = crate::raw::AsyncDiskMmapFile<SmolRuntime>;
+            /// smol based writable disk mmap file, see [`AsyncDiskMmapFileMut`](crate::raw::AsyncDiskMmapFileMut).
+            pub type AsyncDiskMmapFileMut = crate::raw::AsyncDiskMmapFileMut<SmolRuntime>;
+            /// smol based in-memory mmap file, see [`AsyncMemoryMmapFile`](crate::raw::AsyncMemoryMmapFile).
+            pub type AsyncMemoryMmapFile = crate::raw::AsyncMemoryMmapFile<SmolRuntime>;
+            /// smol based writable in-memory mmap file, see [`AsyncMemoryMmapFileMut`](crate::raw::AsyncMemoryMmapFileMut).
+            pub type AsyncMemoryMmapFileMut = crate::raw::AsyncMemoryMmapFileMut<SmolRuntime>;
         }
     );
 
@@ -1273,8 +1805,16 @@ pub mod raw {
         /// [`AsyncMmapFile`]: tokio/struct.AsyncMmapFile.html
         /// [`AsyncMmapFileMut`]: tokio/struct.AsyncMmapFileMut.html
         pub mod tokio {
-            pub use crate::disk::tokio_impl::{AsyncDiskMmapFile, AsyncDiskMmapFileMut};
-            pub use crate::memory::tokio_impl::{AsyncMemoryMmapFile, AsyncMemoryMmapFileMut};
+            use crate::tokio::TokioRuntime;
+
+            /// tokio based disk mmap file, see [`AsyncDiskMmapFile`](crate::raw::AsyncDiskMmapFile).
+            pub type AsyncDiskMmapFile = crate::raw::AsyncDiskMmapFile<TokioRuntime>;
+            /// tokio based writable disk mmap file, see [`AsyncDiskMmapFileMut`](crate::raw::AsyncDiskMmapFileMut).
+            pub type AsyncDiskMmapFileMut = crate::raw::AsyncDiskMmapFileMut<TokioRuntime>;
+            /// tokio based in-memory mmap file, see [`AsyncMemoryMmapFile`](crate::raw::AsyncMemoryMmapFile).
+            pub type AsyncMemoryMmapFile = crate::raw::AsyncMemoryMmapFile<TokioRuntime>;
+            /// tokio based writable in-memory mmap file, see [`AsyncMemoryMmapFileMut`](crate::raw::AsyncMemoryMmapFileMut).
+            pub type AsyncMemoryMmapFileMut = crate::raw::AsyncMemoryMmapFileMut<TokioRuntime>;
         }
     );
 }
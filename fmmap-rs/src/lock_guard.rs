@@ -0,0 +1,195 @@
+cfg_sync!(
+    mod sync_impl;
+    pub use sync_impl::{FileLockGuard, FileLockGuardMut};
+);
+
+cfg_async! {
+    macro_rules! declare_and_impl_async_file_lock_guards {
+        ($path_str: literal) => {
+            /// AsyncFileLockGuard holds a shared or exclusive lock of the file,
+            /// the file is unlocked when the guard is dropped.
+            ///
+            /// The guard derefs to the data of the mmap file, see [`AsyncMmapFile::lock_shared_guard`]
+            /// and [`AsyncMmapFileMut::lock_shared_guard`].
+            ///
+            /// The lock belongs to the file handle rather than to the guard, so the guard borrows
+            /// the mmap file mutably, a second guard can not be taken, nor the file be unlocked,
+            /// while the guard is alive.
+            ///
+            #[doc = concat!("[`AsyncMmapFile::lock_shared_guard`]: ", $path_str, "/struct.AsyncMmapFile.html#method.lock_shared_guard")]
+            #[doc = concat!("[`AsyncMmapFileMut::lock_shared_guard`]: ", $path_str, "/struct.AsyncMmapFileMut.html#method.lock_shared_guard")]
+            pub struct AsyncFileLockGuard<'a, F: AsyncMmapFileExt + ?Sized> {
+                file: &'a mut F,
+            }
+
+            impl<'a, F: AsyncMmapFileExt + ?Sized> AsyncFileLockGuard<'a, F> {
+                /// Wraps a file which is already locked.
+                #[inline]
+                pub(crate) fn new(file: &'a mut F) -> Self {
+                    Self { file }
+                }
+
+                /// Unlocks the file, returns the error if unlocking fails,
+                /// while dropping the guard ignores the error.
+                pub fn unlock(self) -> Result<()> {
+                    let rst = self.file.unlock();
+                    mem::forget(self);
+                    rst
+                }
+            }
+
+            impl<F: AsyncMmapFileExt + ?Sized> Deref for AsyncFileLockGuard<'_, F> {
+                type Target = [u8];
+
+                #[inline]
+                fn deref(&self) -> &Self::Target {
+                    self.file.as_slice()
+                }
+            }
+
+            impl<F: AsyncMmapFileExt + ?Sized> Drop for AsyncFileLockGuard<'_, F> {
+                fn drop(&mut self) {
+                    let _ = self.file.unlock();
+                }
+            }
+
+            impl<F: AsyncMmapFileExt + ?Sized> Debug for AsyncFileLockGuard<'_, F> {
+                fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct("AsyncFileLockGuard")
+                        .field("path", &self.file.path())
+                        .finish()
+                }
+            }
+
+            /// AsyncFileLockGuardMut holds an exclusive lock of the file,
+            /// the file is unlocked when the guard is dropped.
+            ///
+            /// The guard derefs mutably to the data of the mmap file, see [`AsyncMmapFileMut::lock_exclusive_guard`].
+            ///
+            #[doc = concat!("[`AsyncMmapFileMut::lock_exclusive_guard`]: ", $path_str, "/struct.AsyncMmapFileMut.html#method.lock_exclusive_guard")]
+            pub struct AsyncFileLockGuardMut<'a, F: AsyncMmapFileExt + AsyncMmapFileMutExt + ?Sized> {
+                file: &'a mut F,
+            }
+
+            impl<'a, F: AsyncMmapFileExt + AsyncMmapFileMutExt + ?Sized> AsyncFileLockGuardMut<'a, F> {
+                /// Wraps a file which is already locked.
+                #[inline]
+                pub(crate) fn new(file: &'a mut F) -> Self {
+                    Self { file }
+                }
+
+                /// Unlocks the file, returns the error if unlocking fails,
+                /// while dropping the guard ignores the error.
+                pub fn unlock(self) -> Result<()> {
+                    let rst = self.file.unlock();
+                    mem::forget(self);
+                    rst
+                }
+            }
+
+            impl<F: AsyncMmapFileExt + AsyncMmapFileMutExt + ?Sized> Deref for AsyncFileLockGuardMut<'_, F> {
+                type Target = [u8];
+
+                #[inline]
+                fn deref(&self) -> &Self::Target {
+                    self.file.as_slice()
+                }
+            }
+
+            impl<F: AsyncMmapFileExt + AsyncMmapFileMutExt + ?Sized> DerefMut for AsyncFileLockGuardMut<'_, F> {
+                #[inline]
+                fn deref_mut(&mut self) -> &mut Self::Target {
+                    self.file.as_mut_slice()
+                }
+            }
+
+            impl<F: AsyncMmapFileExt + AsyncMmapFileMutExt + ?Sized> Drop for AsyncFileLockGuardMut<'_, F> {
+                fn drop(&mut self) {
+                    let _ = self.file.unlock();
+                }
+            }
+
+            impl<F: AsyncMmapFileExt + AsyncMmapFileMutExt + ?Sized> Debug for AsyncFileLockGuardMut<'_, F> {
+                fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct("AsyncFileLockGuardMut")
+                        .field("path", &self.file.path())
+                        .finish()
+                }
+            }
+        };
+    }
+
+    macro_rules! test_async_file_lock_guards {
+        ($filename_prefix: literal, $runtime: ident, $attr: meta) => {
+            #[cfg(test)]
+            mod tests {
+                use crate::error::Result;
+                use crate::$runtime::{AsyncMmapFile, AsyncMmapFileExt, AsyncMmapFileMut, AsyncOptions};
+                use scopeguard::defer;
+
+                #[$attr]
+                async fn test_async_lock_guard() {
+                    let path = concat!($filename_prefix, "_lock_guard_test.txt");
+                    let mut file1 = AsyncMmapFileMut::create_with_options(path, AsyncOptions::new().max_size(8)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap());
+                    let mut file2 = AsyncMmapFile::open(path).await.unwrap();
+
+                    {
+                        let mut guard = file1.lock_exclusive_guard().await.unwrap();
+                        guard[..4].copy_from_slice(b"abcd");
+                        assert_eq!(&guard[..4], b"abcd");
+                        file2.try_lock_shared().unwrap_err();
+                    }
+
+                    // the exclusive lock is released on drop
+                    let guard = file2.lock_shared_guard().await.unwrap();
+                    assert_eq!(&guard[..4], b"abcd");
+                    file1.try_lock_exclusive().unwrap_err();
+                    let guard1 = file1.lock_shared_guard().await.unwrap();
+                    guard.unlock().unwrap();
+                    file2.try_lock_exclusive().unwrap_err();
+                    drop(guard1);
+                    file2.lock_exclusive_guard().await.unwrap().unlock().unwrap();
+                }
+
+                #[$attr]
+                async fn test_async_lock_guard_contended() {
+                    let path = concat!($filename_prefix, "_lock_guard_contended_test.txt");
+                    let file1 = AsyncMmapFileMut::create_with_options(path, AsyncOptions::new().max_size(8)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap());
+                    let mut file2 = AsyncMmapFile::open(path).await.unwrap();
+
+                    file1.lock_exclusive().unwrap();
+                    let handle = std::thread::spawn(move || -> Result<()> {
+                        std::thread::sleep(std::time::Duration::from_millis(50));
+                        file1.unlock()
+                    });
+                    // waits until the other thread releases the exclusive lock
+                    let guard = file2.lock_shared_guard().await.unwrap();
+                    handle.join().unwrap().unwrap();
+                    assert_eq!(guard.len(), 8);
+                }
+            }
+        };
+    }
+}
+
+cfg_async!(
+    mod async_impl;
+    pub use async_impl::{AsyncFileLockGuard, AsyncFileLockGuardMut};
+);
+
+cfg_async_std!(
+    #[cfg(test)]
+    mod async_std_impl;
+);
+
+cfg_smol!(
+    #[cfg(test)]
+    mod smol_impl;
+);
+
+cfg_tokio!(
+    #[cfg(test)]
+    mod tokio_impl;
+);
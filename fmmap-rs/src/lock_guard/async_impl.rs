@@ -0,0 +1,7 @@
+use crate::error::Result;
+use crate::{AsyncMmapFileExt, AsyncMmapFileMutExt};
+use std::fmt::{Debug, Formatter};
+use std::mem;
+use std::ops::{Deref, DerefMut};
+
+declare_and_impl_async_file_lock_guards!("tokio");
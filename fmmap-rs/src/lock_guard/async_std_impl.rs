@@ -0,0 +1 @@
+test_async_file_lock_guards!("std_async", async_std, async_std::test);
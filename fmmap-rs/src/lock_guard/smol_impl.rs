@@ -0,0 +1 @@
+test_async_file_lock_guards!("smol_async", smol, smol_potat::test);
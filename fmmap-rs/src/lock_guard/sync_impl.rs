@@ -0,0 +1,158 @@
+use crate::error::Result;
+use crate::{MmapFileExt, MmapFileMutExt};
+use std::fmt::{Debug, Formatter};
+use std::mem;
+use std::ops::{Deref, DerefMut};
+
+/// FileLockGuard holds a shared or exclusive lock of the file,
+/// the file is unlocked when the guard is dropped.
+///
+/// The guard derefs to the data of the mmap file, see [`MmapFile::lock_shared_guard`]
+/// and [`MmapFileMut::lock_shared_guard`].
+///
+/// The lock belongs to the file handle rather than to the guard, so the guard borrows
+/// the mmap file mutably, a second guard can not be taken, nor the file be unlocked,
+/// while the guard is alive.
+///
+/// ```compile_fail
+/// use fmmap::MmapFile;
+///
+/// let mut file = MmapFile::memory_from_vec("lock_guard.mem", vec![0; 8]);
+/// let guard1 = file.lock_exclusive_guard().unwrap();
+/// // downgrading or releasing the lock of guard1 is rejected
+/// let guard2 = file.lock_shared_guard().unwrap();
+/// assert_eq!(guard1.len(), guard2.len());
+/// ```
+///
+/// [`MmapFile::lock_shared_guard`]: struct.MmapFile.html#method.lock_shared_guard
+/// [`MmapFileMut::lock_shared_guard`]: struct.MmapFileMut.html#method.lock_shared_guard
+pub struct FileLockGuard<'a, F: MmapFileExt + ?Sized> {
+    file: &'a mut F,
+}
+
+impl<'a, F: MmapFileExt + ?Sized> FileLockGuard<'a, F> {
+    /// Wraps a file which is already locked.
+    #[inline]
+    pub(crate) fn new(file: &'a mut F) -> Self {
+        Self { file }
+    }
+
+    /// Unlocks the file, returns the error if unlocking fails,
+    /// while dropping the guard ignores the error.
+    pub fn unlock(self) -> Result<()> {
+        let rst = self.file.unlock();
+        mem::forget(self);
+        rst
+    }
+}
+
+impl<F: MmapFileExt + ?Sized> Deref for FileLockGuard<'_, F> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.file.as_slice()
+    }
+}
+
+impl<F: MmapFileExt + ?Sized> Drop for FileLockGuard<'_, F> {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+impl<F: MmapFileExt + ?Sized> Debug for FileLockGuard<'_, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileLockGuard")
+            .field("path", &self.file.path())
+            .finish()
+    }
+}
+
+/// FileLockGuardMut holds an exclusive lock of the file,
+/// the file is unlocked when the guard is dropped.
+///
+/// The guard derefs mutably to the data of the mmap file, see [`MmapFileMut::lock_exclusive_guard`].
+///
+/// [`MmapFileMut::lock_exclusive_guard`]: struct.MmapFileMut.html#method.lock_exclusive_guard
+pub struct FileLockGuardMut<'a, F: MmapFileExt + MmapFileMutExt + ?Sized> {
+    file: &'a mut F,
+}
+
+impl<'a, F: MmapFileExt + MmapFileMutExt + ?Sized> FileLockGuardMut<'a, F> {
+    /// Wraps a file which is already locked.
+    #[inline]
+    pub(crate) fn new(file: &'a mut F) -> Self {
+        Self { file }
+    }
+
+    /// Unlocks the file, returns the error if unlocking fails,
+    /// while dropping the guard ignores the error.
+    pub fn unlock(self) -> Result<()> {
+        let rst = self.file.unlock();
+        mem::forget(self);
+        rst
+    }
+}
+
+impl<F: MmapFileExt + MmapFileMutExt + ?Sized> Deref for FileLockGuardMut<'_, F> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.file.as_slice()
+    }
+}
+
+impl<F: MmapFileExt + MmapFileMutExt + ?Sized> DerefMut for FileLockGuardMut<'_, F> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.file.as_mut_slice()
+    }
+}
+
+impl<F: MmapFileExt + MmapFileMutExt + ?Sized> Drop for FileLockGuardMut<'_, F> {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+impl<F: MmapFileExt + MmapFileMutExt + ?Sized> Debug for FileLockGuardMut<'_, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileLockGuardMut")
+            .field("path", &self.file.path())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MmapFile, MmapFileExt, MmapFileMut, Options};
+    use scopeguard::defer;
+
+    #[test]
+    fn test_lock_guard() {
+        let path = "lock_guard_test.txt";
+        let mut file1 =
+            MmapFileMut::create_with_options(path, Options::new().max_size(8)).unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        let mut file2 = MmapFile::open(path).unwrap();
+
+        {
+            let mut guard = file1.lock_exclusive_guard().unwrap();
+            guard[..4].copy_from_slice(b"abcd");
+            assert_eq!(&guard[..4], b"abcd");
+            file2.try_lock_shared().unwrap_err();
+        }
+
+        // the exclusive lock is released on drop
+        let guard = file2.lock_shared_guard().unwrap();
+        assert_eq!(&guard[..4], b"abcd");
+        file1.try_lock_exclusive().unwrap_err();
+        let guard1 = file1.lock_shared_guard().unwrap();
+        guard.unlock().unwrap();
+        file2.try_lock_exclusive().unwrap_err();
+        drop(guard1);
+        file2.lock_exclusive_guard().unwrap().unlock().unwrap();
+    }
+}
@@ -0,0 +1 @@
+test_async_file_lock_guards!("tokio_async", tokio, tokio::test);
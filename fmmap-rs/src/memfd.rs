@@ -0,0 +1,73 @@
+use std::ops::{BitOr, BitOrAssign};
+
+/// The seals of a memfd, which restrict the operations on the file, see [`MmapFileMut::seal`].
+///
+/// The seals can be combined with `|`, e.g. `SealFlags::SHRINK | SealFlags::GROW`.
+///
+/// [`MmapFileMut::seal`]: struct.MmapFileMut.html#method.seal
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SealFlags(u32);
+
+impl SealFlags {
+    /// `F_SEAL_SEAL`, no more seals can be added.
+    pub const SEAL: Self = Self(0x0001);
+    /// `F_SEAL_SHRINK`, the file cannot be shrunk.
+    pub const SHRINK: Self = Self(0x0002);
+    /// `F_SEAL_GROW`, the file cannot be grown.
+    pub const GROW: Self = Self(0x0004);
+    /// `F_SEAL_WRITE`, the content of the file cannot be modified.
+    ///
+    /// This seal cannot be added while there are writable shared mappings of the file.
+    pub const WRITE: Self = Self(0x0008);
+
+    /// Returns the empty set of seals.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the raw `F_SEAL_*` bits.
+    #[inline]
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns whether there are no seals.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether all the seals in `other` are also in `self`.
+    #[inline]
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[cfg(all(feature = "sync", any(target_os = "linux", target_os = "android")))]
+    #[inline]
+    pub(crate) const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+}
+
+impl BitOr for SealFlags {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for SealFlags {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[cfg(all(feature = "sync", any(target_os = "linux", target_os = "android")))]
+mod sync_impl;
+#[cfg(all(feature = "sync", any(target_os = "linux", target_os = "android")))]
+pub use sync_impl::{MemfdMmapFile, MemfdMmapFileMut};
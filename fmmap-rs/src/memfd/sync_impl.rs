@@ -0,0 +1,387 @@
+use crate::disk::{DiskMmapFile, DiskMmapFileMut};
+use crate::error::{Error, ErrorKind, Result};
+use crate::{
+    Advice, MetaData, MmapFileExt, MmapFileMutExt, Options, RangeLockGuard, SealFlags,
+    UncheckedAdvice,
+};
+use memmapix::Mmap;
+use rustix::fs::MemfdFlags;
+use std::fs::File;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, OwnedFd};
+use std::path::Path;
+
+/// Adds `seals` to the memfd `file` (`F_ADD_SEALS`).
+fn add_seals(file: &File, seals: SealFlags) -> Result<()> {
+    rustix::fs::fcntl_add_seals(file, rustix::fs::SealFlags::from_bits_retain(seals.bits()))
+        .map_err(|e| Error::new(ErrorKind::SealFailed, std::io::Error::from(e)))
+}
+
+/// Returns the seals of `file` (`F_GET_SEALS`), or `None` if `file` is not a memfd.
+fn get_seals(file: &File) -> Option<SealFlags> {
+    rustix::fs::fcntl_get_seals(file)
+        .ok()
+        .map(|seals| SealFlags::from_bits(seals.bits()))
+}
+
+macro_rules! impl_memfd_mmap_file_ext {
+    ($name: ident) => {
+        impl MmapFileExt for $name {
+            #[inline]
+            fn len(&self) -> usize {
+                self.disk.len()
+            }
+
+            #[inline]
+            fn as_slice(&self) -> &[u8] {
+                self.disk.as_slice()
+            }
+
+            /// A memfd has no path in the file system, returns an empty path.
+            #[inline]
+            fn path(&self) -> &Path {
+                self.disk.path()
+            }
+
+            fn metadata(&self) -> Result<MetaData> {
+                self.disk
+                    .metadata()
+                    .map(|meta| meta.with_memfd_seals(get_seals(&self.disk.file)))
+            }
+
+            #[inline]
+            fn is_exec(&self) -> bool {
+                self.disk.is_exec()
+            }
+
+            #[inline]
+            fn lock_exclusive(&self) -> Result<()> {
+                self.disk.lock_exclusive()
+            }
+
+            #[inline]
+            fn lock_shared(&self) -> Result<()> {
+                self.disk.lock_shared()
+            }
+
+            #[inline]
+            fn try_lock_exclusive(&self) -> Result<()> {
+                self.disk.try_lock_exclusive()
+            }
+
+            #[inline]
+            fn try_lock_shared(&self) -> Result<()> {
+                self.disk.try_lock_shared()
+            }
+
+            #[inline]
+            fn unlock(&self) -> Result<()> {
+                self.disk.unlock()
+            }
+
+            #[inline]
+            fn lock_range_shared(&self, offset: u64, len: u64) -> Result<RangeLockGuard> {
+                self.disk.lock_range_shared(offset, len)
+            }
+
+            #[inline]
+            fn lock_range_exclusive(&self, offset: u64, len: u64) -> Result<RangeLockGuard> {
+                self.disk.lock_range_exclusive(offset, len)
+            }
+
+            #[inline]
+            fn try_lock_range_shared(&self, offset: u64, len: u64) -> Result<RangeLockGuard> {
+                self.disk.try_lock_range_shared(offset, len)
+            }
+
+            #[inline]
+            fn try_lock_range_exclusive(&self, offset: u64, len: u64) -> Result<RangeLockGuard> {
+                self.disk.try_lock_range_exclusive(offset, len)
+            }
+
+            #[inline]
+            fn advise(&self, advice: Advice) -> Result<()> {
+                self.disk.advise(advice)
+            }
+
+            #[inline]
+            fn advise_range(&self, advice: Advice, offset: usize, len: usize) -> Result<()> {
+                self.disk.advise_range(advice, offset, len)
+            }
+
+            #[inline]
+            unsafe fn unchecked_advise_range(
+                &self,
+                advice: UncheckedAdvice,
+                offset: usize,
+                len: usize,
+            ) -> Result<()> {
+                self.disk.unchecked_advise_range(advice, offset, len)
+            }
+
+            #[inline]
+            fn resident_pages(&self) -> Result<usize> {
+                self.disk.resident_pages()
+            }
+
+            #[inline]
+            fn residency_ratio(&self, offset: usize, len: usize) -> Result<f64> {
+                self.disk.residency_ratio(offset, len)
+            }
+        }
+
+        impl $name {
+            /// Adds `seals` to the memfd (`F_ADD_SEALS`), the seals can never be removed.
+            ///
+            /// Failures, e.g. the memfd is sealed with [`SealFlags::SEAL`], are reported
+            /// as [`ErrorKind::SealFailed`].
+            ///
+            /// [`SealFlags::SEAL`]: fmmap::SealFlags::SEAL
+            /// [`ErrorKind::SealFailed`]: fmmap::error::ErrorKind::SealFailed
+            #[inline]
+            pub fn seal(&self, seals: SealFlags) -> Result<()> {
+                add_seals(&self.disk.file, seals)
+            }
+        }
+
+        impl AsFd for $name {
+            #[inline]
+            fn as_fd(&self) -> BorrowedFd<'_> {
+                self.disk.file.as_fd()
+            }
+        }
+    };
+}
+
+/// MemfdMmapFile contains a read-only memory map of a memfd,
+/// which is usually received from another process, see [`MemfdMmapFile::from_fd`].
+///
+/// [`MemfdMmapFile::from_fd`]: struct.MemfdMmapFile.html#method.from_fd
+pub struct MemfdMmapFile {
+    disk: DiskMmapFile,
+}
+
+impl_memfd_mmap_file_ext!(MemfdMmapFile);
+
+impl MemfdMmapFile {
+    /// Mmap the whole file of `fd` read-only, e.g. a memfd received from another process.
+    ///
+    /// The seals of a memfd are reported by [`MetaData::memfd_seals`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::{MmapFileExt, MmapFileMutExt, SealFlags};
+    /// use fmmap::raw::{MemfdMmapFile, MemfdMmapFileMut};
+    /// use std::os::unix::io::AsFd;
+    ///
+    /// let mut file = MemfdMmapFileMut::create("memfd_from_fd", 12).unwrap();
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// let file = file.freeze().unwrap();
+    /// file.seal(SealFlags::SHRINK | SealFlags::GROW | SealFlags::WRITE).unwrap();
+    ///
+    /// let fd = file.as_fd().try_clone_to_owned().unwrap();
+    /// let file = MemfdMmapFile::from_fd(fd).unwrap();
+    /// assert_eq!(file.as_slice(), "some data...".as_bytes());
+    /// ```
+    ///
+    /// [`MetaData::memfd_seals`]: fmmap::MetaData::memfd_seals
+    pub fn from_fd(fd: OwnedFd) -> Result<Self> {
+        Ok(Self {
+            disk: DiskMmapFile::from_file(File::from(fd), None, Options::new())?,
+        })
+    }
+}
+
+/// MemfdMmapFileMut contains a writable memory map of a memfd (`memfd_create`),
+/// which lives in memory and can be shared with another process by its fd.
+///
+/// The memfd allows sealing, see [`seal`].
+///
+/// [`seal`]: struct.MemfdMmapFileMut.html#method.seal
+pub struct MemfdMmapFileMut {
+    disk: DiskMmapFileMut,
+}
+
+impl_memfd_mmap_file_ext!(MemfdMmapFileMut);
+
+impl MmapFileMutExt for MemfdMmapFileMut {
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.disk.as_mut_slice()
+    }
+
+    #[inline]
+    fn is_cow(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn flush(&self) -> Result<()> {
+        self.disk.flush()
+    }
+
+    #[inline]
+    fn flush_async(&self) -> Result<()> {
+        self.disk.flush_async()
+    }
+
+    #[inline]
+    fn flush_range(&self, offset: usize, len: usize) -> Result<()> {
+        self.disk.flush_range(offset, len)
+    }
+
+    #[inline]
+    fn flush_async_range(&self, offset: usize, len: usize) -> Result<()> {
+        self.disk.flush_async_range(offset, len)
+    }
+
+    /// Truncates the memfd and remaps it, which fails if the memfd is sealed
+    /// with [`SealFlags::SHRINK`] or [`SealFlags::GROW`].
+    ///
+    /// [`SealFlags::SHRINK`]: fmmap::SealFlags::SHRINK
+    /// [`SealFlags::GROW`]: fmmap::SealFlags::GROW
+    #[inline]
+    fn truncate(&mut self, max_sz: u64) -> Result<()> {
+        self.disk.truncate(max_sz)
+    }
+
+    /// The memfd has no path, it is released when the last fd and mapping are closed.
+    #[inline]
+    fn drop_remove(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn close_with_truncate(self, max_sz: i64) -> Result<()> {
+        let DiskMmapFileMut { mmap, file, .. } = self.disk;
+        drop(mmap);
+        if max_sz >= 0 {
+            file.set_len(max_sz as u64)
+                .map_err(|e| Error::new(ErrorKind::TruncationFailed, e))?;
+        }
+        Ok(())
+    }
+}
+
+impl MemfdMmapFileMut {
+    /// Create a memfd (`memfd_create`) of `size` zeroed bytes and mmap it.
+    ///
+    /// `name` is only used for debugging, e.g. it is shown as `/memfd:name` in `/proc/self/fd`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::{MmapFileExt, MmapFileMutExt};
+    /// use fmmap::raw::MemfdMmapFileMut;
+    ///
+    /// let mut file = MemfdMmapFileMut::create("memfd_create", 100).unwrap();
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// assert_eq!(file.bytes(0, 12).unwrap(), "some data...".as_bytes());
+    /// ```
+    pub fn create(name: &str, size: u64) -> Result<Self> {
+        let fd = rustix::fs::memfd_create(name, MemfdFlags::CLOEXEC | MemfdFlags::ALLOW_SEALING)
+            .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, name, e))?;
+        let file = File::from(fd);
+        file.set_len(size)
+            .map_err(|e| Error::new_source_msg(ErrorKind::TruncationFailed, name, e))?;
+        Ok(Self {
+            disk: DiskMmapFileMut::from_file(file, None, Options::new())?,
+        })
+    }
+
+    /// Make the memfd mmap read-only.
+    ///
+    /// The memfd is mapped again through a read-only fd, so it can be sealed with [`SealFlags::WRITE`] after.
+    ///
+    /// The read-only fd is opened through `/proc/self/fd`. If `/proc` is not mounted, the memfd is mapped
+    /// through its writable fd instead, and sealing it with [`SealFlags::WRITE`] after fails
+    /// with [`ErrorKind::SealFailed`], the other seals still work.
+    ///
+    /// [`SealFlags::WRITE`]: fmmap::SealFlags::WRITE
+    /// [`ErrorKind::SealFailed`]: fmmap::error::ErrorKind::SealFailed
+    pub fn freeze(self) -> Result<MemfdMmapFile> {
+        let DiskMmapFileMut {
+            mmap, file, path, ..
+        } = self.disk;
+        drop(mmap);
+        // a shared mapping of a writable fd may be made writable by `mprotect`,
+        // so the kernel refuses `F_SEAL_WRITE` as long as it exists.
+        let mmap = match File::open(format!("/proc/self/fd/{}", file.as_raw_fd())) {
+            Ok(read_only) => unsafe { Mmap::map(&read_only) },
+            // `/proc` is not mounted, the memfd can still be sealed except `F_SEAL_WRITE`
+            Err(_) => unsafe { Mmap::map(&file) },
+        }
+        .map_err(|e| Error::new(ErrorKind::MmapFailed, e))?;
+        Ok(MemfdMmapFile {
+            disk: DiskMmapFile::from_mmap(mmap, file, path),
+        })
+    }
+
+    /// Transition the memfd mmap to be readable and executable.
+    pub fn freeze_exec(self) -> Result<MemfdMmapFile> {
+        Ok(MemfdMmapFile {
+            disk: self.disk.freeze_exec()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MmapFile, MmapFileMut};
+
+    #[test]
+    fn test_memfd() {
+        let mut file = MmapFileMut::memfd("test_memfd", 100).unwrap();
+        assert_eq!(file.len(), 100);
+        assert_eq!(file.path(), Path::new(""));
+        assert_eq!(
+            file.metadata().unwrap().memfd_seals(),
+            Some(SealFlags::empty())
+        );
+        file.write_all("some data...".as_bytes(), 0).unwrap();
+        file.truncate(200).unwrap();
+        assert_eq!(file.bytes(0, 12).unwrap(), "some data...".as_bytes());
+
+        // the size cannot change after sealing
+        file.seal(SealFlags::SHRINK | SealFlags::GROW).unwrap();
+        assert_eq!(
+            file.truncate(100).unwrap_err().kind(),
+            ErrorKind::TruncationFailed
+        );
+        // a writable mapping blocks sealing the content
+        assert_eq!(
+            file.seal(SealFlags::WRITE).unwrap_err().kind(),
+            ErrorKind::SealFailed
+        );
+
+        let file = file.freeze().unwrap();
+        file.seal(SealFlags::WRITE | SealFlags::SEAL).unwrap();
+        let seals = file.metadata().unwrap().memfd_seals().unwrap();
+        assert!(seals.contains(SealFlags::SHRINK | SealFlags::GROW | SealFlags::WRITE));
+        assert_eq!(
+            file.seal(SealFlags::SHRINK).unwrap_err().kind(),
+            ErrorKind::SealFailed
+        );
+
+        // map the duplicated fd like a receiving process
+        let fd = file.as_memfd().unwrap().try_clone_to_owned().unwrap();
+        let received = MmapFile::from_fd(fd).unwrap();
+        assert_eq!(received.len(), 200);
+        assert_eq!(received.path(), Path::new(""));
+        assert_eq!(received.bytes(0, 12).unwrap(), "some data...".as_bytes());
+        assert_eq!(received.metadata().unwrap().memfd_seals(), Some(seals));
+        drop(file);
+        assert_eq!(received.bytes(0, 12).unwrap(), "some data...".as_bytes());
+    }
+
+    #[test]
+    fn test_memfd_not_memfd() {
+        let file = MmapFileMut::memory_from_vec("memory.txt", vec![0; 10]);
+        assert!(file.as_memfd().is_none());
+        assert!(file.metadata().unwrap().memfd_seals().is_none());
+        assert_eq!(
+            file.seal(SealFlags::SEAL).unwrap_err().kind(),
+            ErrorKind::SealFailed
+        );
+    }
+}
@@ -1,123 +1,144 @@
 macro_rules! define_impl_constructor_for_mmap_file {
-    ($name: ident, $name_str: literal, $path_str: literal) => {
+    ($name: ident $(<$runtime: ident>)?, $name_str: literal, $path_str: literal $(, [$($feature: literal => $doc_test_runtime: literal),*])?) => {
         /// Use [`Bytes`] to mock a mmap, which is useful for test and in-memory storage engine.
         ///
         /// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
         #[derive(Clone, PartialEq, Eq)]
-        pub struct $name {
+        pub struct $name $(<$runtime: crate::AsyncRuntime>)? {
             mmap: Bytes,
             path: PathBuf,
             create_at: SystemTime,
+            $(_runtime: std::marker::PhantomData<fn() -> $runtime>,)?
         }
 
-        impl $name {
+        impl $(<$runtime: crate::AsyncRuntime>)? $name $(<$runtime>)? {
             #[doc = concat!("Create a ", $name_str)]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = "use bytes::{BufMut, BytesMut};"]
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
             #[doc = "let mut data = BytesMut::with_capacity(100);"]
             #[doc = "data.put_slice(\"some data...\".as_bytes());"]
-            #[doc = concat!($name_str, "::new(\"foo.mem\", data.freeze());")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::new(\"foo.mem\", data.freeze());")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn new<P: AsRef<Path>>(path: P, data: Bytes) -> Self {
                 Self {
                     mmap: data,
                     path: path.as_ref().to_path_buf(),
                     create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = concat!("Create a ", $name_str, " from Vec")]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
             #[doc = "let data = (0..=255u8).collect::<Vec<_>>();"]
-            #[doc = concat!($name_str, "::from_vec(\"foo.mem\", data);")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::from_vec(\"foo.mem\", data);")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn from_vec<P: AsRef<Path>>(path: P, src: Vec<u8>) -> Self {
                 Self {
                     mmap: Bytes::from(src),
                     path: path.as_ref().to_path_buf(),
                     create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = concat!("Create a ", $name_str, " from String")]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
             #[doc = "let data: &'static str = \"some data...\";"]
-            #[doc = concat!($name_str, "::from_string(\"foo.mem\", data.to_string());")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::from_string(\"foo.mem\", data.to_string());")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn from_string<P: AsRef<Path>>(path: P, src: String) -> Self {
                 Self {
                     mmap: Bytes::from(src),
                     path: path.as_ref().to_path_buf(),
-                    create_at: SystemTime::now()
+                    create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = concat!("Create a ", $name_str, " from static slice")]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = "use bytes::Bytes;"]
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
             #[doc = "let data: &'static [u8] = \"some data...\".as_bytes();"]
-            #[doc = concat!($name_str, "::from_slice(\"foo.mem\", data);")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::from_slice(\"foo.mem\", data);")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn from_slice<P: AsRef<Path>>(path: P, src: &'static [u8]) -> Self {
                 Self {
                     mmap: Bytes::from(src),
                     path: path.as_ref().to_path_buf(),
-                    create_at: SystemTime::now()
+                    create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = concat!("Create a ", $name_str, " from static str")]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = "use bytes::Bytes;"]
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
             #[doc = "let data: &'static str = \"some data...\";"]
-            #[doc = concat!($name_str, "::from_str(\"foo.mem\", data);")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::from_str(\"foo.mem\", data);")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn from_str<P: AsRef<Path>>(path: P, src: &'static str) -> Self {
                 Self {
                     mmap: Bytes::from(src),
                     path: path.as_ref().to_path_buf(),
-                    create_at: SystemTime::now()
+                    create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = concat!("Create a ", $name_str, " by copy from slice")]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
-            #[doc = concat!($name_str, "::copy_from_slice(\"foo.mem\", \"some data...\".as_bytes());")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::copy_from_slice(\"foo.mem\", \"some data...\".as_bytes());")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn copy_from_slice<P: AsRef<Path>>(path: P, src: &[u8]) -> Self {
                 Self {
                     mmap: Bytes::copy_from_slice(src),
                     path: path.as_ref().to_path_buf(),
                     create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = "Returns the inner bytes"]
             #[doc = "# Examples"]
-            #[doc =  "```rust"]
+            #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = "use bytes::Bytes;"]
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
-            #[doc = concat!("let b1 = ", $name_str, "::copy_from_slice(\"foo.mem\", \"some data...\".as_bytes()).into_bytes();")]
+            #[doc = concat!("let b1 = ", $name_str, $("::<", stringify!($runtime), ">", )?"::copy_from_slice(\"foo.mem\", \"some data...\".as_bytes()).into_bytes();")]
             #[doc = "assert_eq!(b1, Bytes::copy_from_slice(\"some data...\".as_bytes()));"]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn into_bytes(self) -> Bytes {
                 self.mmap
@@ -127,7 +148,7 @@ macro_rules! define_impl_constructor_for_mmap_file {
 }
 
 macro_rules! define_and_impl_constructor_for_mmap_file_mut {
-    ($name: ident, $name_str: literal, $immutable: ident, $immutable_str: literal, $trait_str: literal, $path_str: literal) => {
+    ($name: ident $(<$runtime: ident>)?, $name_str: literal, $immutable: ident, $immutable_str: literal, $trait_str: literal, $path_str: literal $(, [$($feature: literal => $doc_test_runtime: literal),*])?) => {
         #[doc = "Use [`BytesMut`] to mock a mmap, which is useful for test and in-memory storage engine."]
         #[doc = ""]
         #[doc = "# Notes"]
@@ -140,115 +161,136 @@ macro_rules! define_and_impl_constructor_for_mmap_file_mut {
         #[doc = "[`truncate`]: structs.MemoryMmapFileMut.html#methods.truncate"]
         #[doc = "[`BytesMut`]: https://docs.rs/bytes/1.1.0/bytes/struct.BytesMut.html"]
         #[derive(Clone, PartialEq, Eq)]
-        pub struct $name {
+        pub struct $name $(<$runtime: crate::AsyncRuntime>)? {
             mmap: BytesMut,
             path: PathBuf,
             create_at: SystemTime,
+            $(_runtime: std::marker::PhantomData<fn() -> $runtime>,)?
         }
 
-        impl $name {
+        impl $(<$runtime: crate::AsyncRuntime>)? $name $(<$runtime>)? {
             #[doc = concat!("Create a ", $name_str)]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
-            #[doc = concat!($name_str, "::new(\"foo.mem\");")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::new(\"foo.mem\");")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn new<P: AsRef<Path>>(path: P) -> Self {
                 Self {
                     mmap: BytesMut::new(),
                     path: path.as_ref().to_path_buf(),
                     create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = concat!("Create a ", $name_str, "with capacity")]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = concat!("use fmmap::raw::",$path_str, $name_str, ";")]
             #[doc = ""]
-            #[doc = concat!($name_str, "::with_capacity(\"foo.mem\", 1000);")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::with_capacity(\"foo.mem\", 1000);")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn with_capacity<P: AsRef<Path>>(path: P, cap: usize) -> Self {
                 Self {
                     mmap: BytesMut::with_capacity(cap),
                     path: path.as_ref().to_path_buf(),
                     create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = concat!("Create a ", $name_str, " from Vec")]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
             #[doc = "let data = (0..=255u8).collect::<Vec<_>>();"]
-            #[doc = concat!($name_str, "::from_vec(\"foo.mem\", data);")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::from_vec(\"foo.mem\", data);")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn from_vec<P: AsRef<Path>>(path: P, src: Vec<u8>) -> Self {
                 Self {
                     mmap: BytesMut::from_iter(src),
                     path: path.as_ref().to_path_buf(),
                     create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = concat!("Create a ", $name_str, " from String")]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
             #[doc = "let data: &'static str = \"some data...\";"]
-            #[doc = concat!($name_str, "::from_string(\"foo.mem\", data.to_string());")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::from_string(\"foo.mem\", data.to_string());")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn from_string<P: AsRef<Path>>(path: P, src: String) -> Self {
                 Self {
                     mmap: BytesMut::from(src.as_bytes()),
                     path: path.as_ref().to_path_buf(),
-                    create_at: SystemTime::now()
+                    create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = concat!("Create a ", $name_str, " from static str")]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = "use bytes::Bytes;"]
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
             #[doc = "let data: &'static str = \"some data...\";"]
-            #[doc = concat!($name_str, "::from_str(\"foo.mem\", data);")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::from_str(\"foo.mem\", data);")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn from_str<P: AsRef<Path>>(path: P, src: &'static str) -> Self {
                 Self {
                     mmap: BytesMut::from(src),
                     path: path.as_ref().to_path_buf(),
-                    create_at: SystemTime::now()
+                    create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = concat!("Create a ", $name_str, " by from slice")]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
-            #[doc = concat!($name_str, "::from_slice(\"foo.mem\", \"some data...\".as_bytes());")]
+            #[doc = concat!($name_str, $("::<", stringify!($runtime), ">", )?"::from_slice(\"foo.mem\", \"some data...\".as_bytes());")]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn from_slice<P: AsRef<Path>>(path: P, src: &[u8]) -> Self {
                 Self {
                     mmap: BytesMut::from(src),
                     path: path.as_ref().to_path_buf(),
-                    create_at: SystemTime::now()
+                    create_at: SystemTime::now(),
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
             #[doc = "Returns the inner mutable bytes"]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = "use bytes::BytesMut;"]
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
-            #[doc = concat!("let b1 = ", $name_str, "::from_slice(\"foo.mem\", \"some data...\".as_bytes()).into_bytes_mut();")]
+            #[doc = concat!("let b1 = ", $name_str, $("::<", stringify!($runtime), ">", )?"::from_slice(\"foo.mem\", \"some data...\".as_bytes()).into_bytes_mut();")]
             #[doc = "assert_eq!(b1, BytesMut::from(\"some data...\".as_bytes()));"]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn into_bytes_mut(self) -> BytesMut {
                 self.mmap
@@ -257,11 +299,13 @@ macro_rules! define_and_impl_constructor_for_mmap_file_mut {
             #[doc = "Returns the inner bytes"]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = "use bytes::Bytes;"]
             #[doc = concat!("use fmmap::raw::", $path_str, $name_str, ";")]
             #[doc = ""]
-            #[doc = concat!("let b1 = ", $name_str, "::from_slice(\"foo.mem\", \"some data...\".as_bytes()).into_bytes();")]
+            #[doc = concat!("let b1 = ", $name_str, $("::<", stringify!($runtime), ">", )?"::from_slice(\"foo.mem\", \"some data...\".as_bytes()).into_bytes();")]
             #[doc = "assert_eq!(b1, Bytes::copy_from_slice(\"some data...\".as_bytes()));"]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             pub fn into_bytes(self) -> Bytes {
                 self.mmap.freeze()
@@ -271,23 +315,26 @@ macro_rules! define_and_impl_constructor_for_mmap_file_mut {
             #[doc = "Make the memory mmap file immutable"]
             #[doc = "# Examples"]
             #[doc = "```rust"]
+            $(#[doc = concat!("# fn doc_test<", stringify!($runtime), ": fmmap::AsyncRuntime>() {")])?
             #[doc = "use bytes::Bytes;"]
             #[doc = concat!("use fmmap::", $path_str, $trait_str, ";")]
             #[doc = concat!("use fmmap::raw::", $path_str, "{", $name_str, ",", $immutable_str, "};")]
             #[doc = ""]
-            #[doc = concat!("let b1 = ", $name_str, "::from_string(\"foo.mem\", \"some data...\".to_string()).freeze();")]
-            #[doc = concat!("let b2 = ", $immutable_str, "::from_string(\"foo.mem\", \"some data...\".to_string());")]
+            #[doc = concat!("let b1 = ", $name_str, $("::<", stringify!($runtime), ">", )?"::from_string(\"foo.mem\", \"some data...\".to_string()).freeze();")]
+            #[doc = concat!("let b2 = ", $immutable_str, $("::<", stringify!($runtime), ">", )?"::from_string(\"foo.mem\", \"some data...\".to_string());")]
             #[doc = "assert!(!b1.is_exec());"]
             #[doc = "assert_eq!(b1.len(), b2.len());"]
             #[doc = "assert_eq!(b1.as_slice(), b2.as_slice());"]
             #[doc = "assert_eq!(b1.path_string(), b2.path_string());"]
+            $(#[doc = "# }"] $(#[cfg_attr(feature = $feature, doc = concat!("# doc_test::<", $doc_test_runtime, ">();"))])*)?
             #[doc = "```"]
             #[inline]
-            pub fn freeze(self) -> $immutable {
+            pub fn freeze(self) -> $immutable $(<$runtime>)? {
                 $immutable {
                     mmap: self.mmap.freeze(),
                     path: self.path,
                     create_at: self.create_at,
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
         }
@@ -322,6 +369,12 @@ cfg_sync! {
                 }
 
                 noop_file_lock!();
+
+                noop_range_lock!();
+
+                noop_advise!();
+
+                memory_residency!();
             }
         };
     }
@@ -333,7 +386,9 @@ cfg_async! {
     macro_rules! impl_async_mmap_file_ext {
         ($name: ident) => {
             #[async_trait]
-            impl AsyncMmapFileExt for $name {
+            impl<R: AsyncRuntime> AsyncMmapFileExt for $name<R> {
+                type Runtime = R;
+
                 fn len(&self) -> usize {
                     self.mmap.len()
                 }
@@ -352,6 +407,27 @@ cfg_async! {
 
                 noop_file_lock!();
 
+                noop_range_lock!();
+
+                noop_advise!();
+
+                memory_residency!();
+
+                #[inline]
+                async fn lock_exclusive_offload(&self) -> crate::error::Result<()> {
+                    Ok(())
+                }
+
+                #[inline]
+                async fn lock_shared_offload(&self) -> crate::error::Result<()> {
+                    Ok(())
+                }
+
+                #[inline]
+                async fn read_at_async(&self, dst: &mut [u8], offset: usize) -> crate::error::Result<usize> {
+                    Ok(crate::offload::copy_at(self.as_slice(), dst, offset))
+                }
+
                 async fn metadata(&self) -> crate::error::Result<MetaData> {
                     Ok(MetaData::memory(MemoryMetaData::new(
                         self.mmap.len() as u64,
@@ -365,7 +441,7 @@ cfg_async! {
     macro_rules! impl_async_mmap_file_mut_ext {
         () => {
             #[async_trait]
-            impl AsyncMmapFileMutExt for AsyncMemoryMmapFileMut {
+            impl<R: AsyncRuntime> AsyncMmapFileMutExt for AsyncMemoryMmapFileMut<R> {
                 #[inline]
                 fn as_mut_slice(&mut self) -> &mut [u8] {
                     self.mmap.as_mut()
@@ -378,6 +454,16 @@ cfg_async! {
 
                 noop_flush!();
 
+                #[inline]
+                async fn flush_offload(&self) -> crate::error::Result<()> {
+                    Ok(())
+                }
+
+                #[inline]
+                async fn flush_range_offload(&self, _offset: usize, _len: usize) -> crate::error::Result<()> {
+                    Ok(())
+                }
+
                 #[inline]
                 async fn truncate(&mut self, max_sz: u64) -> crate::error::Result<()> {
                     self.mmap.resize(max_sz as usize, 0);
@@ -398,14 +484,7 @@ cfg_async! {
     }
 }
 
-cfg_async_std!(
-    pub(crate) mod async_std_impl;
-);
-
-cfg_smol!(
-    pub(crate) mod smol_impl;
-);
-
-cfg_tokio!(
-    pub(crate) mod tokio_impl;
+cfg_async!(
+    mod async_impl;
+    pub use async_impl::{AsyncMemoryMmapFile, AsyncMemoryMmapFileMut};
 );
@@ -0,0 +1,23 @@
+use crate::metadata::MemoryMetaData;
+use crate::{AsyncMmapFileExt, AsyncMmapFileMutExt, AsyncRuntime, MetaData};
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+define_impl_constructor_for_mmap_file!(AsyncMemoryMmapFile<R>, "AsyncMemoryMmapFile", "", [
+    "tokio" => "fmmap::tokio::TokioRuntime",
+    "smol" => "fmmap::smol::SmolRuntime",
+    "async-std" => "fmmap::async_std::AsyncStdRuntime"
+]);
+
+impl_async_mmap_file_ext!(AsyncMemoryMmapFile);
+
+define_and_impl_constructor_for_mmap_file_mut!(AsyncMemoryMmapFileMut<R>, "AsyncMemoryMmapFileMut", AsyncMemoryMmapFile, "AsyncMemoryMmapFile", "AsyncMmapFileExt", "", [
+    "tokio" => "fmmap::tokio::TokioRuntime",
+    "smol" => "fmmap::smol::SmolRuntime",
+    "async-std" => "fmmap::async_std::AsyncStdRuntime"
+]);
+
+impl_async_mmap_file_ext!(AsyncMemoryMmapFileMut);
+impl_async_mmap_file_mut_ext!();
@@ -9,6 +9,7 @@ cfg_unix!(
 );
 
 use crate::error::Error;
+use crate::{HugePageSize, SealFlags};
 use std::fs::Metadata;
 use std::ops::{Deref, DerefMut};
 use std::time::SystemTime;
@@ -67,9 +68,10 @@ impl DerefMut for DiskMetaData {
 /// This structure is returned from the metadata or
 /// symlink_metadata function or method and represents
 /// known metadata about a file such as its permissions, size, modification times, etc
-#[repr(transparent)]
 pub struct MetaData {
     inner: MetaDataInner,
+    huge_pages: Option<HugePageSize>,
+    memfd_seals: Option<SealFlags>,
 }
 
 #[enum_dispatch(MetaDataExt)]
@@ -83,18 +85,55 @@ impl MetaData {
     pub(crate) fn empty(meta: EmptyMetaData) -> Self {
         Self {
             inner: MetaDataInner::Empty(meta),
+            huge_pages: None,
+            memfd_seals: None,
         }
     }
 
     pub(crate) fn memory(meta: MemoryMetaData) -> Self {
         Self {
             inner: MetaDataInner::Memory(meta),
+            huge_pages: None,
+            memfd_seals: None,
         }
     }
 
     pub(crate) fn disk(meta: Metadata) -> Self {
         Self {
             inner: MetaDataInner::Disk(DiskMetaData::new(meta)),
+            huge_pages: None,
+            memfd_seals: None,
         }
     }
+
+    #[inline]
+    pub(crate) fn with_huge_pages(mut self, huge_pages: Option<HugePageSize>) -> Self {
+        self.huge_pages = huge_pages;
+        self
+    }
+
+    #[cfg(all(feature = "sync", any(target_os = "linux", target_os = "android")))]
+    #[inline]
+    pub(crate) fn with_memfd_seals(mut self, seals: Option<SealFlags>) -> Self {
+        self.memfd_seals = seals;
+        self
+    }
+
+    /// Returns the huge pages backing the memory map, see [`Options::huge_pages`],
+    /// or `None` if the memory map uses normal pages.
+    ///
+    /// [`Options::huge_pages`]: struct.Options.html#method.huge_pages
+    #[inline]
+    pub fn huge_pages(&self) -> Option<HugePageSize> {
+        self.huge_pages
+    }
+
+    /// Returns the seals of the memfd backing the memory map, see [`MmapFileMut::memfd`],
+    /// or `None` if the memory map is not backed by a memfd.
+    ///
+    /// [`MmapFileMut::memfd`]: struct.MmapFileMut.html#method.memfd
+    #[inline]
+    pub fn memfd_seals(&self) -> Option<SealFlags> {
+        self.memfd_seals
+    }
 }
@@ -12,6 +12,15 @@ macro_rules! read_impl {
 }
 
 macro_rules! impl_from {
+    ($outer: ident<$runtime: ident>, $enum_inner: ident, [$($inner: ident), +$(,)?]) => {
+        $(
+        impl<$runtime: AsyncRuntime> From<$inner<$runtime>> for $outer<$runtime> {
+            fn from(file: $inner<$runtime>) -> Self {
+                $outer{ inner: <$enum_inner<$runtime>>::from(file) }
+            }
+        }
+        )*
+    };
     ($outer: ident, $enum_inner: ident, [$($inner: ident), +$(,)?]) => {
         $(
         impl From<$inner> for $outer {
@@ -23,7 +32,39 @@ macro_rules! impl_from {
     };
 }
 
+/// Returns the file name of the temporary file which is committed to `file_name`
+/// by an atomic mmap file, the temporary file is hidden and unique in the process.
+pub(crate) fn atomic_tmp_file_name(file_name: Option<&std::ffi::OsStr>) -> std::ffi::OsString {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let mut name = std::ffi::OsString::from(".");
+    if let Some(file_name) = file_name {
+        name.push(file_name);
+    }
+    name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    name
+}
+
 macro_rules! impl_from_mut {
+    ($outer: ident<$runtime: ident>, $enum_inner: ident, [$($inner: ident), +$(,)?]) => {
+        $(
+        impl<$runtime: AsyncRuntime> From<$inner<$runtime>> for $outer<$runtime> {
+            fn from(file: $inner<$runtime>) -> Self {
+                $outer{
+                    inner: <$enum_inner<$runtime>>::from(file),
+                    remove_on_drop: false,
+                    deleted: false,
+                    commit_to: None,
+                }
+            }
+        }
+        )*
+    };
     ($outer: ident, $enum_inner: ident, [$($inner: ident), +$(,)?]) => {
         $(
         impl From<$inner> for $outer {
@@ -32,6 +73,7 @@ macro_rules! impl_from_mut {
                     inner: <$enum_inner>::from(file),
                     remove_on_drop: false,
                     deleted: false,
+                    commit_to: None,
                 }
             }
         }
@@ -40,8 +82,14 @@ macro_rules! impl_from_mut {
 }
 
 macro_rules! impl_drop {
+    ($name: ident<$runtime: ident>, $inner: ident, $empty: ident) => {
+        impl_drop!(@impl [$runtime: AsyncRuntime] $name<$runtime>, $inner<$runtime>, $empty<$runtime>);
+    };
     ($name: ident, $inner: ident, $empty: ident) => {
-        impl Drop for $name {
+        impl_drop!(@impl [] $name, $inner, $empty);
+    };
+    (@impl [$($generics: tt)*] $name: ty, $inner: ty, $empty: ty) => {
+        impl<$($generics)*> Drop for $name {
             fn drop(&mut self) {
                 if self.remove_on_drop && !self.deleted {
                     let empty = <$inner>::Empty(<$empty>::default());
@@ -106,11 +154,96 @@ macro_rules! impl_file_lock {
     };
 }
 
+macro_rules! impl_range_lock {
+    () => {
+        #[inline]
+        fn lock_range_shared(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            self.inner.lock_range_shared(offset, len)
+        }
+
+        #[inline]
+        fn lock_range_exclusive(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            self.inner.lock_range_exclusive(offset, len)
+        }
+
+        #[inline]
+        fn try_lock_range_shared(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            self.inner.try_lock_range_shared(offset, len)
+        }
+
+        #[inline]
+        fn try_lock_range_exclusive(
+            &self,
+            offset: u64,
+            len: u64,
+        ) -> crate::error::Result<crate::RangeLockGuard> {
+            self.inner.try_lock_range_exclusive(offset, len)
+        }
+    };
+}
+
+macro_rules! impl_advise {
+    () => {
+        #[inline]
+        fn advise(&self, advice: crate::Advice) -> crate::error::Result<()> {
+            self.inner.advise(advice)
+        }
+
+        #[inline]
+        fn advise_range(
+            &self,
+            advice: crate::Advice,
+            offset: usize,
+            len: usize,
+        ) -> crate::error::Result<()> {
+            self.inner.advise_range(advice, offset, len)
+        }
+
+        #[inline]
+        unsafe fn unchecked_advise_range(
+            &self,
+            advice: crate::UncheckedAdvice,
+            offset: usize,
+            len: usize,
+        ) -> crate::error::Result<()> {
+            self.inner.unchecked_advise_range(advice, offset, len)
+        }
+
+        #[inline]
+        fn resident_pages(&self) -> crate::error::Result<usize> {
+            self.inner.resident_pages()
+        }
+
+        #[inline]
+        fn residency_ratio(&self, offset: usize, len: usize) -> crate::error::Result<f64> {
+            self.inner.residency_ratio(offset, len)
+        }
+    };
+}
+
 macro_rules! impl_constructor_for_memory_mmap_file {
+    ($memory_base: ident, $name: ident<$runtime: ident>, $name_str: literal, $path_str: literal) => {
+        impl_constructor_for_memory_mmap_file!(@impl [$runtime: AsyncRuntime] $memory_base<$runtime>, $name<$runtime>, $name_str, $path_str);
+    };
     ($memory_base: ident, $name: ident, $name_str: literal, $path_str: literal) => {
+        impl_constructor_for_memory_mmap_file!(@impl [] $memory_base, $name, $name_str, $path_str);
+    };
+    (@impl [$($generics: tt)*] $memory_base: ty, $name: ty, $name_str: literal, $path_str: literal) => {
         use bytes::Bytes;
 
-        impl $name {
+        impl<$($generics)*> $name {
             #[doc = concat!("Create a in-memory ", $name_str)]
             #[doc = "# Examples"]
             #[doc = "```ignore"]
@@ -190,8 +323,14 @@ macro_rules! impl_constructor_for_memory_mmap_file {
 }
 
 macro_rules! impl_constructor_for_memory_mmap_file_mut {
+    ($memory_base: ident, $name: ident<$runtime: ident>, $name_str: literal, $path_str: literal) => {
+        impl_constructor_for_memory_mmap_file_mut!(@impl [$runtime: AsyncRuntime] $memory_base<$runtime>, $name<$runtime>, $name_str, $path_str);
+    };
     ($memory_base: ident, $name: ident, $name_str: literal, $path_str: literal) => {
-        impl $name {
+        impl_constructor_for_memory_mmap_file_mut!(@impl [] $memory_base, $name, $name_str, $path_str);
+    };
+    (@impl [$($generics: tt)*] $memory_base: ty, $name: ty, $name_str: literal, $path_str: literal) => {
+        impl<$($generics)*> $name {
             #[doc = concat!("Create a in-memory ", $name_str)]
             #[doc = "# Examples"]
             #[doc = "```ignore"]
@@ -295,6 +434,10 @@ cfg_sync! {
                 }
 
                 impl_file_lock!();
+
+                impl_range_lock!();
+
+                impl_advise!();
             }
         };
     }
@@ -307,7 +450,9 @@ cfg_async! {
     macro_rules! impl_async_mmap_file_ext {
         ($name: ident) => {
             #[async_trait]
-            impl AsyncMmapFileExt for $name {
+            impl<R: AsyncRuntime> AsyncMmapFileExt for $name<R> {
+                type Runtime = R;
+
                 #[inline]
                 fn len(&self) -> usize {
                     self.inner.len()
@@ -334,6 +479,31 @@ cfg_async! {
                 }
 
                 impl_file_lock!();
+
+                #[inline]
+                async fn lock_exclusive_offload(&self) -> Result<()> {
+                    self.inner.lock_exclusive_offload().await
+                }
+
+                #[inline]
+                async fn lock_shared_offload(&self) -> Result<()> {
+                    self.inner.lock_shared_offload().await
+                }
+
+                #[inline]
+                async fn read_at_async(&self, dst: &mut [u8], offset: usize) -> Result<usize> {
+                    self.inner.read_at_async(dst, offset).await
+                }
+
+                #[cfg(all(feature = "io-uring", target_os = "linux"))]
+                #[inline]
+                async fn read_into(&self, dst: &mut [u8], offset: usize) -> Result<()> {
+                    self.inner.read_into(dst, offset).await
+                }
+
+                impl_range_lock!();
+
+                impl_advise!();
             }
         };
     }
@@ -341,7 +511,7 @@ cfg_async! {
     macro_rules! impl_async_mmap_file_mut_ext {
         ($filename_prefix: literal, $doc_test_runtime: literal, $path_str: literal) => {
             #[async_trait]
-            impl AsyncMmapFileMutExt for AsyncMmapFileMut {
+            impl<R: AsyncRuntime> AsyncMmapFileMutExt for AsyncMmapFileMut<R> {
                 #[inline]
                 fn as_mut_slice(&mut self) -> &mut [u8] {
                     self.inner.as_mut_slice()
@@ -354,6 +524,16 @@ cfg_async! {
 
                 impl_flush!();
 
+                #[inline]
+                async fn flush_offload(&self) -> Result<()> {
+                    self.inner.flush_offload().await
+                }
+
+                #[inline]
+                async fn flush_range_offload(&self, offset: usize, len: usize) -> Result<()> {
+                    self.inner.flush_range_offload(offset, len).await
+                }
+
                 #[inline]
                 async fn truncate(&mut self, max_sz: u64) -> Result<()> {
                     self.inner.truncate(max_sz).await
@@ -424,13 +604,15 @@ cfg_async! {
     }
 
     macro_rules! declare_async_mmap_file_ext {
-        ($disk_file_mut: ty, $opts: ty, $reader: ty) => {
+        () => {
             /// Utility methods to [`AsyncMmapFile`]
             ///
             /// [`AsyncMmapFile`]: structs.AsyncMmapFile.html
             #[async_trait]
-            #[enum_dispatch]
             pub trait AsyncMmapFileExt {
+                /// The runtime which the file operations of the mmap file run on.
+                type Runtime: AsyncRuntime;
+
                 /// Returns the current mmap length
                 fn len(&self) -> usize;
 
@@ -465,9 +647,48 @@ cfg_async! {
                     }
                 }
 
+                /// Returns a reference to the plain-old-data `T` at `offset`, without copying.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+                /// If the data at `offset` is not aligned for `T` in memory, it would return
+                /// `Err(Error::from(ErrorKind::Misaligned(offset, align_of::<T>())))`.
+                #[cfg(feature = "bytemuck")]
+                #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+                fn view<T: bytemuck::Pod>(&self, offset: usize) -> Result<&T> {
+                    crate::view::view(self.as_slice(), offset)
+                }
+
+                /// Returns a slice of `count` plain-old-data `T`s starting from `offset`, without copying.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+                /// If the data at `offset` is not aligned for `T` in memory, it would return
+                /// `Err(Error::from(ErrorKind::Misaligned(offset, align_of::<T>())))`.
+                #[cfg(feature = "bytemuck")]
+                #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+                fn view_slice<T: bytemuck::Pod>(&self, offset: usize, count: usize) -> Result<&[T]> {
+                    crate::view::view_slice(self.as_slice(), offset, count)
+                }
+
                 /// Returns the path of the inner file.
+                ///
+                /// The path is empty if the mmap is not backed by a named file, e.g. an in-memory mmap,
+                /// or a mmap of an opened file without a path hint, see [`file_path`].
+                ///
+                /// [`file_path`]: #method.file_path
                 fn path(&self) -> &Path;
 
+                /// Returns the path of the inner file, or `None` if the mmap is not backed by a named file.
+                fn file_path(&self) -> Option<&Path> {
+                    let path = self.path();
+                    if path.as_os_str().is_empty() {
+                        None
+                    } else {
+                        Some(path)
+                    }
+                }
+
                 /// Returns the path buf of the inner file.
                 fn path_buf(&self) -> PathBuf {
                     self.path().to_path_buf()
@@ -510,10 +731,10 @@ cfg_async! {
                 #[inline]
                 async fn write_all_to_new_file<P: AsRef<Path> + Send + Sync>(&self, new_file_path: P) -> Result<()> {
                     let buf = self.as_slice();
-                    let opts = <$opts>::new().max_size(buf.len() as u64);
+                    let opts = AsyncOptions::<Self::Runtime>::new().max_size(buf.len() as u64);
 
-                    let mut mmap = <$disk_file_mut>::create_with_options(new_file_path, opts).await?;
-                    mmap.writer(0)?.write_all(buf).await?;
+                    let mut mmap = AsyncDiskMmapFileMut::<Self::Runtime>::create_with_options(new_file_path, opts).await?;
+                    mmap.write_all(buf, 0)?;
                     mmap.flush()
                 }
 
@@ -524,10 +745,10 @@ cfg_async! {
                     if buf.len() < offset + len {
                         return Err(Error::from(ErrorKind::EOF));
                     }
-                    let opts = <$opts>::new().max_size(len as u64);
+                    let opts = AsyncOptions::<Self::Runtime>::new().max_size(len as u64);
 
-                    let mut mmap = <$disk_file_mut>::create_with_options(new_file_path, opts).await?;
-                    mmap.writer(0)?.write_all(&buf[offset..offset + len]).await?;
+                    let mut mmap = AsyncDiskMmapFileMut::<Self::Runtime>::create_with_options(new_file_path, opts).await?;
+                    mmap.write_all(&buf[offset..offset + len], 0)?;
                     mmap.flush()
                 }
 
@@ -538,12 +759,12 @@ cfg_async! {
                 ///  `Err(Error::from(ErrorKind::EOF))`.
                 ///
                 /// [`AsyncMmapFileReader`]: structs.AsyncMmapFileReader.html
-                fn reader(&self, offset: usize) -> Result<$reader> {
+                fn reader(&self, offset: usize) -> Result<AsyncMmapFileReader<'_>> {
                     let buf = self.as_slice();
                     if buf.len() < offset {
                         Err(Error::from(ErrorKind::EOF))
                     } else {
-                        Ok(<$reader>::new(Cursor::new(&buf[offset..]), offset, buf.len() - offset))
+                        Ok(AsyncMmapFileReader::new(Cursor::new(&buf[offset..]), offset, buf.len() - offset))
                     }
                 }
 
@@ -554,12 +775,12 @@ cfg_async! {
                 ///  `Err(Error::from(ErrorKind::EOF))`.
                 ///
                 /// [`AsyncMmapFileReader`]: structs.AsyncMmapFileReader.html
-                fn range_reader(&self, offset: usize, len: usize) -> Result<$reader> {
+                fn range_reader(&self, offset: usize, len: usize) -> Result<AsyncMmapFileReader<'_>> {
                     let buf = self.as_slice();
                     if buf.len() < offset + len {
                         Err(Error::from(ErrorKind::EOF))
                     } else {
-                        Ok(<$reader>::new(Cursor::new(&buf[offset.. offset + len]), offset, len))
+                        Ok(AsyncMmapFileReader::new(Cursor::new(&buf[offset.. offset + len]), offset, len))
                     }
                 }
 
@@ -593,6 +814,145 @@ cfg_async! {
                 /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
                 fn unlock(&self) -> Result<()>;
 
+                /// Locks the file for exclusive usage on the blocking pool of the runtime,
+                /// so waiting for the lock does not block the executor thread.
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                /// On non-unix platforms, the file is locked on the current thread.
+                async fn lock_exclusive_offload(&self) -> Result<()>;
+
+                /// Locks the file for shared usage on the blocking pool of the runtime,
+                /// so waiting for the lock does not block the executor thread.
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                /// On non-unix platforms, the file is locked on the current thread.
+                async fn lock_shared_offload(&self) -> Result<()>;
+
+                /// Locks the range `[offset, offset + len)` of the file for shared usage, blocking if the range is currently locked exclusively.
+                /// If `len` is 0, the range extends to the end of the file and beyond.
+                ///
+                /// Returns a guard which unlocks the range on drop. On Linux, the locks are open file description
+                /// locks, which belong to the opened file rather than the process, so they also exclude the other
+                /// mmap files of the same path in this process. Other unix platforms use the POSIX record locks.
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                /// Byte-range locks are only supported on unix platforms.
+                /// On Linux, the file is reopened through `/proc/self/fd` for the guard, so an error is returned if `/proc` is not mounted.
+                fn lock_range_shared(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard>;
+
+                /// Locks the range `[offset, offset + len)` of the file for exclusive usage, blocking if the range is currently locked.
+                /// If `len` is 0, the range extends to the end of the file and beyond.
+                ///
+                /// Returns a guard which unlocks the range on drop. On Linux, the locks are open file description
+                /// locks, which belong to the opened file rather than the process, so they also exclude the other
+                /// mmap files of the same path in this process. Other unix platforms use the POSIX record locks.
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                /// Byte-range locks are only supported on unix platforms.
+                /// On Linux, the file is reopened through `/proc/self/fd` for the guard, so an error is returned if `/proc` is not mounted.
+                /// The file must be opened for writing, e.g. it is not a read-only mmap file.
+                fn lock_range_exclusive(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard>;
+
+                /// Locks the range `[offset, offset + len)` of the file for shared usage, or returns an error if the range is currently locked exclusively.
+                /// If `len` is 0, the range extends to the end of the file and beyond.
+                ///
+                /// Returns a guard which unlocks the range on drop. On Linux, the locks are open file description
+                /// locks, which belong to the opened file rather than the process, so they also exclude the other
+                /// mmap files of the same path in this process. Other unix platforms use the POSIX record locks.
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                /// Byte-range locks are only supported on unix platforms.
+                /// On Linux, the file is reopened through `/proc/self/fd` for the guard, so an error is returned if `/proc` is not mounted.
+                fn try_lock_range_shared(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard>;
+
+                /// Locks the range `[offset, offset + len)` of the file for exclusive usage, or returns an error if the range is currently locked.
+                /// If `len` is 0, the range extends to the end of the file and beyond.
+                ///
+                /// Returns a guard which unlocks the range on drop. On Linux, the locks are open file description
+                /// locks, which belong to the opened file rather than the process, so they also exclude the other
+                /// mmap files of the same path in this process. Other unix platforms use the POSIX record locks.
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                /// Byte-range locks are only supported on unix platforms.
+                /// On Linux, the file is reopened through `/proc/self/fd` for the guard, so an error is returned if `/proc` is not mounted.
+                /// The file must be opened for writing, e.g. it is not a read-only mmap file.
+                fn try_lock_range_exclusive(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard>;
+
+                /// Advises the OS how the whole mmap is going to be accessed.
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                fn advise(&self, advice: crate::Advice) -> Result<()>;
+
+                /// Advises the OS how the range `[offset, offset + len)` of the mmap is going to be accessed.
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                fn advise_range(&self, advice: crate::Advice, offset: usize, len: usize) -> Result<()>;
+
+                /// Advises the OS with a hint which may change the contents of the whole mmap,
+                /// see [`unchecked_advise_range`].
+                ///
+                /// # Safety
+                /// See [`unchecked_advise_range`].
+                ///
+                /// [`unchecked_advise_range`]: trait.AsyncMmapFileExt.html#tymethod.unchecked_advise_range
+                unsafe fn unchecked_advise(&self, advice: crate::UncheckedAdvice) -> Result<()> {
+                    self.unchecked_advise_range(advice, 0, self.len())
+                }
+
+                /// Advises the OS with a hint which may change the contents of the range `[offset, offset + len)`.
+                ///
+                /// # Safety
+                /// On private mappings (e.g. copy-on-write), the range may read back as zeros afterwards.
+                /// The caller must ensure there are no live references into the range,
+                /// e.g. slices returned by [`as_slice`], and must not rely on the contents of the range.
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                ///
+                /// [`as_slice`]: trait.AsyncMmapFileExt.html#tymethod.as_slice
+                unsafe fn unchecked_advise_range(&self, advice: crate::UncheckedAdvice, offset: usize, len: usize) -> Result<()>;
+
+                /// Returns the number of pages of the mmap which are resident in memory (e.g. in the page cache).
+                ///
+                /// # Notes
+                /// In-memory mmaps are always fully resident.
+                fn resident_pages(&self) -> Result<usize>;
+
+                /// Returns the ratio of the pages in the range `[offset, offset + len)` which are resident
+                /// in memory, from `0.0` (nothing is resident) to `1.0` (fully resident).
+                ///
+                /// # Notes
+                /// In-memory mmaps are always fully resident.
+                fn residency_ratio(&self, offset: usize, len: usize) -> Result<f64>;
+
+                /// Asks the OS to read the range `[offset, offset + len)` of the mmap ahead, so that
+                /// later accesses do not block on page faults.
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                #[inline]
+                fn prefetch(&self, offset: usize, len: usize) -> Result<()> {
+                    self.advise_range(crate::Advice::WillNeed, offset, len)
+                }
+
+                /// Read bytes to the dst buf from the offset, returns how many bytes read.
+                ///
+                /// The pages of the range are faulted in on the blocking pool of the runtime before copying,
+                /// so a major page fault does not stall the executor thread.
+                ///
+                /// # Notes
+                /// The pages are copied on the current thread if the underlying is not a real file, e.g. in-memory,
+                /// or on non-unix platforms.
+                async fn read_at_async(&self, dst: &mut [u8], offset: usize) -> Result<usize>;
+
                 /// Read bytes to the dst buf from the offset, returns how many bytes read.
                 fn read(&self, dst: &mut [u8], offset: usize) -> usize {
                     let buf = self.as_slice();
@@ -606,7 +966,7 @@ cfg_async! {
                             dst.copy_from_slice(&buf[offset..offset + dst_len]);
                             dst_len
                         } else {
-                            dst.copy_from_slice(&buf[offset..offset + remaining]);
+                            dst[..remaining].copy_from_slice(&buf[offset..offset + remaining]);
                             remaining
                         }
                     }
@@ -630,6 +990,50 @@ cfg_async! {
                     }
                 }
 
+                /// Read the exact number of bytes required to fill buf, without faulting in the pages on the executor thread.
+                ///
+                /// If the range is resident in memory, the data is copied from the mmap, otherwise
+                /// it is read by `IORING_OP_READ` from the file, and the completion is awaited
+                /// without blocking the executor thread.
+                ///
+                /// # Notes
+                /// The data is copied from the mmap if the underlying is not a real file, e.g. in-memory,
+                /// the mmap is copy-on-write, or io_uring is not supported by the kernel.
+                #[cfg(all(feature = "io-uring", target_os = "linux"))]
+                #[cfg_attr(docsrs, doc(cfg(all(feature = "io-uring", target_os = "linux"))))]
+                async fn read_into(&self, dst: &mut [u8], offset: usize) -> Result<()> {
+                    self.read_exact(dst, offset)
+                }
+
+                /// Read bytes to the dst bufs in order from the offset, returns how many bytes read.
+                ///
+                /// A buffer is filled completely before the next buffer is read into,
+                /// the reading stops at the end of the mmap.
+                fn read_vectored_at(&self, dsts: &mut [std::io::IoSliceMut<'_>], offset: usize) -> usize {
+                    let mut n = 0;
+                    for dst in dsts.iter_mut() {
+                        let read = self.read(dst, offset + n);
+                        n += read;
+                        if read < dst.len() {
+                            break;
+                        }
+                    }
+                    n
+                }
+
+                /// Returns the data of the ranges `(offset, len)` of the mmap without copying,
+                /// in the same order as `ranges`.
+                ///
+                /// # Errors
+                /// If there's not enough data for any range, it would return
+                /// `Err(Error::from(ErrorKind::EOF))`.
+                fn gather(&self, ranges: &[(usize, usize)]) -> Result<Vec<&[u8]>> {
+                    ranges
+                        .iter()
+                        .map(|&(offset, len)| self.bytes(offset, len))
+                        .collect()
+                }
+
                 /// Read a signed 8 bit integer from offset.
                 fn read_i8(&self, offset: usize) -> Result<i8> {
                     let buf = self.as_slice();
@@ -787,17 +1191,46 @@ cfg_async! {
                 fn read_f64_le(&self, offset: usize) -> Result<f64> {
                     read_impl!(self, offset, f64::from_le_bytes)
                 }
+
+                /// Read an unsigned LEB128 variable-length integer from offset,
+                /// returns the integer and the number of bytes read.
+                fn read_uvarint(&self, offset: usize) -> Result<(u64, usize)> {
+                    match self.as_slice().get(offset..) {
+                        Some(buf) => crate::varint::decode_uvarint(buf),
+                        None => Err(Error::from(ErrorKind::EOF)),
+                    }
+                }
+
+                /// Read a zigzag encoded signed LEB128 variable-length integer from offset,
+                /// returns the integer and the number of bytes read.
+                fn read_varint(&self, offset: usize) -> Result<(i64, usize)> {
+                    self.read_uvarint(offset)
+                        .map(|(val, n)| (crate::varint::zigzag_decode(val), n))
+                }
+
+                /// Read a record prefixed by its length from offset,
+                /// returns the record and the number of bytes read, including the prefix.
+                fn read_length_prefixed(&self, offset: usize, prefix: crate::LengthPrefix) -> Result<(&[u8], usize)> {
+                    let buf = match self.as_slice().get(offset..) {
+                        Some(buf) => buf,
+                        None => return Err(Error::from(ErrorKind::EOF)),
+                    };
+                    let (len, n) = prefix.decode(buf)?;
+                    match buf.get(n..).and_then(|record| record.get(..len)) {
+                        Some(record) => Ok((record, n + len)),
+                        None => Err(Error::from(ErrorKind::EOF)),
+                    }
+                }
             }
         };
     }
 
     macro_rules! declare_async_mmap_file_mut_ext {
-        ($writer: ty) => {
+        () => {
             /// Utility methods to [`AsyncMmapFileMut`]
             ///
             /// [`AsyncMmapFileMut`]: structs.AsyncMmapFileMut.html
             #[async_trait]
-            #[enum_dispatch]
             pub trait AsyncMmapFileMutExt {
                 /// Returns the mutable underlying slice of the mmap
                 fn as_mut_slice(&mut self) -> &mut [u8];
@@ -814,6 +1247,29 @@ cfg_async! {
                 /// Whether mmap is copy on write
                 fn is_cow(&self) -> bool;
 
+                /// Returns a reference to the `AtomicU32` at `offset`, e.g. for coordinating multiple
+                /// processes over a shared mmap.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+                /// If the data at `offset` is not 4-byte aligned in memory, it would return
+                /// `Err(Error::from(ErrorKind::Misaligned(offset, 4)))`.
+                fn atomic_u32(&mut self, offset: usize) -> Result<&std::sync::atomic::AtomicU32> {
+                    crate::view::atomic_u32(self.as_mut_slice(), offset)
+                }
+
+                /// Returns a reference to the `AtomicU64` at `offset`, e.g. for coordinating multiple
+                /// processes over a shared mmap.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+                /// If the data at `offset` is not 8-byte aligned in memory, it would return
+                /// `Err(Error::from(ErrorKind::Misaligned(offset, 8)))`.
+                #[cfg(target_has_atomic = "64")]
+                fn atomic_u64(&mut self, offset: usize) -> Result<&std::sync::atomic::AtomicU64> {
+                    crate::view::atomic_u64(self.as_mut_slice(), offset)
+                }
+
                 /// bytes_mut returns mutable data starting from offset off of size sz.
                 ///
                 /// # Errors
@@ -828,6 +1284,30 @@ cfg_async! {
                     }
                 }
 
+                /// Returns a mutable reference to the plain-old-data `T` at `offset`, without copying.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+                /// If the data at `offset` is not aligned for `T` in memory, it would return
+                /// `Err(Error::from(ErrorKind::Misaligned(offset, align_of::<T>())))`.
+                #[cfg(feature = "bytemuck")]
+                #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+                fn view_mut<T: bytemuck::Pod>(&mut self, offset: usize) -> Result<&mut T> {
+                    crate::view::view_mut(self.as_mut_slice(), offset)
+                }
+
+                /// Returns a mutable slice of `count` plain-old-data `T`s starting from `offset`, without copying.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+                /// If the data at `offset` is not aligned for `T` in memory, it would return
+                /// `Err(Error::from(ErrorKind::Misaligned(offset, align_of::<T>())))`.
+                #[cfg(feature = "bytemuck")]
+                #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+                fn view_slice_mut<T: bytemuck::Pod>(&mut self, offset: usize, count: usize) -> Result<&mut [T]> {
+                    crate::view::view_slice_mut(self.as_mut_slice(), offset, count)
+                }
+
                 /// Fill 0 to the specific range
                 fn zero_range(&mut self, start: usize, end: usize) {
                     let buf = self.as_mut_slice();
@@ -872,6 +1352,26 @@ cfg_async! {
                 /// other outstanding changes to the memory map may be flushed as well.
                 fn flush_async_range(&self, offset: usize, len: usize) -> Result<()>;
 
+                /// Flushes outstanding memory map modifications to disk on the blocking pool of the runtime,
+                /// so the flush does not stall the executor thread, see [`flush`].
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                /// On non-unix platforms, the mmap is flushed on the current thread.
+                ///
+                /// [`flush`]: trait.AsyncMmapFileMutExt.html#tymethod.flush
+                async fn flush_offload(&self) -> Result<()>;
+
+                /// Flushes outstanding memory map modifications in the range to disk on the blocking pool of the runtime,
+                /// so the flush does not stall the executor thread, see [`flush_range`].
+                ///
+                /// # Notes
+                /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+                /// On non-unix platforms, the mmap is flushed on the current thread.
+                ///
+                /// [`flush_range`]: trait.AsyncMmapFileMutExt.html#tymethod.flush_range
+                async fn flush_range_offload(&self, offset: usize, len: usize) -> Result<()>;
+
                 /// Truncates the file to the `max_size`, which will lead to
                 /// do re-mmap and sync_dir if the inner is a real file.
                 async fn truncate(&mut self, max_sz: u64) -> Result<()>;
@@ -898,13 +1398,13 @@ cfg_async! {
                 /// [`flush_async`]: traits.MmapFileMutExt.html#methods.flush_async
                 /// [`flush_async_range`]: traits.MmapFileMutExt.html#methods.flush_async_range
                 /// [`MmapFileWriter`]: structs.MmapFileWriter.html
-                fn writer(&mut self, offset: usize) -> Result<$writer> {
+                fn writer(&mut self, offset: usize) -> Result<AsyncMmapFileWriter<'_>> {
                     let buf = self.as_mut_slice();
                     let buf_len = buf.len();
                     if buf_len < offset {
                         Err(Error::from(ErrorKind::EOF))
                     } else {
-                        Ok(<$writer>::new(Cursor::new(&mut buf[offset..]), offset, buf_len - offset))
+                        Ok(AsyncMmapFileWriter::new(Cursor::new(&mut buf[offset..]), offset, buf_len - offset))
                     }
                 }
 
@@ -924,12 +1424,12 @@ cfg_async! {
                 /// [`flush_async`]: traits.AsyncMmapFileMutExt.html#methods.flush_async
                 /// [`flush_async_range`]: traits.AsyncMmapFileMutExt.html#methods.flush_async_range
                 /// [`AsyncMmapFileWriter`]: structs.AsyncMmapFileWriter.html
-                fn range_writer(&mut self, offset: usize, len: usize) -> Result<$writer> {
+                fn range_writer(&mut self, offset: usize, len: usize) -> Result<AsyncMmapFileWriter<'_>> {
                     let buf = self.as_mut_slice();
                     if buf.len() < offset + len {
                         Err(Error::from(ErrorKind::EOF))
                     } else {
-                        Ok(<$writer>::new(
+                        Ok(AsyncMmapFileWriter::new(
                             Cursor::new(&mut buf[offset..offset + len]), offset, len))
                     }
                 }
@@ -970,6 +1470,22 @@ cfg_async! {
                     }
                 }
 
+                /// Write bytes of the src bufs in order to the mmap from the offset, returns how many bytes written.
+                ///
+                /// A buffer is written completely before the next buffer,
+                /// the writing stops at the end of the mmap.
+                fn write_vectored_at(&mut self, srcs: &[std::io::IoSlice<'_>], offset: usize) -> usize {
+                    let mut n = 0;
+                    for src in srcs {
+                        let written = self.write(src, offset + n);
+                        n += written;
+                        if written < src.len() {
+                            break;
+                        }
+                    }
+                    n
+                }
+
                 /// Writes a signed 8 bit integer to mmap from the offset.
                 fn write_i8(&mut self, val: i8, offset: usize) -> Result<()> {
                     self.write_all(&[val as u8], offset)
@@ -1099,36 +1615,68 @@ cfg_async! {
                 fn write_f64_le(&mut self, val: f64, offset: usize) -> Result<()> {
                     self.write_all(&val.to_le_bytes(), offset)
                 }
+
+                /// Writes an unsigned LEB128 variable-length integer to mmap from the offset,
+                /// returns the number of bytes written.
+                fn write_uvarint(&mut self, val: u64, offset: usize) -> Result<usize> {
+                    let mut buf = [0; crate::varint::MAX_VARINT_LEN];
+                    let n = crate::varint::encode_uvarint(val, &mut buf);
+                    self.write_all(&buf[..n], offset).map(|_| n)
+                }
+
+                /// Writes a zigzag encoded signed LEB128 variable-length integer to mmap from the offset,
+                /// returns the number of bytes written.
+                fn write_varint(&mut self, val: i64, offset: usize) -> Result<usize> {
+                    self.write_uvarint(crate::varint::zigzag_encode(val), offset)
+                }
+
+                /// Writes `src` prefixed by its length to mmap from the offset,
+                /// returns the number of bytes written, including the prefix.
+                ///
+                /// Nothing is written if there is not enough space for the prefix and `src`.
+                fn write_length_prefixed(&mut self, src: &[u8], offset: usize, prefix: crate::LengthPrefix) -> Result<usize> {
+                    let mut buf = [0; crate::varint::MAX_VARINT_LEN];
+                    let n = prefix.encode(src.len(), &mut buf)?;
+                    let end = offset.checked_add(n + src.len());
+                    if end.map_or(true, |end| end > self.as_mut_slice().len()) {
+                        return Err(Error::from(ErrorKind::EOF));
+                    }
+                    self.write_all(&buf[..n], offset)?;
+                    self.write_all(src, offset + n)?;
+                    Ok(n + src.len())
+                }
             }
         };
     }
 
     macro_rules! declare_and_impl_inners {
         () => {
-            enum AsyncMmapFileInner {
-                Empty(AsyncEmptyMmapFile),
-                Memory(AsyncMemoryMmapFile),
-                Disk(AsyncDiskMmapFile)
+            enum AsyncMmapFileInner<R: AsyncRuntime> {
+                Empty(AsyncEmptyMmapFile<R>),
+                Memory(AsyncMemoryMmapFile<R>),
+                Disk(AsyncDiskMmapFile<R>)
             }
 
-            impl From<AsyncEmptyMmapFile> for AsyncMmapFileInner {
-                fn from(v: AsyncEmptyMmapFile) -> AsyncMmapFileInner {
+            impl<R: AsyncRuntime> From<AsyncEmptyMmapFile<R>> for AsyncMmapFileInner<R> {
+                fn from(v: AsyncEmptyMmapFile<R>) -> AsyncMmapFileInner<R> {
                     AsyncMmapFileInner::Empty(v)
                 }
             }
-            impl From<AsyncMemoryMmapFile> for AsyncMmapFileInner {
-                fn from(v: AsyncMemoryMmapFile) -> AsyncMmapFileInner {
+            impl<R: AsyncRuntime> From<AsyncMemoryMmapFile<R>> for AsyncMmapFileInner<R> {
+                fn from(v: AsyncMemoryMmapFile<R>) -> AsyncMmapFileInner<R> {
                     AsyncMmapFileInner::Memory(v)
                 }
             }
-            impl From<AsyncDiskMmapFile> for AsyncMmapFileInner {
-                fn from(v: AsyncDiskMmapFile) -> AsyncMmapFileInner {
+            impl<R: AsyncRuntime> From<AsyncDiskMmapFile<R>> for AsyncMmapFileInner<R> {
+                fn from(v: AsyncDiskMmapFile<R>) -> AsyncMmapFileInner<R> {
                     AsyncMmapFileInner::Disk(v)
                 }
             }
 
             #[async_trait]
-            impl AsyncMmapFileExt for AsyncMmapFileInner {
+            impl<R: AsyncRuntime> AsyncMmapFileExt for AsyncMmapFileInner<R> {
+                type Runtime = R;
+
                 #[inline]
                 fn len(&self) -> usize {
                     match self {
@@ -1174,6 +1722,43 @@ cfg_async! {
                     }
                 }
 
+                #[inline]
+                async fn lock_exclusive_offload(&self) -> Result<()> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::lock_exclusive_offload(inner).await,
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::lock_exclusive_offload(inner).await,
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::lock_exclusive_offload(inner).await,
+                    }
+                }
+
+                #[inline]
+                async fn lock_shared_offload(&self) -> Result<()> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::lock_shared_offload(inner).await,
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::lock_shared_offload(inner).await,
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::lock_shared_offload(inner).await,
+                    }
+                }
+
+                #[inline]
+                async fn read_at_async(&self, dst: &mut [u8], offset: usize) -> Result<usize> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::read_at_async(inner, dst, offset).await,
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::read_at_async(inner, dst, offset).await,
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::read_at_async(inner, dst, offset).await,
+                    }
+                }
+
+                #[cfg(all(feature = "io-uring", target_os = "linux"))]
+                #[inline]
+                async fn read_into(&self, dst: &mut [u8], offset: usize) -> Result<()> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::read_into(inner, dst, offset).await,
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::read_into(inner, dst, offset).await,
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::read_into(inner, dst, offset).await,
+                    }
+                }
+
                 #[inline]
                 fn lock_exclusive(&self) -> Result<()> {
                     match self {
@@ -1220,32 +1805,115 @@ cfg_async! {
                         AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::unlock(inner),
                     }
                 }
-            }
 
-            enum AsyncMmapFileMutInner {
-                Empty(AsyncEmptyMmapFile),
-                Memory(AsyncMemoryMmapFileMut),
-                Disk(AsyncDiskMmapFileMut)
-            }
-
-            impl From<AsyncEmptyMmapFile> for AsyncMmapFileMutInner {
-                fn from(v: AsyncEmptyMmapFile) -> AsyncMmapFileMutInner {
-                    AsyncMmapFileMutInner::Empty(v)
+                #[inline]
+                fn lock_range_shared(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::lock_range_shared(inner, offset, len),
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::lock_range_shared(inner, offset, len),
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::lock_range_shared(inner, offset, len),
+                    }
                 }
-            }
-            impl From<AsyncMemoryMmapFileMut> for AsyncMmapFileMutInner {
-                fn from(v: AsyncMemoryMmapFileMut) -> AsyncMmapFileMutInner {
-                    AsyncMmapFileMutInner::Memory(v)
+
+                #[inline]
+                fn lock_range_exclusive(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::lock_range_exclusive(inner, offset, len),
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::lock_range_exclusive(inner, offset, len),
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::lock_range_exclusive(inner, offset, len),
+                    }
                 }
+
+                #[inline]
+                fn try_lock_range_shared(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::try_lock_range_shared(inner, offset, len),
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::try_lock_range_shared(inner, offset, len),
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::try_lock_range_shared(inner, offset, len),
+                    }
+                }
+
+                #[inline]
+                fn try_lock_range_exclusive(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::try_lock_range_exclusive(inner, offset, len),
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::try_lock_range_exclusive(inner, offset, len),
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::try_lock_range_exclusive(inner, offset, len),
+                    }
+                }
+
+                #[inline]
+                fn advise(&self, advice: crate::Advice) -> Result<()> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::advise(inner, advice),
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::advise(inner, advice),
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::advise(inner, advice),
+                    }
+                }
+
+                #[inline]
+                fn advise_range(&self, advice: crate::Advice, offset: usize, len: usize) -> Result<()> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::advise_range(inner, advice, offset, len),
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::advise_range(inner, advice, offset, len),
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::advise_range(inner, advice, offset, len),
+                    }
+                }
+
+                #[inline]
+                unsafe fn unchecked_advise_range(&self, advice: crate::UncheckedAdvice, offset: usize, len: usize) -> Result<()> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::unchecked_advise_range(inner, advice, offset, len),
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::unchecked_advise_range(inner, advice, offset, len),
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::unchecked_advise_range(inner, advice, offset, len),
+                    }
+                }
+
+                #[inline]
+                fn resident_pages(&self) -> Result<usize> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::resident_pages(inner),
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::resident_pages(inner),
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::resident_pages(inner),
+                    }
+                }
+
+                #[inline]
+                fn residency_ratio(&self, offset: usize, len: usize) -> Result<f64> {
+                    match self {
+                        AsyncMmapFileInner::Empty(inner) => AsyncMmapFileExt::residency_ratio(inner, offset, len),
+                        AsyncMmapFileInner::Memory(inner) => AsyncMmapFileExt::residency_ratio(inner, offset, len),
+                        AsyncMmapFileInner::Disk(inner) => AsyncMmapFileExt::residency_ratio(inner, offset, len),
+                    }
+                }
+            }
+
+            enum AsyncMmapFileMutInner<R: AsyncRuntime> {
+                Empty(AsyncEmptyMmapFile<R>),
+                Memory(AsyncMemoryMmapFileMut<R>),
+                Disk(AsyncDiskMmapFileMut<R>)
             }
-            impl From<AsyncDiskMmapFileMut> for AsyncMmapFileMutInner {
-                fn from(v: AsyncDiskMmapFileMut) -> AsyncMmapFileMutInner {
+
+            impl<R: AsyncRuntime> From<AsyncEmptyMmapFile<R>> for AsyncMmapFileMutInner<R> {
+                fn from(v: AsyncEmptyMmapFile<R>) -> AsyncMmapFileMutInner<R> {
+                    AsyncMmapFileMutInner::Empty(v)
+                }
+            }
+            impl<R: AsyncRuntime> From<AsyncMemoryMmapFileMut<R>> for AsyncMmapFileMutInner<R> {
+                fn from(v: AsyncMemoryMmapFileMut<R>) -> AsyncMmapFileMutInner<R> {
+                    AsyncMmapFileMutInner::Memory(v)
+                }
+            }
+            impl<R: AsyncRuntime> From<AsyncDiskMmapFileMut<R>> for AsyncMmapFileMutInner<R> {
+                fn from(v: AsyncDiskMmapFileMut<R>) -> AsyncMmapFileMutInner<R> {
                     AsyncMmapFileMutInner::Disk(v)
                 }
             }
 
             #[async_trait]
-            impl AsyncMmapFileExt for AsyncMmapFileMutInner {
+            impl<R: AsyncRuntime> AsyncMmapFileExt for AsyncMmapFileMutInner<R> {
+                type Runtime = R;
+
                 #[inline]
                 fn len(&self) -> usize {
                     match self {
@@ -1291,6 +1959,43 @@ cfg_async! {
                     }
                 }
 
+                #[inline]
+                async fn lock_exclusive_offload(&self) -> Result<()> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::lock_exclusive_offload(inner).await,
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::lock_exclusive_offload(inner).await,
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::lock_exclusive_offload(inner).await,
+                    }
+                }
+
+                #[inline]
+                async fn lock_shared_offload(&self) -> Result<()> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::lock_shared_offload(inner).await,
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::lock_shared_offload(inner).await,
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::lock_shared_offload(inner).await,
+                    }
+                }
+
+                #[inline]
+                async fn read_at_async(&self, dst: &mut [u8], offset: usize) -> Result<usize> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::read_at_async(inner, dst, offset).await,
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::read_at_async(inner, dst, offset).await,
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::read_at_async(inner, dst, offset).await,
+                    }
+                }
+
+                #[cfg(all(feature = "io-uring", target_os = "linux"))]
+                #[inline]
+                async fn read_into(&self, dst: &mut [u8], offset: usize) -> Result<()> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::read_into(inner, dst, offset).await,
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::read_into(inner, dst, offset).await,
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::read_into(inner, dst, offset).await,
+                    }
+                }
+
                 #[inline]
                 fn lock_exclusive(&self) -> Result<()> {
                     match self {
@@ -1337,10 +2042,91 @@ cfg_async! {
                         AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::unlock(inner),
                     }
                 }
+
+                #[inline]
+                fn lock_range_shared(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::lock_range_shared(inner, offset, len),
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::lock_range_shared(inner, offset, len),
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::lock_range_shared(inner, offset, len),
+                    }
+                }
+
+                #[inline]
+                fn lock_range_exclusive(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::lock_range_exclusive(inner, offset, len),
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::lock_range_exclusive(inner, offset, len),
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::lock_range_exclusive(inner, offset, len),
+                    }
+                }
+
+                #[inline]
+                fn try_lock_range_shared(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::try_lock_range_shared(inner, offset, len),
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::try_lock_range_shared(inner, offset, len),
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::try_lock_range_shared(inner, offset, len),
+                    }
+                }
+
+                #[inline]
+                fn try_lock_range_exclusive(&self, offset: u64, len: u64) -> Result<crate::RangeLockGuard> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::try_lock_range_exclusive(inner, offset, len),
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::try_lock_range_exclusive(inner, offset, len),
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::try_lock_range_exclusive(inner, offset, len),
+                    }
+                }
+
+                #[inline]
+                fn advise(&self, advice: crate::Advice) -> Result<()> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::advise(inner, advice),
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::advise(inner, advice),
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::advise(inner, advice),
+                    }
+                }
+
+                #[inline]
+                fn advise_range(&self, advice: crate::Advice, offset: usize, len: usize) -> Result<()> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::advise_range(inner, advice, offset, len),
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::advise_range(inner, advice, offset, len),
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::advise_range(inner, advice, offset, len),
+                    }
+                }
+
+                #[inline]
+                unsafe fn unchecked_advise_range(&self, advice: crate::UncheckedAdvice, offset: usize, len: usize) -> Result<()> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::unchecked_advise_range(inner, advice, offset, len),
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::unchecked_advise_range(inner, advice, offset, len),
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::unchecked_advise_range(inner, advice, offset, len),
+                    }
+                }
+
+                #[inline]
+                fn resident_pages(&self) -> Result<usize> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::resident_pages(inner),
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::resident_pages(inner),
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::resident_pages(inner),
+                    }
+                }
+
+                #[inline]
+                fn residency_ratio(&self, offset: usize, len: usize) -> Result<f64> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileExt::residency_ratio(inner, offset, len),
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileExt::residency_ratio(inner, offset, len),
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileExt::residency_ratio(inner, offset, len),
+                    }
+                }
             }
 
             #[async_trait]
-            impl AsyncMmapFileMutExt for AsyncMmapFileMutInner {
+            impl<R: AsyncRuntime> AsyncMmapFileMutExt for AsyncMmapFileMutInner<R> {
                 #[inline]
                 fn as_mut_slice(&mut self) -> &mut [u8] {
                     match self {
@@ -1421,6 +2207,24 @@ cfg_async! {
                     }
                 }
 
+                #[inline]
+                async fn flush_offload(&self) -> Result<()> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileMutExt::flush_offload(inner).await,
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileMutExt::flush_offload(inner).await,
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileMutExt::flush_offload(inner).await,
+                    }
+                }
+
+                #[inline]
+                async fn flush_range_offload(&self, offset: usize, len: usize) -> Result<()> {
+                    match self {
+                        AsyncMmapFileMutInner::Empty(inner) => AsyncMmapFileMutExt::flush_range_offload(inner, offset, len).await,
+                        AsyncMmapFileMutInner::Memory(inner) => AsyncMmapFileMutExt::flush_range_offload(inner, offset, len).await,
+                        AsyncMmapFileMutInner::Disk(inner) => AsyncMmapFileMutExt::flush_range_offload(inner, offset, len).await,
+                    }
+                }
+
                 async fn truncate(&mut self, max_sz: u64) -> Result<()> {
                     match self {
                         AsyncMmapFileMutInner::Empty(inner) => {
@@ -1473,15 +2277,15 @@ cfg_async! {
             ///
             /// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
             #[repr(transparent)]
-            pub struct AsyncMmapFile {
-                inner: AsyncMmapFileInner
+            pub struct AsyncMmapFile<R: AsyncRuntime> {
+                inner: AsyncMmapFileInner<R>
             }
 
-            impl_from!(AsyncMmapFile, AsyncMmapFileInner, [AsyncEmptyMmapFile, AsyncMemoryMmapFile, AsyncDiskMmapFile]);
+            impl_from!(AsyncMmapFile<R>, AsyncMmapFileInner, [AsyncEmptyMmapFile, AsyncMemoryMmapFile, AsyncDiskMmapFile]);
 
             impl_async_mmap_file_ext!(AsyncMmapFile);
 
-            impl AsyncMmapFile {
+            impl<R: AsyncRuntime> AsyncMmapFile<R> {
                 /// Open a readable memory map backed by a file
                 ///
                 /// # Examples
@@ -1540,7 +2344,7 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
-                pub async fn open_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self> {
+                pub async fn open_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self> {
                     Ok(Self::from(AsyncDiskMmapFile::open_with_options(path, opts).await?))
                 }
 
@@ -1602,12 +2406,122 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
-                pub async fn open_exec_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self> {
+                pub async fn open_exec_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self> {
                     Ok(Self::from(AsyncDiskMmapFile::open_exec_with_options(path, opts).await?))
                 }
+
+                /// Mmap a file which has already been opened by the caller with [`AsyncOptions`], e.g.
+                /// a file opened with `O_DIRECT`, relative to a directory fd or received over a unix socket.
+                ///
+                /// The file is never opened by `path_hint`, which is only reported by `path` and `file_path`.
+                ///
+                /// # Examples
+                ///
+                #[doc = "```ignore"]
+                #[doc = concat!("use fmmap::", $path_str, "::{AsyncOptions, AsyncMmapFile, AsyncMmapFileExt};")]
+                #[doc = concat!("# use fmmap::", $path_str, "::{AsyncMmapFileMut, AsyncMmapFileMutExt};")]
+                /// # use scopeguard::defer;
+                ///
+                #[doc = concat!("# ", $doc_test_runtime, "::block_on(async {")]
+                #[doc = concat!("# let mut file = AsyncMmapFileMut::create(\"", $filename_prefix, "_from_file_test.txt\").await.unwrap();")]
+                #[doc = concat!(" # defer!(std::fs::remove_file(\"", $filename_prefix, "_from_file_test.txt\").unwrap());")]
+                /// # file.truncate(12).await.unwrap();
+                /// # file.write_all("some data...".as_bytes(), 0).unwrap();
+                /// # file.flush().unwrap();
+                /// # drop(file);
+                ///
+                #[doc = concat!("let file = ", $path_str, "::fs::File::open(\"", $filename_prefix, "_from_file_test.txt\").await.unwrap();")]
+                /// let mut file = AsyncMmapFile::from_file(file, None, AsyncOptions::new()).await.unwrap();
+                /// assert!(file.file_path().is_none());
+                /// let mut buf = vec![0; "some data...".len()];
+                /// file.read_exact(buf.as_mut_slice(), 0).unwrap();
+                /// assert_eq!(buf.as_slice(), "some data...".as_bytes());
+                /// # })
+                #[doc = "```"]
+                ///
+                #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
+                pub async fn from_file(file: R::File, path_hint: Option<PathBuf>, opts: AsyncOptions<R>) -> Result<Self> {
+                    Ok(Self::from(AsyncDiskMmapFile::from_file(file, path_hint, opts).await?))
+                }
+
+                /// Locks the file for shared usage without blocking the executor thread,
+                /// and returns a guard which unlocks the file on drop.
+                ///
+                /// If the file is currently locked exclusively, it waits on the blocking pool of the runtime
+                /// until the lock is acquired, see [`AsyncMmapFileExt::lock_shared_offload`].
+                /// The guard derefs to the data of the mmap file.
+                ///
+                /// # Notes
+                /// This function will do nothing but return the guard if the underlying is not a real file, e.g. in-memory.
+                ///
+                /// [`AsyncMmapFileExt::lock_shared_offload`]: trait.AsyncMmapFileExt.html#tymethod.lock_shared_offload
+                pub async fn lock_shared_guard(&mut self) -> Result<AsyncFileLockGuard<'_, Self>> {
+                    self.lock_shared_offload().await?;
+                    Ok(AsyncFileLockGuard::new(self))
+                }
+
+                /// Locks the file for exclusive usage without blocking the executor thread,
+                /// and returns a guard which unlocks the file on drop.
+                ///
+                /// If the file is currently locked, it waits on the blocking pool of the runtime
+                /// until the lock is acquired, see [`AsyncMmapFileExt::lock_exclusive_offload`].
+                /// The guard derefs to the data of the mmap file.
+                ///
+                /// # Notes
+                /// This function will do nothing but return the guard if the underlying is not a real file, e.g. in-memory.
+                ///
+                /// [`AsyncMmapFileExt::lock_exclusive_offload`]: trait.AsyncMmapFileExt.html#tymethod.lock_exclusive_offload
+                pub async fn lock_exclusive_guard(&mut self) -> Result<AsyncFileLockGuard<'_, Self>> {
+                    self.lock_exclusive_offload().await?;
+                    Ok(AsyncFileLockGuard::new(self))
+                }
+
+                /// Consumes the mmap file, returns an [`AsyncOwnedMmapFileReader`] base on the given `offset`,
+                /// which helps read data from mmap like a normal File.
+                ///
+                /// Unlike [`AsyncMmapFileExt::reader`], the reader owns the mmap file, so it can be returned from functions
+                /// or moved into spawned tasks.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return
+                ///  `Err(Error::from(ErrorKind::EOF))`.
+                ///
+                /// [`AsyncOwnedMmapFileReader`]: structs.AsyncOwnedMmapFileReader.html
+                /// [`AsyncMmapFileExt::reader`]: traits.AsyncMmapFileExt.html#methods.reader
+                pub fn into_reader(self, offset: usize) -> Result<AsyncOwnedMmapFileReader<R>> {
+                    let len = self.len();
+                    if len < offset {
+                        Err(Error::from(ErrorKind::EOF))
+                    } else {
+                        Ok(AsyncOwnedMmapFileReader::new(self, offset, len - offset))
+                    }
+                }
+
+                /// Consumes the mmap file, returns an [`AsyncOwnedMmapFileReader`] base on the given `offset` and `len`,
+                /// which helps read data from mmap like a normal File.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return
+                ///  `Err(Error::from(ErrorKind::EOF))`.
+                ///
+                /// [`AsyncOwnedMmapFileReader`]: structs.AsyncOwnedMmapFileReader.html
+                pub fn into_range_reader(self, offset: usize, len: usize) -> Result<AsyncOwnedMmapFileReader<R>> {
+                    if self.len() < offset + len {
+                        Err(Error::from(ErrorKind::EOF))
+                    } else {
+                        Ok(AsyncOwnedMmapFileReader::new(self, offset, len))
+                    }
+                }
             }
 
-            impl_constructor_for_memory_mmap_file!(AsyncMemoryMmapFile, AsyncMmapFile, "AsyncMmapFile", $path_str);
+            impl<R: AsyncRuntime> AsRef<[u8]> for AsyncMmapFile<R> {
+                #[inline]
+                fn as_ref(&self) -> &[u8] {
+                    self.as_slice()
+                }
+            }
+
+            impl_constructor_for_memory_mmap_file!(AsyncMemoryMmapFile, AsyncMmapFile<R>, "AsyncMmapFile", $path_str);
         };
     }
 
@@ -1621,19 +2535,21 @@ cfg_async! {
             /// - __Empty__: a state represents null mmap, which is helpful for drop, remove, close the `AsyncMmapFileMut`. This state cannot be constructed directly.
             ///
             /// [`BytesMut`]: https://docs.rs/bytes/1.1.0/bytes/struct.BytesMut.html
-            pub struct AsyncMmapFileMut {
-                inner: AsyncMmapFileMutInner,
+            pub struct AsyncMmapFileMut<R: AsyncRuntime> {
+                inner: AsyncMmapFileMutInner<R>,
                 remove_on_drop: bool,
                 deleted: bool,
+                /// The target path if the file is created by `create_atomic`.
+                commit_to: Option<PathBuf>,
             }
 
-            impl_from_mut!(AsyncMmapFileMut, AsyncMmapFileMutInner, [AsyncEmptyMmapFile, AsyncMemoryMmapFileMut, AsyncDiskMmapFileMut]);
+            impl_from_mut!(AsyncMmapFileMut<R>, AsyncMmapFileMutInner, [AsyncEmptyMmapFile, AsyncMemoryMmapFileMut, AsyncDiskMmapFileMut]);
 
             impl_async_mmap_file_ext!(AsyncMmapFileMut);
 
             impl_async_mmap_file_mut_ext!($filename_prefix, $doc_test_runtime, $path_str);
 
-            impl AsyncMmapFileMut {
+            impl<R: AsyncRuntime> AsyncMmapFileMut<R> {
                 /// Create a new file and mmap this file
                 ///
                 /// # Notes
@@ -1681,10 +2597,60 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
-                pub async fn create_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self> {
+                pub async fn create_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self> {
                     Ok(Self::from(AsyncDiskMmapFileMut::create_with_options(path, opts).await?))
                 }
 
+                /// Create a temporary file in the same directory as `path` and mmap it,
+                /// the file is moved to `path` by [`commit`], so `path` holds either the old content
+                /// or the complete new content, even if the process crashes.
+                ///
+                /// The temporary file is removed on drop if [`commit`] is not invoked.
+                ///
+                /// # Notes
+                /// The new file is zero size, so, before write, you should truncate first.
+                /// Or you can use [`create_atomic_with_options`] and set `max_size` field for [`AsyncOptions`] to enable directly write
+                /// without truncating.
+                ///
+                /// # Example
+                ///
+                #[doc = "```ignore"]
+                #[doc = concat!("use fmmap::", $path_str, "::{AsyncMmapFileMut, AsyncMmapFileMutExt};")]
+                /// # use scopeguard::defer;
+                ///
+                #[doc = concat!("# ", $doc_test_runtime, "::block_on(async {")]
+                #[doc = concat!("let mut file = AsyncMmapFileMut::create_atomic(\"", $filename_prefix, "_create_atomic_test.txt\").await.unwrap();")]
+                #[doc = concat!("# defer!(std::fs::remove_file(\"", $filename_prefix, "_create_atomic_test.txt\").unwrap());")]
+                /// file.truncate(12).await.unwrap();
+                /// file.write_all("some data...".as_bytes(), 0).unwrap();
+                /// file.commit().await.unwrap();
+                /// # })
+                #[doc = "```"]
+                ///
+                #[doc = concat!("[`commit`]: ", $path_str, "/struct.AsyncMmapFileMut.html#method.commit")]
+                #[doc = concat!("[`create_atomic_with_options`]: ", $path_str, "/struct.AsyncMmapFileMut.html#method.create_atomic_with_options")]
+                #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
+                pub async fn create_atomic<P: AsRef<Path>>(path: P) -> Result<Self> {
+                    Self::create_atomic_with_options(path, AsyncOptions::new()).await
+                }
+
+                /// Create a temporary file in the same directory as `path` and mmap it with [`AsyncOptions`],
+                /// the file is moved to `path` by [`commit`].
+                ///
+                /// The `read`, `write` and `create_new` fields of [`AsyncOptions`] are always set to `true`.
+                ///
+                #[doc = concat!("[`commit`]: ", $path_str, "/struct.AsyncMmapFileMut.html#method.commit")]
+                #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
+                pub async fn create_atomic_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self> {
+                    let path = path.as_ref();
+                    let tmp = path.with_file_name(crate::mmap_file::atomic_tmp_file_name(path.file_name()));
+                    let opts = opts.read(true).write(true).create_new(true);
+                    let mut file = Self::from(AsyncDiskMmapFileMut::open_with_options(tmp, opts).await?);
+                    file.remove_on_drop = true;
+                    file.commit_to = Some(path.to_path_buf());
+                    Ok(file)
+                }
+
                 /// Open or Create(if not exists) a file and mmap this file.
                 ///
                 /// # Notes
@@ -1858,7 +2824,7 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
-                pub async fn open_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self> {
+                pub async fn open_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self> {
                     Ok(Self::from(AsyncDiskMmapFileMut::open_with_options(path, opts).await?))
                 }
 
@@ -1950,7 +2916,7 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
-                pub async fn open_exist_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self> {
+                pub async fn open_exist_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self> {
                     Ok(Self::from(AsyncDiskMmapFileMut::open_exist_with_options(path, opts).await?))
                 }
 
@@ -2047,10 +3013,46 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
-                pub async fn open_cow_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions) -> Result<Self> {
+                pub async fn open_cow_with_options<P: AsRef<Path>>(path: P, opts: AsyncOptions<R>) -> Result<Self> {
                     Ok(Self::from(AsyncDiskMmapFileMut::open_cow_with_options(path, opts).await?))
                 }
 
+                /// Mmap a writable file which has already been opened by the caller with [`AsyncOptions`], e.g.
+                /// a file opened with `O_TMPFILE`, relative to a directory fd or received over a unix socket.
+                ///
+                /// The file is never opened by `path_hint`, which is only reported by `path` and `file_path`.
+                /// If the file is empty, it is truncated to `max_size` of [`AsyncOptions`].
+                /// Without a `path_hint`, [`remove`] only truncates the file.
+                ///
+                /// # Examples
+                ///
+                #[doc = "```ignore"]
+                #[doc = concat!("use fmmap::", $path_str, "::{AsyncOptions, AsyncMmapFileMut, AsyncMmapFileExt, AsyncMmapFileMutExt};")]
+                /// # use scopeguard::defer;
+                ///
+                #[doc = concat!("# ", $doc_test_runtime, "::block_on(async {")]
+                #[doc = concat!("let file = ", $path_str, "::fs::OpenOptions::new()")]
+                ///     .read(true)
+                ///     .write(true)
+                ///     .create(true)
+                #[doc = concat!("    .open(\"", $filename_prefix, "_from_file_mut_test.txt\")")]
+                ///     .await
+                ///     .unwrap();
+                #[doc = concat!("# defer!(std::fs::remove_file(\"", $filename_prefix, "_from_file_mut_test.txt\").unwrap());")]
+                /// let opts = AsyncOptions::new().max_size(100);
+                #[doc = concat!("let mut file = AsyncMmapFileMut::from_file(file, Some(\"", $filename_prefix, "_from_file_mut_test.txt\".into()), opts).await.unwrap();")]
+                /// assert_eq!(file.len(), 100);
+                /// file.write_all("some data...".as_bytes(), 0).unwrap();
+                /// file.flush().unwrap();
+                /// # })
+                #[doc = "```"]
+                ///
+                #[doc = concat!("[`AsyncOptions`]: ", $path_str, "/struct.AsyncOptions.html")]
+                /// [`remove`]: #method.remove
+                pub async fn from_file(file: R::File, path_hint: Option<PathBuf>, opts: AsyncOptions<R>) -> Result<Self> {
+                    Ok(Self::from(AsyncDiskMmapFileMut::from_file(file, path_hint, opts).await?))
+                }
+
                 /// Make the mmap file read-only.
                 ///
                 /// # Notes
@@ -2084,7 +3086,7 @@ cfg_async! {
                 #[doc = concat!("[Read more]: ", $path_str, "/struct.AsyncMmapFileMut.html#methods.set_remove_on_drop")]
                 ///
                 #[inline]
-                pub fn freeze(mut self) -> Result<AsyncMmapFile> {
+                pub fn freeze(mut self) -> Result<AsyncMmapFile<R>> {
                     let empty = AsyncMmapFileMutInner::Empty(AsyncEmptyMmapFile::default());
                     // swap the inner to empty
                     let inner = mem::replace(&mut self.inner, empty);
@@ -2126,7 +3128,7 @@ cfg_async! {
                 #[doc = concat!("[Read more]: ", $path_str, "/struct.AsyncMmapFileMut.html#methods.set_remove_on_drop")]
                 ///
                 #[inline]
-                pub fn freeze_exec(mut self) -> Result<AsyncMmapFile> {
+                pub fn freeze_exec(mut self) -> Result<AsyncMmapFile<R>> {
                     let empty = AsyncMmapFileMutInner::Empty(AsyncEmptyMmapFile::default());
                     // swap the inner to empty
                     let inner = mem::replace(&mut self.inner, empty);
@@ -2137,6 +3139,86 @@ cfg_async! {
                     }
                 }
 
+                /// Locks the file for shared usage without blocking the executor thread,
+                /// and returns a guard which unlocks the file on drop.
+                ///
+                /// If the file is currently locked exclusively, it waits on the blocking pool of the runtime
+                /// until the lock is acquired, see [`AsyncMmapFileExt::lock_shared_offload`].
+                /// The guard derefs to the data of the mmap file.
+                ///
+                /// # Notes
+                /// This function will do nothing but return the guard if the underlying is not a real file, e.g. in-memory.
+                ///
+                /// [`AsyncMmapFileExt::lock_shared_offload`]: trait.AsyncMmapFileExt.html#tymethod.lock_shared_offload
+                pub async fn lock_shared_guard(&mut self) -> Result<AsyncFileLockGuard<'_, Self>> {
+                    self.lock_shared_offload().await?;
+                    Ok(AsyncFileLockGuard::new(self))
+                }
+
+                /// Locks the file for exclusive usage without blocking the executor thread,
+                /// and returns a guard which unlocks the file on drop.
+                ///
+                /// If the file is currently locked, it waits on the blocking pool of the runtime
+                /// until the lock is acquired, see [`AsyncMmapFileExt::lock_exclusive_offload`].
+                /// The guard derefs mutably to the data of the mmap file.
+                ///
+                /// # Notes
+                /// This function will do nothing but return the guard if the underlying is not a real file, e.g. in-memory.
+                ///
+                /// [`AsyncMmapFileExt::lock_exclusive_offload`]: trait.AsyncMmapFileExt.html#tymethod.lock_exclusive_offload
+                pub async fn lock_exclusive_guard(&mut self) -> Result<AsyncFileLockGuardMut<'_, Self>> {
+                    self.lock_exclusive_offload().await?;
+                    Ok(AsyncFileLockGuardMut::new(self))
+                }
+
+                /// Consumes the mmap file, returns an [`AsyncOwnedMmapFileWriter`] base on the given `offset`,
+                /// which helps read or write data from mmap like a normal File.
+                ///
+                /// Unlike [`AsyncMmapFileMutExt::writer`], the writer owns the mmap file, so it can be returned from functions
+                /// or moved into spawned tasks. The mmap file can be taken back by [`AsyncOwnedMmapFileWriter::into_inner`].
+                ///
+                /// # Notes
+                /// If you use a writer to write data to mmap, there is no guarantee all
+                /// data will be durably stored. So you need to call [`flush`]/[`flush_range`]/[`flush_async`]/[`flush_async_range`] in [`AsyncMmapFileMutExt`]
+                /// to guarantee all data will be durably stored.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return
+                ///  `Err(Error::from(ErrorKind::EOF))`.
+                ///
+                /// [`AsyncOwnedMmapFileWriter`]: structs.AsyncOwnedMmapFileWriter.html
+                /// [`AsyncOwnedMmapFileWriter::into_inner`]: structs.AsyncOwnedMmapFileWriter.html#methods.into_inner
+                /// [`AsyncMmapFileMutExt::writer`]: traits.AsyncMmapFileMutExt.html#methods.writer
+                /// [`flush`]: traits.AsyncMmapFileMutExt.html#methods.flush
+                /// [`flush_range`]: traits.AsyncMmapFileMutExt.html#methods.flush_range
+                /// [`flush_async`]: traits.AsyncMmapFileMutExt.html#methods.flush_async
+                /// [`flush_async_range`]: traits.AsyncMmapFileMutExt.html#methods.flush_async_range
+                /// [`AsyncMmapFileMutExt`]: traits.AsyncMmapFileMutExt.html
+                pub fn into_writer(self, offset: usize) -> Result<AsyncOwnedMmapFileWriter<R>> {
+                    let len = self.len();
+                    if len <= offset {
+                        Err(Error::from(ErrorKind::EOF))
+                    } else {
+                        Ok(AsyncOwnedMmapFileWriter::new(self, offset, len - offset))
+                    }
+                }
+
+                /// Consumes the mmap file, returns an [`AsyncOwnedMmapFileWriter`] base on the given `offset` and `len`,
+                /// which helps read or write data from mmap like a normal File.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return
+                ///  `Err(Error::from(ErrorKind::EOF))`.
+                ///
+                /// [`AsyncOwnedMmapFileWriter`]: structs.AsyncOwnedMmapFileWriter.html
+                pub fn into_range_writer(self, offset: usize, len: usize) -> Result<AsyncOwnedMmapFileWriter<R>> {
+                    if self.len() < offset + len {
+                        Err(Error::from(ErrorKind::EOF))
+                    } else {
+                        Ok(AsyncOwnedMmapFileWriter::new(self, offset, len))
+                    }
+                }
+
                 /// Returns whether remove the underlying file on drop.
                 #[inline]
                 pub fn get_remove_on_drop(&self) -> bool {
@@ -2166,7 +3248,7 @@ cfg_async! {
                         AsyncMmapFileMutInner::Disk(disk) => {
                             disk.flush()?;
                             if max_sz >= 0 {
-                                disk.file.set_len(max_sz as u64).await.map_err(From::from)
+                                R::set_len(&disk.file, max_sz as u64).await.map_err(From::from)
                             } else {
                                 Ok(())
                             }
@@ -2185,22 +3267,126 @@ cfg_async! {
                         AsyncMmapFileMutInner::Disk(disk) => {
                             let path = disk.path;
                             drop(disk.mmap);
-                            disk.file
-                                .set_len(0)
-                                .await?;
+                            R::set_len(&disk.file, 0).await?;
                             drop(disk.file);
-                            remove_file(path)
+                            if path.as_os_str().is_empty() {
+                                return Ok(());
+                            }
+                            R::remove_file(path)
                                 .await
                                 .map_err(From::from)
                         },
                         _ => Ok(()),
                     }
                 }
+
+                /// Flush the data, fsync the file, and if the file is created by [`create_atomic`],
+                /// rename it over the target path and sync the parent directory.
+                ///
+                /// # Notes
+                /// If the rename fails, the temporary file is removed and the target path is left untouched.
+                ///
+                #[doc = concat!("[`create_atomic`]: ", $path_str, "/struct.AsyncMmapFileMut.html#method.create_atomic")]
+                pub async fn commit(mut self) -> Result<()> {
+                    let empty = AsyncMmapFileMutInner::Empty(AsyncEmptyMmapFile::default());
+                    // swap the inner to empty
+                    let inner = mem::replace(&mut self.inner, empty);
+                    let commit_to = self.commit_to.take();
+                    // the temporary file is handled below
+                    self.remove_on_drop = false;
+                    match inner {
+                        AsyncMmapFileMutInner::Disk(disk) => {
+                            let tmp = disk.path.clone();
+                            let mut rst = disk.flush();
+                            if rst.is_ok() {
+                                rst = R::sync_all(&disk.file)
+                                    .await
+                                    .map_err(|e| Error::new(ErrorKind::SyncFileFailed, e));
+                            }
+                            drop(disk);
+                            let target = match commit_to {
+                                Some(target) => target,
+                                None => return rst,
+                            };
+
+                            if rst.is_ok() {
+                                let (from, to) = (tmp.clone(), target.clone());
+                                rst = R::spawn_blocking(move || std::fs::rename(from, to))
+                                    .await
+                                    .and_then(|rst| rst)
+                                    .map_err(|e| Error::new_source_msg(ErrorKind::IO, target.to_string_lossy(), e));
+                            }
+                            match rst {
+                                Ok(_) => crate::utils::sync_parent_in::<R>(&target).await,
+                                Err(e) => {
+                                    let _ = R::remove_file(tmp).await;
+                                    Err(e)
+                                }
+                            }
+                        },
+                        _ => Ok(()),
+                    }
+                }
+            }
+
+            impl<R: AsyncRuntime> AsRef<[u8]> for AsyncMmapFileMut<R> {
+                #[inline]
+                fn as_ref(&self) -> &[u8] {
+                    self.as_slice()
+                }
+            }
+
+            impl<R: AsyncRuntime> AsMut<[u8]> for AsyncMmapFileMut<R> {
+                #[inline]
+                fn as_mut(&mut self) -> &mut [u8] {
+                    self.as_mut_slice()
+                }
             }
 
-            impl_constructor_for_memory_mmap_file_mut!(AsyncMemoryMmapFileMut, AsyncMmapFileMut, "AsyncMmapFileMut", $path_str);
+            impl_constructor_for_memory_mmap_file_mut!(AsyncMemoryMmapFileMut, AsyncMmapFileMut<R>, "AsyncMmapFileMut", $path_str);
 
-            impl_drop!(AsyncMmapFileMut, AsyncMmapFileMutInner, AsyncEmptyMmapFile);
+            impl_drop!(AsyncMmapFileMut<R>, AsyncMmapFileMutInner, AsyncEmptyMmapFile);
+        };
+    }
+
+    macro_rules! test_async_atomic_mmap_file {
+        ($filename_prefix: literal, $attr: meta) => {
+            #[cfg(test)]
+            mod atomic_tests {
+                use super::*;
+                use scopeguard::defer;
+
+                #[$attr]
+                async fn test_create_atomic() {
+                    let path = concat!($filename_prefix, "_create_atomic.txt");
+                    std::fs::write(path, "old data").unwrap();
+                    defer!(std::fs::remove_file(path).unwrap());
+                    let tmp_files = || {
+                        std::fs::read_dir(".")
+                            .unwrap()
+                            .filter(|e| {
+                                let name = e.as_ref().unwrap().file_name();
+                                name.to_string_lossy().starts_with(concat!(".", $filename_prefix, "_create_atomic.txt."))
+                            })
+                            .count()
+                    };
+
+                    let mut file = AsyncMmapFileMut::create_atomic_with_options(path, AsyncOptions::new().max_size(12)).await.unwrap();
+                    file.write_all("some data...".as_bytes(), 0).unwrap();
+                    assert_eq!(tmp_files(), 1);
+                    drop(file);
+                    assert_eq!(tmp_files(), 0);
+                    assert_eq!(std::fs::read(path).unwrap(), "old data".as_bytes());
+
+                    let mut file = AsyncMmapFileMut::create_atomic(path).await.unwrap();
+                    file.truncate(12).await.unwrap();
+                    file.write_all("some data...".as_bytes(), 0).unwrap();
+                    assert_eq!(std::fs::read(path).unwrap(), "old data".as_bytes());
+                    file.commit().await.unwrap();
+                    assert_eq!(tmp_files(), 0);
+                    assert_eq!(std::fs::read(path).unwrap(), "some data...".as_bytes());
+                }
+            }
         };
     }
 
@@ -2274,14 +3460,22 @@ cfg_async! {
     }
 }
 
+cfg_async!(
+    mod async_impl;
+    pub use async_impl::{AsyncMmapFile, AsyncMmapFileExt, AsyncMmapFileMut, AsyncMmapFileMutExt};
+);
+
 cfg_async_std!(
-    pub(crate) mod async_std_impl;
+    #[cfg(test)]
+    mod async_std_impl;
 );
 
 cfg_smol!(
-    pub(crate) mod smol_impl;
+    #[cfg(test)]
+    mod smol_impl;
 );
 
 cfg_tokio!(
-    pub(crate) mod tokio_impl;
+    #[cfg(test)]
+    mod tokio_impl;
 );
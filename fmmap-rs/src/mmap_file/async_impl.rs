@@ -0,0 +1,24 @@
+use crate::disk::{AsyncDiskMmapFile, AsyncDiskMmapFileMut};
+use crate::empty::AsyncEmptyMmapFile;
+use crate::error::{Error, ErrorKind, Result};
+use crate::memory::{AsyncMemoryMmapFile, AsyncMemoryMmapFileMut};
+use crate::metadata::MetaData;
+use crate::{
+    AsyncFileLockGuard, AsyncFileLockGuardMut, AsyncMmapFileReader, AsyncMmapFileWriter,
+    AsyncOptions, AsyncOwnedMmapFileReader, AsyncOwnedMmapFileWriter, AsyncRuntime,
+};
+use async_trait::async_trait;
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::mem;
+use std::path::{Path, PathBuf};
+
+declare_async_mmap_file_ext!();
+
+declare_async_mmap_file_mut_ext!();
+
+declare_and_impl_inners!();
+
+declare_and_impl_async_mmap_file!("tokio_async", "tokio_test", "tokio");
+
+delcare_and_impl_async_mmap_file_mut!("tokio_async", "tokio_test", "tokio");
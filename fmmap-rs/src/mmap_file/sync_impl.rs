@@ -1,14 +1,41 @@
+#[cfg(unix)]
+use crate::anon::{AnonMmapFile, AnonMmapFileMut};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::dir::Dir;
 use crate::disk::{DiskMmapFile, DiskMmapFileMut};
 use crate::empty::EmptyMmapFile;
 use crate::error::{Error, ErrorKind, Result};
+use crate::lock_guard::{FileLockGuard, FileLockGuardMut};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::memfd::{MemfdMmapFile, MemfdMmapFileMut};
 use crate::memory::{MemoryMmapFile, MemoryMmapFileMut};
 use crate::metadata::MetaData;
+use crate::mmap_file::atomic_tmp_file_name;
 use crate::options::Options;
-use crate::{MmapFileReader, MmapFileWriter};
+use crate::varint::{
+    decode_uvarint, encode_uvarint, zigzag_decode, zigzag_encode, LengthPrefix, MAX_VARINT_LEN,
+};
+use crate::utils::sync_parent;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::utils::sync_parent_at;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::SealFlags;
+use crate::{
+    Advice, UncheckedAdvice, MmapFileReader, MmapFileWriter, OwnedMmapFileReader, OwnedMmapFileWriter,
+    RangeLockGuard,
+};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use rustix::fs::{AtFlags, OFlags, CWD};
 use std::borrow::Cow;
-use std::io::{Cursor, Write};
+use std::fs::File;
+use std::io::{Cursor, IoSlice, IoSliceMut, Write};
 use std::mem;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, OwnedFd};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicU32;
+#[cfg(target_has_atomic = "64")]
+use std::sync::atomic::AtomicU64;
 
 /// Utility methods to [`MmapFile`]
 ///
@@ -49,9 +76,48 @@ pub trait MmapFileExt {
         }
     }
 
+    /// Returns a reference to the plain-old-data `T` at `offset`, without copying.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+    /// If the data at `offset` is not aligned for `T` in memory, it would return
+    /// `Err(Error::from(ErrorKind::Misaligned(offset, align_of::<T>())))`.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    fn view<T: bytemuck::Pod>(&self, offset: usize) -> Result<&T> {
+        crate::view::view(self.as_slice(), offset)
+    }
+
+    /// Returns a slice of `count` plain-old-data `T`s starting from `offset`, without copying.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+    /// If the data at `offset` is not aligned for `T` in memory, it would return
+    /// `Err(Error::from(ErrorKind::Misaligned(offset, align_of::<T>())))`.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    fn view_slice<T: bytemuck::Pod>(&self, offset: usize, count: usize) -> Result<&[T]> {
+        crate::view::view_slice(self.as_slice(), offset, count)
+    }
+
     /// Returns the path of the inner file.
+    ///
+    /// The path is empty if the mmap is not backed by a named file, e.g. an in-memory mmap,
+    /// or a mmap of an opened file without a path hint, see [`file_path`].
+    ///
+    /// [`file_path`]: #method.file_path
     fn path(&self) -> &Path;
 
+    /// Returns the path of the inner file, or `None` if the mmap is not backed by a named file.
+    fn file_path(&self) -> Option<&Path> {
+        let path = self.path();
+        if path.as_os_str().is_empty() {
+            None
+        } else {
+            Some(path)
+        }
+    }
+
     /// Returns the path buf of the inner file.
     fn path_buf(&self) -> PathBuf {
         self.path().to_path_buf()
@@ -188,6 +254,124 @@ pub trait MmapFileExt {
     /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
     fn unlock(&self) -> Result<()>;
 
+    /// Locks the range `[offset, offset + len)` of the file for shared usage, blocking if the range is currently locked exclusively.
+    /// If `len` is 0, the range extends to the end of the file and beyond.
+    ///
+    /// Returns a guard which unlocks the range on drop. On Linux, the locks are open file description
+    /// locks, which belong to the opened file rather than the process, so they also exclude the other
+    /// mmap files of the same path in this process. Other unix platforms use the POSIX record locks.
+    ///
+    /// # Notes
+    /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+    /// Byte-range locks are only supported on unix platforms.
+    /// On Linux, the file is reopened through `/proc/self/fd` for the guard, so an error is returned if `/proc` is not mounted.
+    fn lock_range_shared(&self, offset: u64, len: u64) -> Result<RangeLockGuard>;
+
+    /// Locks the range `[offset, offset + len)` of the file for exclusive usage, blocking if the range is currently locked.
+    /// If `len` is 0, the range extends to the end of the file and beyond.
+    ///
+    /// Returns a guard which unlocks the range on drop. On Linux, the locks are open file description
+    /// locks, which belong to the opened file rather than the process, so they also exclude the other
+    /// mmap files of the same path in this process. Other unix platforms use the POSIX record locks.
+    ///
+    /// # Notes
+    /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+    /// Byte-range locks are only supported on unix platforms.
+    /// On Linux, the file is reopened through `/proc/self/fd` for the guard, so an error is returned if `/proc` is not mounted.
+    /// The file must be opened for writing, e.g. it is not a read-only mmap file.
+    fn lock_range_exclusive(&self, offset: u64, len: u64) -> Result<RangeLockGuard>;
+
+    /// Locks the range `[offset, offset + len)` of the file for shared usage, or returns an error if the range is currently locked exclusively.
+    /// If `len` is 0, the range extends to the end of the file and beyond.
+    ///
+    /// Returns a guard which unlocks the range on drop. On Linux, the locks are open file description
+    /// locks, which belong to the opened file rather than the process, so they also exclude the other
+    /// mmap files of the same path in this process. Other unix platforms use the POSIX record locks.
+    ///
+    /// # Notes
+    /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+    /// Byte-range locks are only supported on unix platforms.
+    /// On Linux, the file is reopened through `/proc/self/fd` for the guard, so an error is returned if `/proc` is not mounted.
+    fn try_lock_range_shared(&self, offset: u64, len: u64) -> Result<RangeLockGuard>;
+
+    /// Locks the range `[offset, offset + len)` of the file for exclusive usage, or returns an error if the range is currently locked.
+    /// If `len` is 0, the range extends to the end of the file and beyond.
+    ///
+    /// Returns a guard which unlocks the range on drop. On Linux, the locks are open file description
+    /// locks, which belong to the opened file rather than the process, so they also exclude the other
+    /// mmap files of the same path in this process. Other unix platforms use the POSIX record locks.
+    ///
+    /// # Notes
+    /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+    /// Byte-range locks are only supported on unix platforms.
+    /// On Linux, the file is reopened through `/proc/self/fd` for the guard, so an error is returned if `/proc` is not mounted.
+    /// The file must be opened for writing, e.g. it is not a read-only mmap file.
+    fn try_lock_range_exclusive(&self, offset: u64, len: u64) -> Result<RangeLockGuard>;
+
+    /// Advises the OS how the whole mmap is going to be accessed.
+    ///
+    /// # Notes
+    /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+    fn advise(&self, advice: Advice) -> Result<()>;
+
+    /// Advises the OS how the range `[offset, offset + len)` of the mmap is going to be accessed.
+    ///
+    /// # Notes
+    /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+    fn advise_range(&self, advice: Advice, offset: usize, len: usize) -> Result<()>;
+
+    /// Advises the OS with a hint which may change the contents of the whole mmap,
+    /// see [`unchecked_advise_range`].
+    ///
+    /// # Safety
+    /// See [`unchecked_advise_range`].
+    ///
+    /// [`unchecked_advise_range`]: trait.MmapFileExt.html#tymethod.unchecked_advise_range
+    unsafe fn unchecked_advise(&self, advice: UncheckedAdvice) -> Result<()> {
+        self.unchecked_advise_range(advice, 0, self.len())
+    }
+
+    /// Advises the OS with a hint which may change the contents of the range `[offset, offset + len)`.
+    ///
+    /// # Safety
+    /// On private mappings (e.g. copy-on-write or anonymous memory), the range may read back as zeros
+    /// afterwards. The caller must ensure there are no live references into the range,
+    /// e.g. slices returned by [`as_slice`], and must not rely on the contents of the range.
+    ///
+    /// # Notes
+    /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+    ///
+    /// [`as_slice`]: trait.MmapFileExt.html#tymethod.as_slice
+    unsafe fn unchecked_advise_range(
+        &self,
+        advice: UncheckedAdvice,
+        offset: usize,
+        len: usize,
+    ) -> Result<()>;
+
+    /// Returns the number of pages of the mmap which are resident in memory (e.g. in the page cache).
+    ///
+    /// # Notes
+    /// In-memory mmaps are always fully resident.
+    fn resident_pages(&self) -> Result<usize>;
+
+    /// Returns the ratio of the pages in the range `[offset, offset + len)` which are resident
+    /// in memory, from `0.0` (nothing is resident) to `1.0` (fully resident).
+    ///
+    /// # Notes
+    /// In-memory mmaps are always fully resident.
+    fn residency_ratio(&self, offset: usize, len: usize) -> Result<f64>;
+
+    /// Asks the OS to read the range `[offset, offset + len)` of the mmap ahead, so that
+    /// later accesses do not block on page faults.
+    ///
+    /// # Notes
+    /// This function will do nothing if the underlying is not a real file, e.g. in-memory.
+    #[inline]
+    fn prefetch(&self, offset: usize, len: usize) -> Result<()> {
+        self.advise_range(Advice::WillNeed, offset, len)
+    }
+
     /// Read bytes to the dst buf from the offset, returns how many bytes read.
     fn read(&self, dst: &mut [u8], offset: usize) -> usize {
         let buf = self.as_slice();
@@ -201,7 +385,7 @@ pub trait MmapFileExt {
                 dst.copy_from_slice(&buf[offset..offset + dst_len]);
                 dst_len
             } else {
-                dst.copy_from_slice(&buf[offset..offset + remaining]);
+                dst[..remaining].copy_from_slice(&buf[offset..offset + remaining]);
                 remaining
             }
         }
@@ -225,6 +409,35 @@ pub trait MmapFileExt {
         }
     }
 
+    /// Read bytes to the dst bufs in order from the offset, returns how many bytes read.
+    ///
+    /// A buffer is filled completely before the next buffer is read into,
+    /// the reading stops at the end of the mmap.
+    fn read_vectored_at(&self, dsts: &mut [IoSliceMut<'_>], offset: usize) -> usize {
+        let mut n = 0;
+        for dst in dsts.iter_mut() {
+            let read = self.read(dst, offset + n);
+            n += read;
+            if read < dst.len() {
+                break;
+            }
+        }
+        n
+    }
+
+    /// Returns the data of the ranges `(offset, len)` of the mmap without copying,
+    /// in the same order as `ranges`.
+    ///
+    /// # Errors
+    /// If there's not enough data for any range, it would return
+    /// `Err(Error::from(ErrorKind::EOF))`.
+    fn gather(&self, ranges: &[(usize, usize)]) -> Result<Vec<&[u8]>> {
+        ranges
+            .iter()
+            .map(|&(offset, len)| self.bytes(offset, len))
+            .collect()
+    }
+
     /// Read a signed 8 bit integer from offset.
     fn read_i8(&self, offset: usize) -> Result<i8> {
         let buf = self.as_slice();
@@ -382,6 +595,36 @@ pub trait MmapFileExt {
     fn read_f64_le(&self, offset: usize) -> Result<f64> {
         read_impl!(self, offset, f64::from_le_bytes)
     }
+
+    /// Read an unsigned LEB128 variable-length integer from offset,
+    /// returns the integer and the number of bytes read.
+    fn read_uvarint(&self, offset: usize) -> Result<(u64, usize)> {
+        match self.as_slice().get(offset..) {
+            Some(buf) => decode_uvarint(buf),
+            None => Err(Error::from(ErrorKind::EOF)),
+        }
+    }
+
+    /// Read a zigzag encoded signed LEB128 variable-length integer from offset,
+    /// returns the integer and the number of bytes read.
+    fn read_varint(&self, offset: usize) -> Result<(i64, usize)> {
+        self.read_uvarint(offset)
+            .map(|(val, n)| (zigzag_decode(val), n))
+    }
+
+    /// Read a record prefixed by its length from offset,
+    /// returns the record and the number of bytes read, including the prefix.
+    fn read_length_prefixed(&self, offset: usize, prefix: LengthPrefix) -> Result<(&[u8], usize)> {
+        let buf = match self.as_slice().get(offset..) {
+            Some(buf) => buf,
+            None => return Err(Error::from(ErrorKind::EOF)),
+        };
+        let (len, n) = prefix.decode(buf)?;
+        match buf.get(n..).and_then(|record| record.get(..len)) {
+            Some(record) => Ok((record, n + len)),
+            None => Err(Error::from(ErrorKind::EOF)),
+        }
+    }
 }
 
 /// Utility methods to [`MmapFileMut`]
@@ -404,6 +647,29 @@ pub trait MmapFileMutExt {
     /// Whether mmap is copy on write
     fn is_cow(&self) -> bool;
 
+    /// Returns a reference to the `AtomicU32` at `offset`, e.g. for coordinating multiple
+    /// processes over a shared mmap.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+    /// If the data at `offset` is not 4-byte aligned in memory, it would return
+    /// `Err(Error::from(ErrorKind::Misaligned(offset, 4)))`.
+    fn atomic_u32(&mut self, offset: usize) -> Result<&AtomicU32> {
+        crate::view::atomic_u32(self.as_mut_slice(), offset)
+    }
+
+    /// Returns a reference to the `AtomicU64` at `offset`, e.g. for coordinating multiple
+    /// processes over a shared mmap.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+    /// If the data at `offset` is not 8-byte aligned in memory, it would return
+    /// `Err(Error::from(ErrorKind::Misaligned(offset, 8)))`.
+    #[cfg(target_has_atomic = "64")]
+    fn atomic_u64(&mut self, offset: usize) -> Result<&AtomicU64> {
+        crate::view::atomic_u64(self.as_mut_slice(), offset)
+    }
+
     /// bytes_mut returns mutable data starting from offset off of size sz.
     ///
     /// # Errors
@@ -418,6 +684,30 @@ pub trait MmapFileMutExt {
         }
     }
 
+    /// Returns a mutable reference to the plain-old-data `T` at `offset`, without copying.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+    /// If the data at `offset` is not aligned for `T` in memory, it would return
+    /// `Err(Error::from(ErrorKind::Misaligned(offset, align_of::<T>())))`.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    fn view_mut<T: bytemuck::Pod>(&mut self, offset: usize) -> Result<&mut T> {
+        crate::view::view_mut(self.as_mut_slice(), offset)
+    }
+
+    /// Returns a mutable slice of `count` plain-old-data `T`s starting from `offset`, without copying.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+    /// If the data at `offset` is not aligned for `T` in memory, it would return
+    /// `Err(Error::from(ErrorKind::Misaligned(offset, align_of::<T>())))`.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytemuck")))]
+    fn view_slice_mut<T: bytemuck::Pod>(&mut self, offset: usize, count: usize) -> Result<&mut [T]> {
+        crate::view::view_slice_mut(self.as_mut_slice(), offset, count)
+    }
+
     /// Fill 0 to the specific range
     fn zero_range(&mut self, start: usize, end: usize) {
         let buf = self.as_mut_slice();
@@ -567,6 +857,22 @@ pub trait MmapFileMutExt {
         }
     }
 
+    /// Write bytes of the src bufs in order to the mmap from the offset, returns how many bytes written.
+    ///
+    /// A buffer is written completely before the next buffer,
+    /// the writing stops at the end of the mmap.
+    fn write_vectored_at(&mut self, srcs: &[IoSlice<'_>], offset: usize) -> usize {
+        let mut n = 0;
+        for src in srcs {
+            let written = self.write(src, offset + n);
+            n += written;
+            if written < src.len() {
+                break;
+            }
+        }
+        n
+    }
+
     /// Writes a signed 8 bit integer to mmap from the offset.
     fn write_i8(&mut self, val: i8, offset: usize) -> Result<()> {
         self.write_all(&[val as u8], offset)
@@ -696,6 +1002,36 @@ pub trait MmapFileMutExt {
     fn write_f64_le(&mut self, val: f64, offset: usize) -> Result<()> {
         self.write_all(&val.to_le_bytes(), offset)
     }
+
+    /// Writes an unsigned LEB128 variable-length integer to mmap from the offset,
+    /// returns the number of bytes written.
+    fn write_uvarint(&mut self, val: u64, offset: usize) -> Result<usize> {
+        let mut buf = [0; MAX_VARINT_LEN];
+        let n = encode_uvarint(val, &mut buf);
+        self.write_all(&buf[..n], offset).map(|_| n)
+    }
+
+    /// Writes a zigzag encoded signed LEB128 variable-length integer to mmap from the offset,
+    /// returns the number of bytes written.
+    fn write_varint(&mut self, val: i64, offset: usize) -> Result<usize> {
+        self.write_uvarint(zigzag_encode(val), offset)
+    }
+
+    /// Writes `src` prefixed by its length to mmap from the offset,
+    /// returns the number of bytes written, including the prefix.
+    ///
+    /// Nothing is written if there is not enough space for the prefix and `src`.
+    fn write_length_prefixed(&mut self, src: &[u8], offset: usize, prefix: LengthPrefix) -> Result<usize> {
+        let mut buf = [0; MAX_VARINT_LEN];
+        let n = prefix.encode(src.len(), &mut buf)?;
+        let end = offset.checked_add(n + src.len());
+        if end.map_or(true, |end| end > self.as_mut_slice().len()) {
+            return Err(Error::from(ErrorKind::EOF));
+        }
+        self.write_all(&buf[..n], offset)?;
+        self.write_all(src, offset + n)?;
+        Ok(n + src.len())
+    }
 }
 
 #[enum_dispatch(MmapFileExt)]
@@ -703,16 +1039,23 @@ enum MmapFileInner {
     Empty(EmptyMmapFile),
     Memory(MemoryMmapFile),
     Disk(DiskMmapFile),
+    #[cfg(unix)]
+    Anon(AnonMmapFile),
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    Memfd(MemfdMmapFile),
 }
 
 /// A read-only memory map file.
 ///
-/// There is 3 status of this struct:
+/// There is 5 status of this struct:
 /// - __Disk__: mmap to a real file
 /// - __Memory__: use [`Bytes`] to mock a mmap, which is useful for test and in-memory storage engine
+/// - __Anon__: a frozen anonymous mmap, which is not backed by any file (unix only)
+/// - __Memfd__: mmap to a memfd, e.g. received from another process, see [`MmapFile::from_fd`] (Linux only)
 /// - __Empty__: a state represents null mmap, which is helpful for drop, close the `MmapFile`. This state cannot be constructed directly.
 ///
 /// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
+/// [`MmapFile::from_fd`]: struct.MmapFile.html#method.from_fd
 #[repr(transparent)]
 pub struct MmapFile {
     inner: MmapFileInner,
@@ -726,6 +1069,12 @@ impl_from!(
     [EmptyMmapFile, MemoryMmapFile, DiskMmapFile]
 );
 
+#[cfg(unix)]
+impl_from!(MmapFile, MmapFileInner, [AnonMmapFile]);
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl_from!(MmapFile, MmapFileInner, [MemfdMmapFile]);
+
 impl MmapFile {
     /// Open a readable memory map backed by a file
     ///
@@ -848,6 +1197,158 @@ impl MmapFile {
             path, opts,
         )?))
     }
+
+    /// Mmap a file which has already been opened by the caller with [`Options`], e.g.
+    /// a file opened with `O_DIRECT`, relative to a directory fd or received over a unix socket.
+    ///
+    /// The file is never opened by `path_hint`, which is only reported by [`path`] and [`file_path`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_compile
+    /// use fmmap::{Options, MmapFile, MmapFileExt};
+    /// use std::fs::File;
+    /// # use scopeguard::defer;
+    ///
+    /// # let mut file = File::create("from_file_test.txt").unwrap();
+    /// # defer!(std::fs::remove_file("from_file_test.txt").unwrap());
+    /// # std::io::Write::write_all(&mut file, "some data...".as_bytes()).unwrap();
+    /// # drop(file);
+    ///
+    /// let file = File::open("from_file_test.txt").unwrap();
+    /// let mut file = MmapFile::from_file(file, None, Options::new()).unwrap();
+    /// assert!(file.file_path().is_none());
+    /// let mut buf = vec![0; "some data...".len()];
+    /// file.read_exact(buf.as_mut_slice(), 0);
+    /// assert_eq!(buf.as_slice(), "some data...".as_bytes());
+    /// ```
+    ///
+    /// [`Options`]: struct.Options.html
+    /// [`path`]: trait.MmapFileExt.html#tymethod.path
+    /// [`file_path`]: trait.MmapFileExt.html#method.file_path
+    pub fn from_file(file: File, path_hint: Option<PathBuf>, opts: Options) -> Result<Self> {
+        Ok(Self::from(DiskMmapFile::from_file(file, path_hint, opts)?))
+    }
+
+    /// Mmap the whole file of `fd` read-only, e.g. a memfd received from another process,
+    /// see [`MmapFileMut::memfd`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::{MmapFile, MmapFileExt, MmapFileMut, MmapFileMutExt, SealFlags};
+    ///
+    /// let mut file = MmapFileMut::memfd("from_fd", 12).unwrap();
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// let file = file.freeze().unwrap();
+    /// file.seal(SealFlags::SHRINK | SealFlags::GROW | SealFlags::WRITE).unwrap();
+    ///
+    /// // the fd can be sent to another process, e.g. over a unix socket
+    /// let fd = file.as_memfd().unwrap().try_clone_to_owned().unwrap();
+    /// let file = MmapFile::from_fd(fd).unwrap();
+    /// assert_eq!(file.as_slice(), "some data...".as_bytes());
+    /// ```
+    ///
+    /// [`MmapFileMut::memfd`]: struct.MmapFileMut.html#method.memfd
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    pub fn from_fd(fd: OwnedFd) -> Result<Self> {
+        Ok(Self::from(MemfdMmapFile::from_fd(fd)?))
+    }
+
+    /// Adds `seals` to the memfd backing the mmap file (`F_ADD_SEALS`), see [`MmapFileMut::seal`].
+    ///
+    /// [`MmapFileMut::seal`]: struct.MmapFileMut.html#method.seal
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    pub fn seal(&self, seals: SealFlags) -> Result<()> {
+        match &self.inner {
+            MmapFileInner::Memfd(memfd) => memfd.seal(seals),
+            _ => Err(Error::new_with_message(
+                ErrorKind::SealFailed,
+                "the mmap file is not backed by a memfd",
+            )),
+        }
+    }
+
+    /// Returns the fd of the memfd backing the mmap file, which can be duplicated and sent
+    /// to another process, or `None` if the mmap file is not backed by a memfd.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    pub fn as_memfd(&self) -> Option<BorrowedFd<'_>> {
+        match &self.inner {
+            MmapFileInner::Memfd(memfd) => Some(memfd.as_fd()),
+            _ => None,
+        }
+    }
+
+    /// Locks the file for shared usage, blocking if the file is currently locked exclusively,
+    /// and returns a guard which unlocks the file on drop.
+    ///
+    /// The guard derefs to the data of the mmap file.
+    ///
+    /// # Notes
+    /// This function will do nothing but return the guard if the underlying is not a real file, e.g. in-memory.
+    pub fn lock_shared_guard(&mut self) -> Result<FileLockGuard<'_, Self>> {
+        self.lock_shared()?;
+        Ok(FileLockGuard::new(self))
+    }
+
+    /// Locks the file for exclusive usage, blocking if the file is currently locked,
+    /// and returns a guard which unlocks the file on drop.
+    ///
+    /// The guard derefs to the data of the mmap file.
+    ///
+    /// # Notes
+    /// This function will do nothing but return the guard if the underlying is not a real file, e.g. in-memory.
+    pub fn lock_exclusive_guard(&mut self) -> Result<FileLockGuard<'_, Self>> {
+        self.lock_exclusive()?;
+        Ok(FileLockGuard::new(self))
+    }
+
+    /// Consumes the mmap file, returns an [`OwnedMmapFileReader`] base on the given `offset`,
+    /// which helps read data from mmap like a normal File.
+    ///
+    /// Unlike [`MmapFileExt::reader`], the reader owns the mmap file, so it can be returned from functions
+    /// or moved to other threads.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return
+    ///  `Err(Error::from(ErrorKind::EOF))`.
+    ///
+    /// [`OwnedMmapFileReader`]: structs.OwnedMmapFileReader.html
+    /// [`MmapFileExt::reader`]: traits.MmapFileExt.html#methods.reader
+    pub fn into_reader(self, offset: usize) -> Result<OwnedMmapFileReader> {
+        let len = self.len();
+        if len < offset {
+            Err(Error::from(ErrorKind::EOF))
+        } else {
+            Ok(OwnedMmapFileReader::new(self, offset, len - offset))
+        }
+    }
+
+    /// Consumes the mmap file, returns an [`OwnedMmapFileReader`] base on the given `offset` and `len`,
+    /// which helps read data from mmap like a normal File.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return
+    ///  `Err(Error::from(ErrorKind::EOF))`.
+    ///
+    /// [`OwnedMmapFileReader`]: structs.OwnedMmapFileReader.html
+    pub fn into_range_reader(self, offset: usize, len: usize) -> Result<OwnedMmapFileReader> {
+        if self.len() < offset + len {
+            Err(Error::from(ErrorKind::EOF))
+        } else {
+            Ok(OwnedMmapFileReader::new(self, offset, len))
+        }
+    }
+}
+
+impl AsRef<[u8]> for MmapFile {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
 }
 
 impl_constructor_for_memory_mmap_file!(MemoryMmapFile, MmapFile, "MmapFile", "sync");
@@ -857,20 +1358,30 @@ enum MmapFileMutInner {
     Empty(EmptyMmapFile),
     Memory(MemoryMmapFileMut),
     Disk(DiskMmapFileMut),
+    #[cfg(unix)]
+    Anon(AnonMmapFileMut),
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    Memfd(MemfdMmapFileMut),
 }
 
 /// A writable memory map file.
 ///
-/// There is 3 status of this struct:
+/// There is 5 status of this struct:
 /// - __Disk__: mmap to a real file
 /// - __Memory__: use [`BytesMut`] to mock a mmap, which is useful for test and in-memory storage engine
+/// - __Anon__: an anonymous mmap (`MAP_ANONYMOUS`), which is not backed by any file, see [`Options::anonymous`] (unix only)
+/// - __Memfd__: mmap to a memfd, which can be shared with another process by its fd, see [`MmapFileMut::memfd`] (Linux only)
 /// - __Empty__: a state represents null mmap, which is helpful for drop, remove, close the `MmapFileMut`. This state cannot be constructed directly.
 ///
 /// [`BytesMut`]: https://docs.rs/bytes/1.1.0/bytes/struct.BytesMut.html
+/// [`Options::anonymous`]: struct.Options.html#method.anonymous
+/// [`MmapFileMut::memfd`]: struct.MmapFileMut.html#method.memfd
 pub struct MmapFileMut {
     inner: MmapFileMutInner,
     remove_on_drop: bool,
     deleted: bool,
+    /// The target path if the file is created by `create_atomic`.
+    commit_to: Option<PathBuf>,
 }
 
 impl_from_mut!(
@@ -879,6 +1390,12 @@ impl_from_mut!(
     [EmptyMmapFile, MemoryMmapFileMut, DiskMmapFileMut]
 );
 
+#[cfg(unix)]
+impl_from_mut!(MmapFileMut, MmapFileMutInner, [AnonMmapFileMut]);
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl_from_mut!(MmapFileMut, MmapFileMutInner, [MemfdMmapFileMut]);
+
 impl_mmap_file_ext!(MmapFileMut);
 
 impl MmapFileMutExt for MmapFileMut {
@@ -1011,6 +1528,70 @@ impl MmapFileMut {
         )?))
     }
 
+    /// Create a temporary file in the same directory as `path` and mmap it,
+    /// the file is moved to `path` by [`commit`], so `path` holds either the old content
+    /// or the complete new content, even if the process crashes.
+    ///
+    /// The temporary file is removed on drop if [`commit`] is not invoked.
+    ///
+    /// # Notes
+    /// The new file is zero size, so before do write, you should truncate first.
+    /// Or you can use [`create_atomic_with_options`] and set `max_size` field for [`Options`] to enable directly write
+    /// without truncating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmmap::{MmapFileMut, MmapFileMutExt};
+    /// # use scopeguard::defer;
+    ///
+    /// let mut file = MmapFileMut::create_atomic("create_atomic_test.txt").unwrap();
+    /// # defer!(std::fs::remove_file("create_atomic_test.txt").unwrap());
+    /// file.truncate(12).unwrap();
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// file.commit().unwrap();
+    /// assert_eq!(std::fs::read("create_atomic_test.txt").unwrap(), "some data...".as_bytes());
+    /// ```
+    ///
+    /// [`commit`]: structs.MmapFileMut.html#methods.commit
+    /// [`create_atomic_with_options`]: structs.MmapFileMut.html#methods.create_atomic_with_options
+    /// [`Options`]: struct.Options.html
+    pub fn create_atomic<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::create_atomic_with_options(path, Options::new())
+    }
+
+    /// Create a temporary file in the same directory as `path` and mmap it with [`Options`],
+    /// the file is moved to `path` by [`commit`].
+    ///
+    /// The `read`, `write` and `create_new` fields of [`Options`] are always set to `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmmap::{MmapFileMut, MmapFileMutExt, Options};
+    /// # use scopeguard::defer;
+    ///
+    /// let opts = Options::new()
+    ///     // truncate to 100
+    ///     .max_size(100);
+    /// let mut file = MmapFileMut::create_atomic_with_options("create_atomic_with_options_test.txt", opts).unwrap();
+    /// # defer!(std::fs::remove_file("create_atomic_with_options_test.txt").unwrap());
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// file.commit().unwrap();
+    /// ```
+    ///
+    /// [`commit`]: structs.MmapFileMut.html#methods.commit
+    /// [`Options`]: struct.Options.html
+    pub fn create_atomic_with_options<P: AsRef<Path>>(path: P, opts: Options) -> Result<Self> {
+        let path = path.as_ref();
+        let tmp = path.with_file_name(atomic_tmp_file_name(path.file_name()));
+        let opts = opts.read(true).write(true).create_new(true);
+        let mut file = Self::from(DiskMmapFileMut::open_with_options(tmp, opts)?);
+        file.remove_on_drop = true;
+        file.commit_to = Some(path.to_path_buf());
+        Ok(file)
+    }
+
     /// Open or Create(if not exists) a file and mmap this file.
     ///
     /// # Notes
@@ -1366,6 +1947,42 @@ impl MmapFileMut {
         )?))
     }
 
+    /// Mmap a writable file which has already been opened by the caller with [`Options`], e.g.
+    /// a file opened with `O_TMPFILE`, relative to a directory fd or received over a unix socket.
+    ///
+    /// The file is never opened by `path_hint`, which is only reported by [`path`] and [`file_path`].
+    /// If the file is empty, it is truncated to `max_size` of [`Options`].
+    /// Without a `path_hint`, [`remove`] only truncates the file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_compile
+    /// use fmmap::{Options, MmapFileMut, MmapFileExt, MmapFileMutExt};
+    /// use std::fs::OpenOptions;
+    /// # use scopeguard::defer;
+    ///
+    /// let file = OpenOptions::new()
+    ///     .read(true)
+    ///     .write(true)
+    ///     .create(true)
+    ///     .open("from_file_mut_test.txt")
+    ///     .unwrap();
+    /// # defer!(std::fs::remove_file("from_file_mut_test.txt").unwrap());
+    /// let opts = Options::new().max_size(100);
+    /// let mut file = MmapFileMut::from_file(file, Some("from_file_mut_test.txt".into()), opts).unwrap();
+    /// assert_eq!(file.len(), 100);
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// file.flush().unwrap();
+    /// ```
+    ///
+    /// [`Options`]: struct.Options.html
+    /// [`path`]: trait.MmapFileExt.html#tymethod.path
+    /// [`file_path`]: trait.MmapFileExt.html#method.file_path
+    /// [`remove`]: struct.MmapFileMut.html#method.remove
+    pub fn from_file(file: File, path_hint: Option<PathBuf>, opts: Options) -> Result<Self> {
+        Ok(Self::from(DiskMmapFileMut::from_file(file, path_hint, opts)?))
+    }
+
     /// Make the mmap file read-only.
     ///
     /// # Notes
@@ -1394,6 +2011,10 @@ impl MmapFileMut {
             MmapFileMutInner::Empty(empty) => Ok(MmapFile::from(empty)), // unreachable, keep this for good measure
             MmapFileMutInner::Memory(memory) => Ok(MmapFile::from(memory.freeze())),
             MmapFileMutInner::Disk(disk) => Ok(MmapFile::from(disk.freeze()?)),
+            #[cfg(unix)]
+            MmapFileMutInner::Anon(anon) => Ok(MmapFile::from(anon.freeze()?)),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            MmapFileMutInner::Memfd(memfd) => Ok(MmapFile::from(memfd.freeze()?)),
         }
     }
 
@@ -1427,6 +2048,255 @@ impl MmapFileMut {
             MmapFileMutInner::Empty(empty) => Ok(MmapFile::from(empty)), // unreachable, keep this for good measure
             MmapFileMutInner::Memory(memory) => Ok(MmapFile::from(memory.freeze())),
             MmapFileMutInner::Disk(disk) => Ok(MmapFile::from(disk.freeze_exec()?)),
+            #[cfg(unix)]
+            MmapFileMutInner::Anon(anon) => Ok(MmapFile::from(anon.freeze_exec()?)),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            MmapFileMutInner::Memfd(memfd) => Ok(MmapFile::from(memfd.freeze_exec()?)),
+        }
+    }
+
+    /// Create an unnamed file in `dir` (`O_TMPFILE`) and mmap it.
+    ///
+    /// The file is invisible to other processes until it is linked into `dir` by [`link_at`],
+    /// so a fully written file can be published without leaving a partial file behind on crash.
+    /// The file is removed when it is dropped without being linked.
+    ///
+    /// # Examples
+    ///
+    /// ```no_compile
+    /// use fmmap::{Dir, MmapFileExt, MmapFileMut, MmapFileMutExt};
+    /// # use scopeguard::defer;
+    ///
+    /// let dir = Dir::open(".").unwrap();
+    /// let mut file = MmapFileMut::create_unnamed_in(&dir).unwrap();
+    /// assert!(file.file_path().is_none());
+    /// file.truncate(12).unwrap();
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    ///
+    /// file.link_at(&dir, "create_unnamed_in_test.txt").unwrap();
+    /// # defer!(std::fs::remove_file("create_unnamed_in_test.txt").unwrap());
+    /// assert_eq!(std::fs::read("create_unnamed_in_test.txt").unwrap(), "some data...".as_bytes());
+    /// ```
+    ///
+    /// [`link_at`]: struct.MmapFileMut.html#method.link_at
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    pub fn create_unnamed_in(dir: &Dir) -> Result<Self> {
+        Self::create_unnamed_in_with_options(dir, Options::new())
+    }
+
+    /// Create an unnamed file in `dir` (`O_TMPFILE`) and mmap it with [`Options`],
+    /// see [`create_unnamed_in`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_compile
+    /// use fmmap::{Dir, Options, MmapFileExt, MmapFileMut, MmapFileMutExt};
+    ///
+    /// let dir = Dir::open(".").unwrap();
+    /// let opts = Options::new().max_size(100);
+    /// let mut file = MmapFileMut::create_unnamed_in_with_options(&dir, opts).unwrap();
+    /// assert_eq!(file.len(), 100);
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// ```
+    ///
+    /// [`Options`]: struct.Options.html
+    /// [`create_unnamed_in`]: struct.MmapFileMut.html#method.create_unnamed_in
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    pub fn create_unnamed_in_with_options(dir: &Dir, opts: Options) -> Result<Self> {
+        let file = dir.open_file_at(Path::new("."), OFlags::TMPFILE | OFlags::RDWR)?;
+        Self::from_file(file, None, opts)
+    }
+
+    /// Flush the data and link the file at `path` relative to `dir` (`linkat`),
+    /// e.g. an unnamed file created by [`create_unnamed_in`], then sync the parent directory.
+    ///
+    /// The file is linked by its fd (`AT_EMPTY_PATH`) first, which needs no `/proc`, but requires
+    /// `CAP_DAC_READ_SEARCH` on kernels before 6.10. If it is not permitted, the file is linked
+    /// through `/proc/self/fd` instead, which requires `/proc` to be mounted.
+    ///
+    /// # Errors
+    /// If `path` already exists, or the mmap file is not backed by a file,
+    /// e.g. an in-memory mmap or a memfd, it would return an error.
+    ///
+    /// [`create_unnamed_in`]: struct.MmapFileMut.html#method.create_unnamed_in
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    pub fn link_at<P: AsRef<Path>>(&mut self, dir: &Dir, path: P) -> Result<()> {
+        let path = path.as_ref();
+        match &mut self.inner {
+            MmapFileMutInner::Disk(disk) => {
+                disk.flush()?;
+                match rustix::fs::linkat(&disk.file, "", dir, path, AtFlags::EMPTY_PATH) {
+                    // the fd can not be linked without `CAP_DAC_READ_SEARCH`
+                    Err(rustix::io::Errno::NOENT) | Err(rustix::io::Errno::PERM) => {
+                        let fd_path = format!("/proc/self/fd/{}", disk.file.as_raw_fd());
+                        rustix::fs::linkat(CWD, fd_path, dir, path, AtFlags::SYMLINK_FOLLOW)
+                    }
+                    rst => rst,
+                }
+                .map_err(|e| Error::new_source_msg(ErrorKind::IO, path.to_string_lossy(), e))?;
+                sync_parent_at(dir, path)?;
+                if let Some(path) = dir.path_hint(path) {
+                    disk.path = path;
+                }
+                Ok(())
+            }
+            _ => Err(Error::new_with_message(
+                ErrorKind::IO,
+                "the mmap file is not backed by a file",
+            )),
+        }
+    }
+
+    /// Create a memfd (`memfd_create`) of `size` zeroed bytes and mmap it.
+    ///
+    /// The memfd lives in memory and has no path, it can be shared with another process
+    /// by its fd, see [`as_memfd`] and [`MmapFile::from_fd`]. The memfd allows sealing, see [`seal`].
+    /// `name` is only used for debugging, e.g. it is shown as `/memfd:name` in `/proc/self/fd`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::{MetaDataExt, MmapFileExt, MmapFileMut, MmapFileMutExt, SealFlags};
+    ///
+    /// let mut file = MmapFileMut::memfd("memfd", 100).unwrap();
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    ///
+    /// let meta = file.metadata().unwrap();
+    /// assert_eq!(meta.len(), 100);
+    /// assert_eq!(meta.memfd_seals(), Some(SealFlags::empty()));
+    /// ```
+    ///
+    /// [`as_memfd`]: struct.MmapFileMut.html#method.as_memfd
+    /// [`MmapFile::from_fd`]: struct.MmapFile.html#method.from_fd
+    /// [`seal`]: struct.MmapFileMut.html#method.seal
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    pub fn memfd(name: &str, size: u64) -> Result<Self> {
+        Ok(Self::from(MemfdMmapFileMut::create(name, size)?))
+    }
+
+    /// Adds `seals` to the memfd backing the mmap file (`F_ADD_SEALS`), the seals can never be removed.
+    ///
+    /// [`SealFlags::WRITE`] cannot be added while the memfd is mapped writable,
+    /// [`freeze`] the mmap file first.
+    ///
+    /// # Errors
+    /// Failures, e.g. the mmap file is not backed by a memfd, or the memfd is sealed with [`SealFlags::SEAL`],
+    /// are reported as [`ErrorKind::SealFailed`].
+    ///
+    /// [`SealFlags::WRITE`]: struct.SealFlags.html#associatedconstant.WRITE
+    /// [`SealFlags::SEAL`]: struct.SealFlags.html#associatedconstant.SEAL
+    /// [`freeze`]: struct.MmapFileMut.html#method.freeze
+    /// [`ErrorKind::SealFailed`]: error/enum.ErrorKind.html#variant.SealFailed
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    pub fn seal(&self, seals: SealFlags) -> Result<()> {
+        match &self.inner {
+            MmapFileMutInner::Memfd(memfd) => memfd.seal(seals),
+            _ => Err(Error::new_with_message(
+                ErrorKind::SealFailed,
+                "the mmap file is not backed by a memfd",
+            )),
+        }
+    }
+
+    /// Returns the fd of the memfd backing the mmap file, which can be duplicated and sent
+    /// to another process, or `None` if the mmap file is not backed by a memfd.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    pub fn as_memfd(&self) -> Option<BorrowedFd<'_>> {
+        match &self.inner {
+            MmapFileMutInner::Memfd(memfd) => Some(memfd.as_fd()),
+            _ => None,
+        }
+    }
+
+    /// Locks the file for shared usage, blocking if the file is currently locked exclusively,
+    /// and returns a guard which unlocks the file on drop.
+    ///
+    /// The guard derefs to the data of the mmap file.
+    ///
+    /// # Notes
+    /// This function will do nothing but return the guard if the underlying is not a real file, e.g. in-memory.
+    pub fn lock_shared_guard(&mut self) -> Result<FileLockGuard<'_, Self>> {
+        self.lock_shared()?;
+        Ok(FileLockGuard::new(self))
+    }
+
+    /// Locks the file for exclusive usage, blocking if the file is currently locked,
+    /// and returns a guard which unlocks the file on drop.
+    ///
+    /// The guard derefs mutably to the data of the mmap file.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use fmmap::{MmapFileMut, Options};
+    /// # use scopeguard::defer;
+    ///
+    /// let mut file = MmapFileMut::create_with_options("lock_exclusive_guard_test.txt", Options::new().max_size(8)).unwrap();
+    /// # defer!(std::fs::remove_file("lock_exclusive_guard_test.txt").unwrap());
+    /// {
+    ///     let mut guard = file.lock_exclusive_guard().unwrap();
+    ///     guard[..4].copy_from_slice(b"abcd");
+    /// } // the file is unlocked here
+    /// ```
+    ///
+    /// # Notes
+    /// This function will do nothing but return the guard if the underlying is not a real file, e.g. in-memory.
+    pub fn lock_exclusive_guard(&mut self) -> Result<FileLockGuardMut<'_, Self>> {
+        self.lock_exclusive()?;
+        Ok(FileLockGuardMut::new(self))
+    }
+
+    /// Consumes the mmap file, returns an [`OwnedMmapFileWriter`] base on the given `offset`,
+    /// which helps read or write data from mmap like a normal File.
+    ///
+    /// Unlike [`MmapFileMutExt::writer`], the writer owns the mmap file, so it can be returned from functions
+    /// or moved to other threads. The mmap file can be taken back by [`OwnedMmapFileWriter::into_inner`].
+    ///
+    /// # Notes
+    /// If you use a writer to write data to mmap, there is no guarantee all
+    /// data will be durably stored. So you need to call [`flush`]/[`flush_range`]/[`flush_async`]/[`flush_async_range`] in [`MmapFileMutExt`]
+    /// to guarantee all data will be durably stored.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return
+    ///  `Err(Error::from(ErrorKind::EOF))`.
+    ///
+    /// [`OwnedMmapFileWriter`]: structs.OwnedMmapFileWriter.html
+    /// [`OwnedMmapFileWriter::into_inner`]: structs.OwnedMmapFileWriter.html#methods.into_inner
+    /// [`MmapFileMutExt::writer`]: traits.MmapFileMutExt.html#methods.writer
+    /// [`flush`]: traits.MmapFileMutExt.html#methods.flush
+    /// [`flush_range`]: traits.MmapFileMutExt.html#methods.flush_range
+    /// [`flush_async`]: traits.MmapFileMutExt.html#methods.flush_async
+    /// [`flush_async_range`]: traits.MmapFileMutExt.html#methods.flush_async_range
+    /// [`MmapFileMutExt`]: traits.MmapFileMutExt.html
+    pub fn into_writer(self, offset: usize) -> Result<OwnedMmapFileWriter> {
+        let len = self.len();
+        if len <= offset {
+            Err(Error::from(ErrorKind::EOF))
+        } else {
+            Ok(OwnedMmapFileWriter::new(self, offset, len - offset))
+        }
+    }
+
+    /// Consumes the mmap file, returns an [`OwnedMmapFileWriter`] base on the given `offset` and `len`,
+    /// which helps read or write data from mmap like a normal File.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return
+    ///  `Err(Error::from(ErrorKind::EOF))`.
+    ///
+    /// [`OwnedMmapFileWriter`]: structs.OwnedMmapFileWriter.html
+    pub fn into_range_writer(self, offset: usize, len: usize) -> Result<OwnedMmapFileWriter> {
+        if self.len() < offset + len {
+            Err(Error::from(ErrorKind::EOF))
+        } else {
+            Ok(OwnedMmapFileWriter::new(self, offset, len))
         }
     }
 
@@ -1481,6 +2351,9 @@ impl MmapFileMut {
                     .set_len(0)
                     .and_then(|_| {
                         drop(disk.file);
+                        if path.as_os_str().is_empty() {
+                            return Ok(());
+                        }
                         std::fs::remove_file(path)
                     })
                     .map_err(From::from)
@@ -1488,6 +2361,64 @@ impl MmapFileMut {
             _ => Ok(()),
         }
     }
+
+    /// Flush the data, fsync the file, and if the file is created by [`create_atomic`],
+    /// rename it over the target path and sync the parent directory.
+    ///
+    /// # Notes
+    /// If the rename fails, the temporary file is removed and the target path is left untouched.
+    ///
+    /// [`create_atomic`]: structs.MmapFileMut.html#methods.create_atomic
+    pub fn commit(mut self) -> Result<()> {
+        let empty = MmapFileMutInner::Empty(EmptyMmapFile::default());
+        // swap the inner to empty
+        let inner = mem::replace(&mut self.inner, empty);
+        let commit_to = self.commit_to.take();
+        // the temporary file is handled below
+        self.remove_on_drop = false;
+        match inner {
+            MmapFileMutInner::Disk(disk) => {
+                let tmp = disk.path.clone();
+                let rst = disk.flush().and_then(|_| {
+                    disk.file
+                        .sync_all()
+                        .map_err(|e| Error::new(ErrorKind::SyncFileFailed, e))
+                });
+                drop(disk);
+                let target = match commit_to {
+                    Some(target) => target,
+                    None => return rst,
+                };
+
+                match rst.and_then(|_| {
+                    std::fs::rename(&tmp, &target).map_err(|e| {
+                        Error::new_source_msg(ErrorKind::IO, target.to_string_lossy(), e)
+                    })
+                }) {
+                    Ok(_) => sync_parent(&target),
+                    Err(e) => {
+                        let _ = std::fs::remove_file(&tmp);
+                        Err(e)
+                    }
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl AsRef<[u8]> for MmapFileMut {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsMut<[u8]> for MmapFileMut {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
 }
 
 impl_constructor_for_memory_mmap_file_mut!(MemoryMmapFileMut, MmapFileMut, "MmapFileMut", "sync");
@@ -1495,3 +2426,151 @@ impl_constructor_for_memory_mmap_file_mut!(MemoryMmapFileMut, MmapFileMut, "Mmap
 impl_drop!(MmapFileMut, MmapFileMutInner, EmptyMmapFile);
 
 impl_sync_tests!("", MmapFile, MmapFileMut);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_residency() {
+        let file = MmapFile::memory_from_vec("memory_residency.txt", vec![1; 100]);
+        assert!(file.resident_pages().unwrap() >= 1);
+        assert_eq!(file.residency_ratio(0, 100).unwrap(), 1.0);
+        file.residency_ratio(90, 20).unwrap_err();
+        file.prefetch(0, 100).unwrap();
+    }
+
+    #[test]
+    fn test_create_atomic() {
+        let path = "create_atomic.txt";
+        std::fs::write(path, "old data").unwrap();
+        scopeguard::defer!(std::fs::remove_file(path).unwrap());
+        let tmp_files = || {
+            std::fs::read_dir(".")
+                .unwrap()
+                .filter(|e| {
+                    let name = e.as_ref().unwrap().file_name();
+                    name.to_string_lossy().starts_with(".create_atomic.txt.")
+                })
+                .count()
+        };
+
+        let mut file =
+            MmapFileMut::create_atomic_with_options(path, Options::new().max_size(12)).unwrap();
+        file.write_all("some data...".as_bytes(), 0).unwrap();
+        assert_eq!(tmp_files(), 1);
+        drop(file);
+        assert_eq!(tmp_files(), 0);
+        assert_eq!(std::fs::read(path).unwrap(), "old data".as_bytes());
+
+        let mut file = MmapFileMut::create_atomic(path).unwrap();
+        file.truncate(12).unwrap();
+        file.write_all("some data...".as_bytes(), 0).unwrap();
+        assert_eq!(std::fs::read(path).unwrap(), "old data".as_bytes());
+        file.commit().unwrap();
+        assert_eq!(tmp_files(), 0);
+        assert_eq!(std::fs::read(path).unwrap(), "some data...".as_bytes());
+    }
+
+    #[test]
+    fn test_memory_atomic() {
+        use std::sync::atomic::Ordering;
+
+        let mut file = MmapFileMut::memory_from_vec("memory_atomic.txt", vec![0; 100]);
+        let offset = (4 - file.as_slice().as_ptr() as usize % 4) % 4;
+        let a = file.atomic_u32(offset).unwrap();
+        a.fetch_add(1, Ordering::SeqCst);
+        assert_eq!(file.atomic_u32(offset).unwrap().load(Ordering::SeqCst), 1);
+        assert_eq!(
+            file.atomic_u32(offset + 1).unwrap_err().kind(),
+            ErrorKind::Misaligned(offset + 1, 4)
+        );
+    }
+
+    #[test]
+    fn test_varint() {
+        let mut file = MmapFileMut::memory_from_vec("varint.txt", vec![0; 32]);
+        assert_eq!(file.write_uvarint(300, 0).unwrap(), 2);
+        assert_eq!(file.write_varint(-65, 2).unwrap(), 2);
+        assert_eq!(
+            file.write_length_prefixed(b"abc", 4, LengthPrefix::Varint)
+                .unwrap(),
+            4
+        );
+        assert_eq!(
+            file.write_length_prefixed(b"abc", 8, LengthPrefix::U32)
+                .unwrap(),
+            7
+        );
+        // nothing is written if the record does not fit
+        file.write_length_prefixed(&[1; 20], 15, LengthPrefix::U32)
+            .unwrap_err();
+        assert_eq!(file.slice(15, 4), &[0; 4]);
+
+        let file = file.freeze().unwrap();
+        assert_eq!(file.read_uvarint(0).unwrap(), (300, 2));
+        assert_eq!(file.read_varint(2).unwrap(), (-65, 2));
+        assert_eq!(
+            file.read_length_prefixed(4, LengthPrefix::Varint).unwrap(),
+            (&b"abc"[..], 4)
+        );
+        assert_eq!(
+            file.read_length_prefixed(8, LengthPrefix::U32).unwrap(),
+            (&b"abc"[..], 7)
+        );
+        assert_eq!(
+            file.read_length_prefixed(8, LengthPrefix::U32Le)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::EOF
+        );
+        assert_eq!(file.read_uvarint(32).unwrap_err().kind(), ErrorKind::EOF);
+    }
+
+    #[test]
+    fn test_vectored_at() {
+        let mut file = MmapFileMut::memory_from_vec("vectored.txt", vec![0; 10]);
+        let srcs = [IoSlice::new(b"abc"), IoSlice::new(b""), IoSlice::new(b"defg")];
+        assert_eq!(file.write_vectored_at(&srcs, 1), 7);
+        // the writing stops at the end of the mmap
+        assert_eq!(file.write_vectored_at(&srcs, 6), 4);
+        assert_eq!(file.write_vectored_at(&srcs, 10), 0);
+        assert_eq!(file.as_slice(), b"\0abcdeabcd");
+
+        let file = file.freeze().unwrap();
+        let (mut a, mut b) = ([0; 4], [0; 8]);
+        let mut dsts = [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)];
+        assert_eq!(file.read_vectored_at(&mut dsts, 2), 8);
+        assert_eq!(&a, b"bcde");
+        assert_eq!(&b[..4], b"abcd");
+        assert_eq!(
+            file.gather(&[(6, 4), (1, 2), (10, 0)]).unwrap(),
+            vec![&b"abcd"[..], b"ab", b""]
+        );
+        assert_eq!(
+            file.gather(&[(0, 1), (8, 3)]).unwrap_err().kind(),
+            ErrorKind::EOF
+        );
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn test_create_unnamed_in() {
+        let path = "sync_create_unnamed_in_test.txt";
+        let dir = crate::Dir::open(".").unwrap();
+        let opts = Options::new().max_size(12);
+        let mut file = MmapFileMut::create_unnamed_in_with_options(&dir, opts).unwrap();
+        assert!(file.file_path().is_none());
+        file.write_all("some data...".as_bytes(), 0).unwrap();
+
+        file.link_at(&dir, path).unwrap();
+        scopeguard::defer!(std::fs::remove_file(path).unwrap());
+        assert_eq!(file.file_path(), Some(Path::new(".").join(path).as_path()));
+        assert_eq!(std::fs::read(path).unwrap(), "some data...".as_bytes());
+        // the path already exists
+        assert!(file.link_at(&dir, path).is_err());
+
+        let mut file = MmapFileMut::memory_from_vec("unnamed.txt", vec![0; 10]);
+        assert!(file.link_at(&dir, path).is_err());
+    }
+}
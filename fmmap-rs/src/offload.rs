@@ -0,0 +1,167 @@
+//! Helpers for the async mmap file operations which are offloaded to the blocking pool
+//! of the runtime.
+//!
+//! The offloaded closures must be `'static`, so they only capture the address of the mapping,
+//! and only pass it to the system calls, which fail gracefully instead of faulting
+//! if the mapping has been unmapped, e.g. the future is cancelled and the mmap file is dropped.
+#[cfg(unix)]
+use crate::advice::page_size;
+#[cfg(unix)]
+use crate::error::{Error, ErrorKind, Result};
+#[cfg(unix)]
+use std::fs::File;
+#[cfg(unix)]
+use std::sync::{Arc, Mutex};
+
+/// `MADV_POPULATE_READ` (since Linux 5.14), which is not exported by all the `libc` versions.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const MADV_POPULATE_READ: libc::c_int = 22;
+
+/// Returns the page aligned `(start, len)` which covers `[start, start + len)`.
+#[cfg(unix)]
+#[inline]
+fn page_range(start: usize, len: usize) -> (usize, usize) {
+    let page_size = page_size();
+    let aligned = start - start % page_size;
+    (aligned, len + start - aligned)
+}
+
+/// Faults in the pages of `[start, start + len)`, so the later accesses do not block on I/O.
+///
+/// This is best-effort: `MADV_POPULATE_READ` is used on Linux, and `MADV_WILLNEED`
+/// is used on the other unix platforms or if the kernel does not support it.
+#[cfg(unix)]
+pub(crate) fn populate(start: usize, len: usize) {
+    if len == 0 {
+        return;
+    }
+    let (aligned, len) = page_range(start, len);
+    let addr = aligned as *mut libc::c_void;
+    // Safety: madvise only reads the pages, and returns an error if the range is not mapped.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    unsafe {
+        if libc::madvise(addr, len, MADV_POPULATE_READ) == 0 {
+            return;
+        }
+    }
+    // Safety: same as above.
+    unsafe {
+        libc::madvise(addr, len, libc::MADV_WILLNEED);
+    }
+}
+
+/// Returns the address of `offset` in `buf`, or an error if `[offset, offset + len)` is out of bound.
+#[cfg(unix)]
+pub(crate) fn range_addr(buf: &[u8], offset: usize, len: usize) -> Result<usize> {
+    let end = offset.saturating_add(len);
+    if end > buf.len() {
+        return Err(Error::from(ErrorKind::OutOfBound(end, buf.len())));
+    }
+    Ok(buf.as_ptr() as usize + offset)
+}
+
+/// Flushes the modified pages of `[start, start + len)` to disk, see `msync(MS_SYNC)`.
+#[cfg(unix)]
+pub(crate) fn msync(start: usize, len: usize) -> std::io::Result<()> {
+    if len == 0 {
+        return Ok(());
+    }
+    let (aligned, len) = page_range(start, len);
+    // Safety: msync does not access the memory, and returns an error if the range is not mapped.
+    unsafe {
+        rustix::mm::msync(
+            aligned as *mut std::ffi::c_void,
+            len,
+            rustix::mm::MsyncFlags::SYNC,
+        )
+        .map_err(Into::into)
+    }
+}
+
+/// Locks the file for exclusive or shared usage, blocking until the lock is acquired.
+#[cfg(unix)]
+pub(crate) fn lock_file(file: &File, exclusive: bool) -> Result<()> {
+    let rst = if exclusive {
+        fs4::FileExt::lock_exclusive(file)
+    } else {
+        fs4::FileExt::lock_shared(file)
+    };
+    rst.map_err(|e| Error::new(ErrorKind::IO, e))
+}
+
+/// The state of a file lock which is acquired on the blocking pool.
+#[cfg(unix)]
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum LockState {
+    Waiting,
+    Locked,
+    Cancelled,
+}
+
+/// A file lock which is acquired on the blocking pool, it is held by the waiting future.
+///
+/// If the future is dropped before the lock is acquired, the blocking task releases
+/// the lock as soon as it is acquired, and if the future is dropped after the lock is acquired
+/// but before it is handed over, the lock is released on drop, so the lock never leaks.
+#[cfg(unix)]
+pub(crate) struct PendingLock {
+    file: Arc<File>,
+    state: Arc<Mutex<LockState>>,
+    done: bool,
+}
+
+#[cfg(unix)]
+impl PendingLock {
+    /// `file` should share the open file description with the mmap file, e.g. a duplicate of it.
+    pub(crate) fn new(file: File) -> Self {
+        Self {
+            file: Arc::new(file),
+            state: Arc::new(Mutex::new(LockState::Waiting)),
+            done: false,
+        }
+    }
+
+    /// Returns the blocking task which locks the file.
+    pub(crate) fn task(&self, exclusive: bool) -> impl FnOnce() -> Result<()> + Send + 'static {
+        let file = self.file.clone();
+        let state = self.state.clone();
+        move || {
+            lock_file(&file, exclusive)?;
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            if *state == LockState::Cancelled {
+                let _ = fs4::FileExt::unlock(file.as_ref());
+            } else {
+                *state = LockState::Locked;
+            }
+            Ok(())
+        }
+    }
+
+    /// Hands over the lock acquired by the task to the caller.
+    pub(crate) fn finish(mut self) {
+        self.done = true;
+    }
+}
+
+#[cfg(unix)]
+impl Drop for PendingLock {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if *state == LockState::Locked {
+            let _ = fs4::FileExt::unlock(self.file.as_ref());
+        }
+        *state = LockState::Cancelled;
+    }
+}
+
+/// Copies the data starting from `offset` of `src` to `dst`, returns the number of bytes copied.
+#[inline]
+pub(crate) fn copy_at(src: &[u8], dst: &mut [u8], offset: usize) -> usize {
+    let src = src.get(offset..).unwrap_or_default();
+    let n = src.len().min(dst.len());
+    dst[..n].copy_from_slice(&src[..n]);
+    n
+}
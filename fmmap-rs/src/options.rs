@@ -1,28 +1,42 @@
 macro_rules! declare_and_impl_options {
-    ($name: ident, $file_open_options: ident) => {
+    ($name: ident $(<$runtime: ident>)?, $file_open_options: ident) => {
         /// A memory map builder, providing advanced options and flags for specifying memory map file behavior.
         ///
         // TODO: support file lock options
         #[derive(Clone)]
-        pub struct $name {
+        pub struct $name $(<$runtime: crate::AsyncRuntime>)? {
             pub(crate) mmap_opts: MmapOptions,
             pub(crate) file_opts: $file_open_options,
             pub(crate) max_size: u64,
+            pub(crate) reserve: usize,
+            pub(crate) lock_in_memory: bool,
+            pub(crate) huge_pages: Option<crate::HugePageSize>,
+            pub(crate) map_shared: bool,
+            pub(crate) no_reserve: bool,
+            pub(crate) offset: u64,
+            $(_runtime: std::marker::PhantomData<fn() -> $runtime>,)?
         }
 
-        impl Default for $name {
+        impl $(<$runtime: crate::AsyncRuntime>)? Default for $name $(<$runtime>)? {
             fn default() -> Self {
                 Self::new()
             }
         }
 
-        impl $name {
+        impl $(<$runtime: crate::AsyncRuntime>)? $name $(<$runtime>)? {
             /// Creates a new set of options for configuring and creating a memory map.
             pub fn new() -> Self {
                 Self {
                     mmap_opts: MmapOptions::new(),
                     file_opts: <$file_open_options>::new(),
                     max_size: 0,
+                    reserve: 0,
+                    lock_in_memory: false,
+                    huge_pages: None,
+                    map_shared: false,
+                    no_reserve: false,
+                    offset: 0,
+                    $(_runtime: std::marker::PhantomData::<fn() -> $runtime>,)?
                 }
             }
 
@@ -31,6 +45,7 @@ macro_rules! declare_and_impl_options {
             /// By default, the offset is 0.
             pub fn offset(mut self, offset: u64) -> Self {
                 self.mmap_opts.offset(offset);
+                self.offset = offset;
                 self
             }
 
@@ -73,6 +88,78 @@ macro_rules! declare_and_impl_options {
                 self
             }
 
+            /// Reserves `size` bytes of virtual address space for a writable memory map up front,
+            /// so growing the file with `truncate` never moves the mapping.
+            ///
+            /// Only the bytes backed by the file are accessible, the rest of the range is kept as a
+            /// `PROT_NONE` reservation and committed with `MAP_FIXED` when the file grows.
+            /// Growing the file beyond `size` bytes fails with [`ErrorKind::TruncationFailed`].
+            /// The `offset`, `len` and `populate` options are ignored when reserving.
+            ///
+            /// This option only has effect on Linux when mmaping a real file in write mode.
+            ///
+            /// [`ErrorKind::TruncationFailed`]: fmmap::error::ErrorKind::TruncationFailed
+            pub fn reserve(mut self, size: usize) -> Self {
+                self.reserve = size;
+                self
+            }
+
+            /// Locks the whole memory map in memory (`mlock`) right after it is created,
+            /// so the pages are never paged out.
+            ///
+            /// The lock is released when the memory map is dropped, and applied again to the new pages
+            /// when the memory map is remapped (e.g. by `truncate`).
+            /// Failures, e.g. exceeding the `RLIMIT_MEMLOCK` resource limit, are reported as [`ErrorKind::MlockFailed`].
+            ///
+            /// This option only has effect when mmaping a real file or anonymous memory, and fails on non-unix platforms.
+            ///
+            /// [`ErrorKind::MlockFailed`]: fmmap::error::ErrorKind::MlockFailed
+            pub fn lock_in_memory(mut self) -> Self {
+                self.lock_in_memory = true;
+                self
+            }
+
+            /// Backs the memory map with huge pages, which reduces the TLB misses of large memory maps.
+            ///
+            /// [`HugePageSize::Transparent`] enables transparent huge pages for the memory map (`MADV_HUGEPAGE`).
+            /// [`HugePageSize::Size2M`] and [`HugePageSize::Size1G`] require the file to be on a `hugetlbfs`
+            /// mount with the same page size, which is checked when the file is mmapped.
+            /// Failures are reported as [`ErrorKind::HugePagesFailed`], the chosen huge pages are reported
+            /// by [`MetaData::huge_pages`].
+            ///
+            /// The hugetlb pages of anonymous memory are allocated from the pool directly.
+            ///
+            /// This option only has effect when mmaping a real file or anonymous memory, and fails on non-Linux platforms.
+            ///
+            /// [`HugePageSize::Transparent`]: fmmap::HugePageSize::Transparent
+            /// [`HugePageSize::Size2M`]: fmmap::HugePageSize::Size2M
+            /// [`HugePageSize::Size1G`]: fmmap::HugePageSize::Size1G
+            /// [`ErrorKind::HugePagesFailed`]: fmmap::error::ErrorKind::HugePagesFailed
+            /// [`MetaData::huge_pages`]: fmmap::MetaData::huge_pages
+            pub fn huge_pages(mut self, size: crate::HugePageSize) -> Self {
+                self.huge_pages = Some(size);
+                self
+            }
+
+            /// Shares the anonymous memory map with the child processes forked after it is created (`MAP_SHARED`),
+            /// instead of copying it on write (`MAP_PRIVATE`).
+            ///
+            /// This option only has effect on anonymous memory maps.
+            pub fn map_shared(mut self) -> Self {
+                self.map_shared = true;
+                self
+            }
+
+            /// Does not reserve swap space for the anonymous memory map (`MAP_NORESERVE`),
+            /// so a large sparse memory map can be created even if there is not enough memory to back it.
+            ///
+            /// This option only has effect on anonymous memory maps, and is ignored on the platforms
+            /// other than Linux and Android.
+            pub fn no_reserve(mut self) -> Self {
+                self.no_reserve = true;
+                self
+            }
+
             /// Sets the option for read access. For details, please see [`std::fs::OpenOptions::read`]
             ///
             /// [`std::fs::OpenOptions::read`]: https://doc.rust-lang.org/std/fs/struct.OpenOptions.html#method.read
@@ -156,9 +243,9 @@ macro_rules! declare_and_impl_options {
 
 #[cfg(unix)]
 macro_rules! impl_options_unix_ext {
-    ($name: ident) => {
+    ($name: ident $(<$runtime: ident>)?) => {
         #[cfg(unix)]
-        impl $name {
+        impl $(<$runtime: crate::AsyncRuntime>)? $name $(<$runtime>)? {
             /// Sets the mode bits that a new file will be created with. [Read more]
             ///
             /// [Read more]: https://doc.rust-lang.org/std/os/unix/fs/trait.OpenOptionsExt.html#tymethod.mode
@@ -182,9 +269,9 @@ macro_rules! impl_options_unix_ext {
 
 #[cfg(windows)]
 macro_rules! impl_options_windows_ext {
-    ($name: ident) => {
+    ($name: ident $(<$runtime: ident>)?) => {
         #[cfg(windows)]
-        impl $name {
+        impl $(<$runtime: crate::AsyncRuntime>)? $name $(<$runtime>)? {
             /// Overrides the `dwDesiredAccess` argument to the call to [`CreateFile`] with the specified value. [Read more]
             ///
             /// [`CreateFile`]: https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-createfilea
@@ -252,9 +339,9 @@ cfg_sync!(
 cfg_async! {
     macro_rules! declare_and_impl_async_options {
         ($filename_prefix: literal, $doc_test_runtime: literal, $path_str: literal) => {
-            declare_and_impl_options!(AsyncOptions, OpenOptions);
+            declare_and_impl_options!(AsyncOptions<R>, OpenOptions);
 
-            impl AsyncOptions {
+            impl<R: AsyncRuntime> AsyncOptions<R> {
                 /// Create a new file and mmap this file with [`AsyncOptions`]
                 ///
                 /// # Example
@@ -275,7 +362,7 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 /// [`AsyncOptions`]: struct.AsyncOptions.html
-                pub async fn create_mmap_file_mut<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFileMut, Error> {
+                pub async fn create_mmap_file_mut<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFileMut<R>, Error> {
                     Ok(AsyncMmapFileMut::from(AsyncDiskMmapFileMut::create_with_options(path, self).await?))
                 }
 
@@ -309,7 +396,7 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 /// [`AsyncOptions`]: struct.AsyncOptions.html
-                pub async fn open_mmap_file<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFile, Error> {
+                pub async fn open_mmap_file<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFile<R>, Error> {
                     Ok(AsyncMmapFile::from(AsyncDiskMmapFile::open_with_options(path, self).await?))
                 }
 
@@ -343,7 +430,7 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 /// [`AsyncOptions`]: struct.AsyncOptions.html
-                pub async fn open_exec_mmap_file<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFile, Error> {
+                pub async fn open_exec_mmap_file<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFile<R>, Error> {
                     Ok(AsyncMmapFile::from(AsyncDiskMmapFile::open_exec_with_options(path, self).await?))
                 }
 
@@ -437,7 +524,7 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 /// [`AsyncOptions`]: struct.AsyncOptions.html
-                pub async fn open_mmap_file_mut<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFileMut, Error> {
+                pub async fn open_mmap_file_mut<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFileMut<R>, Error> {
                     Ok(AsyncMmapFileMut::from(AsyncDiskMmapFileMut::open_with_options(path, self).await?))
                 }
 
@@ -486,7 +573,7 @@ cfg_async! {
                 #[doc = "```"]
                 ///
                 /// [`AsyncOptions`]: struct.AsyncOptions.html
-                pub async fn open_exist_mmap_file_mut<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFileMut, Error> {
+                pub async fn open_exist_mmap_file_mut<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFileMut<R>, Error> {
                     Ok(AsyncMmapFileMut::from(AsyncDiskMmapFileMut::open_exist_with_options(path, self).await?))
                 }
 
@@ -539,7 +626,7 @@ cfg_async! {
                 ///
                 ///
                 /// [`AsyncOptions`]: struct.AsyncOptions.html
-                pub async fn open_cow_mmap_file_mut<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFileMut, Error> {
+                pub async fn open_cow_mmap_file_mut<P: AsRef<Path>>(self, path: P) -> Result<AsyncMmapFileMut<R>, Error> {
                     Ok(AsyncMmapFileMut::from(AsyncDiskMmapFileMut::open_cow_with_options(path, self).await?))
                 }
             }
@@ -728,14 +815,19 @@ cfg_async! {
     }
 }
 
+cfg_async!(
+    mod async_impl;
+    pub use async_impl::AsyncOptions;
+);
+
 cfg_async_std!(
-    pub mod async_std_impl;
+    mod async_std_impl;
 );
 
 cfg_smol!(
-    pub mod smol_impl;
+    mod smol_impl;
 );
 
 cfg_tokio!(
-    pub mod tokio_impl;
+    mod tokio_impl;
 );
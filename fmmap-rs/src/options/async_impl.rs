@@ -0,0 +1,18 @@
+use crate::error::Error;
+use crate::raw::{AsyncDiskMmapFile, AsyncDiskMmapFileMut};
+use crate::{AsyncMmapFile, AsyncMmapFileMut, AsyncRuntime};
+use memmapix::MmapOptions;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+#[cfg(windows)]
+use std::os::windows::fs::OpenOptionsExt;
+use std::fs::OpenOptions;
+use std::path::Path;
+
+declare_and_impl_async_options!("tokio_async", "tokio_test", "tokio");
+
+#[cfg(unix)]
+impl_options_unix_ext!(AsyncOptions<R>);
+
+#[cfg(windows)]
+impl_options_windows_ext!(AsyncOptions<R>);
@@ -1,7 +1,15 @@
+#[cfg(unix)]
+use crate::anon::AnonMmapFileMut;
+#[cfg(unix)]
+use crate::dir::Dir;
 use crate::error::Error;
 use crate::raw::{DiskMmapFile, DiskMmapFileMut};
+#[cfg(unix)]
+use crate::utils::sync_parent_at;
 use crate::{MmapFile, MmapFileMut};
 use memmapix::MmapOptions;
+#[cfg(unix)]
+use rustix::fs::OFlags;
 use std::fs::OpenOptions;
 #[cfg(unix)]
 use std::os::unix::fs::OpenOptionsExt;
@@ -18,6 +26,41 @@ impl_options_unix_ext!(Options);
 impl_options_windows_ext!(Options);
 
 impl Options {
+    /// Create an anonymous memory map (`MAP_ANONYMOUS`) of `len` zeroed bytes with [`Options`],
+    /// which is not backed by any file.
+    ///
+    /// The memory is allocated lazily when it is first touched. The memory map is private
+    /// unless [`map_shared`] is set, and [`no_reserve`], [`huge_pages`] and [`lock_in_memory`]
+    /// also have effect. The other options are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fmmap::{Options, MmapFileMutExt, MmapFileExt};
+    ///
+    /// let mut file = Options::new().no_reserve().anonymous(1 << 20).unwrap();
+    /// assert_eq!(file.len(), 1 << 20);
+    ///
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// // grow the memory map, the new bytes are zeros
+    /// file.truncate(2 << 20).unwrap();
+    /// assert_eq!(file.bytes(0, 12).unwrap(), "some data...".as_bytes());
+    ///
+    /// let file = file.freeze().unwrap();
+    /// assert_eq!(file.len(), 2 << 20);
+    /// ```
+    ///
+    /// [`Options`]: struct.Options.html
+    /// [`map_shared`]: struct.Options.html#method.map_shared
+    /// [`no_reserve`]: struct.Options.html#method.no_reserve
+    /// [`huge_pages`]: struct.Options.html#method.huge_pages
+    /// [`lock_in_memory`]: struct.Options.html#method.lock_in_memory
+    #[cfg(unix)]
+    #[cfg_attr(docsrs, doc(cfg(unix)))]
+    pub fn anonymous(self, len: usize) -> Result<MmapFileMut, Error> {
+        Ok(MmapFileMut::from(AnonMmapFileMut::with_options(len, self)?))
+    }
+
     /// Create a new file and mmap this file with [`Options`]
     ///
     /// # Examples
@@ -41,6 +84,45 @@ impl Options {
         )?))
     }
 
+    /// Create a new file at `path` relative to `dir` (`openat`) and mmap this file with [`Options`]
+    ///
+    /// The directory entry is synced after the file is created. The file has a path
+    /// only if the path of `dir` is known, see [`Dir::from_fd`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmmap::{Dir, Options, MmapFileMut, MmapFileMutExt, MmapFileExt};
+    /// # use scopeguard::defer;
+    ///
+    /// let dir = Dir::open(".").unwrap();
+    /// let mut file = Options::new().max_size(100).create_mmap_file_mut_at(&dir, "create_at_with_options_test.txt").unwrap();
+    /// # defer!(std::fs::remove_file("create_at_with_options_test.txt").unwrap());
+    /// assert!(!file.is_empty());
+    ///
+    /// file.write_all("some data...".as_bytes(), 0).unwrap();
+    /// file.flush().unwrap();
+    /// ```
+    ///
+    /// [`Options`]: struct.Options.html
+    /// [`Dir::from_fd`]: struct.Dir.html#method.from_fd
+    #[cfg(unix)]
+    #[cfg_attr(docsrs, doc(cfg(unix)))]
+    pub fn create_mmap_file_mut_at<P: AsRef<Path>>(
+        self,
+        dir: &Dir,
+        path: P,
+    ) -> Result<MmapFileMut, Error> {
+        let path = path.as_ref();
+        let file = dir.open_file_at(
+            path,
+            OFlags::CREATE | OFlags::EXCL | OFlags::RDWR | OFlags::APPEND,
+        )?;
+        let file = MmapFileMut::from_file(file, dir.path_hint(path), self)?;
+        sync_parent_at(dir, path)?;
+        Ok(file)
+    }
+
     /// Open a readable memory map backed by a file with [`Options`]
     ///
     /// # Examples
@@ -80,6 +162,42 @@ impl Options {
         Ok(MmapFile::from(DiskMmapFile::open_with_options(path, self)?))
     }
 
+    /// Open a readable memory map backed by a file at `path` relative to `dir` (`openat`) with [`Options`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmmap::{Dir, Options, MmapFile, MmapFileExt};
+    /// # use scopeguard::defer;
+    ///
+    /// # let mut file = std::fs::File::create("open_at_test_with_options.txt").unwrap();
+    /// # defer!(std::fs::remove_file("open_at_test_with_options.txt").unwrap());
+    /// # std::io::Write::write_all(&mut file, "sanity text".as_bytes()).unwrap();
+    /// # std::io::Write::write_all(&mut file, "some data...".as_bytes()).unwrap();
+    /// # drop(file);
+    ///
+    /// let dir = Dir::open(".").unwrap();
+    /// // open and mmap the file
+    /// let file = Options::new()
+    ///     // mmap content after the sanity text
+    ///     .offset("sanity text".as_bytes().len() as u64)
+    ///     .open_mmap_file_at(&dir, "open_at_test_with_options.txt")
+    ///     .unwrap();
+    ///
+    /// let mut buf = vec![0; "some data...".len()];
+    /// file.read_exact(buf.as_mut_slice(), 0);
+    /// assert_eq!(buf.as_slice(), "some data...".as_bytes());
+    /// ```
+    ///
+    /// [`Options`]: struct.Options.html
+    #[cfg(unix)]
+    #[cfg_attr(docsrs, doc(cfg(unix)))]
+    pub fn open_mmap_file_at<P: AsRef<Path>>(self, dir: &Dir, path: P) -> Result<MmapFile, Error> {
+        let path = path.as_ref();
+        let file = dir.open_file_at(path, OFlags::RDONLY)?;
+        MmapFile::from_file(file, dir.path_hint(path), self)
+    }
+
     /// Open a readable and executable memory map backed by a file with [`Options`].
     ///
     /// # Examples
@@ -509,4 +627,38 @@ mod tests {
             .unwrap();
         assert_eq!(buf.as_slice(), "some data...".as_bytes());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_mmap_file_at() {
+        let path = concat!("sync", "_options_mmap_file_at.txt");
+        let dir = crate::Dir::open(".").unwrap();
+        let mut file = Options::new()
+            .max_size(23)
+            .create_mmap_file_mut_at(&dir, path)
+            .unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        assert_eq!(file.path(), std::path::Path::new(".").join(path));
+        file.write_all("sanity text".as_bytes(), 0).unwrap();
+        file.write_all("some data...".as_bytes(), "sanity text".len())
+            .unwrap();
+        file.flush().unwrap();
+        drop(file);
+
+        // the file already exists
+        assert!(Options::new().create_mmap_file_mut_at(&dir, path).is_err());
+
+        // the path of a directory fd is unknown
+        let fd = std::os::unix::io::AsFd::as_fd(&dir)
+            .try_clone_to_owned()
+            .unwrap();
+        let dir = crate::Dir::from_fd(fd);
+        let file = Options::new()
+            // mmap content after the sanity text
+            .offset("sanity text".len() as u64)
+            .open_mmap_file_at(&dir, path)
+            .unwrap();
+        assert!(file.file_path().is_none());
+        assert_eq!(file.as_slice(), "some data...".as_bytes());
+    }
 }
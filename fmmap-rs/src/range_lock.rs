@@ -0,0 +1,212 @@
+use crate::error::{Error, ErrorKind, Result};
+use std::fs::File;
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+
+/// An RAII guard of a byte-range lock, the range is unlocked when the guard is dropped.
+///
+/// The guard owns its own opened file, so it does not borrow the mmap file,
+/// and the mmap file can be written while the range is locked.
+///
+/// On Linux, the file is reopened through `/proc/self/fd` for every guard, so each guard has its
+/// own open file description, the ranges of the guards never merge, and dropping a guard
+/// only unlocks its own range, even if it overlaps the range of another guard.
+///
+/// # Notes
+/// On unix platforms other than Linux, the POSIX record locks belong to the process, so the ranges of
+/// the guards on the same file are merged, and dropping a guard releases all the range locks of the process on the file.
+///
+/// See [`lock_range_shared`] and [`lock_range_exclusive`].
+///
+/// [`lock_range_shared`]: trait.MmapFileExt.html#tymethod.lock_range_shared
+/// [`lock_range_exclusive`]: trait.MmapFileExt.html#tymethod.lock_range_exclusive
+#[derive(Debug)]
+pub struct RangeLockGuard {
+    file: Option<File>,
+    offset: u64,
+    len: u64,
+}
+
+impl RangeLockGuard {
+    /// Returns a guard which does nothing, for the mmap files which are not backed by a real file.
+    #[inline]
+    pub(crate) fn noop(offset: u64, len: u64) -> Self {
+        Self {
+            file: None,
+            offset,
+            len,
+        }
+    }
+
+    /// Returns the start offset of the locked range.
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the length of the locked range, 0 means the range extends to the end of the file
+    /// and beyond.
+    #[inline]
+    pub fn range_len(&self) -> u64 {
+        self.len
+    }
+
+    /// Unlocks the range, returns the error if unlocking fails,
+    /// while dropping the guard ignores the error.
+    pub fn unlock(mut self) -> Result<()> {
+        match self.file.take() {
+            #[cfg(unix)]
+            Some(file) => fcntl_lock(
+                file.as_raw_fd(),
+                RangeLockType::Unlock,
+                self.offset,
+                self.len,
+                false,
+            ),
+            #[cfg(not(unix))]
+            Some(_) => Ok(()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for RangeLockGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(file) = self.file.take() {
+            let _ = fcntl_lock(
+                file.as_raw_fd(),
+                RangeLockType::Unlock,
+                self.offset,
+                self.len,
+                false,
+            );
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub(crate) enum RangeLockType {
+    Shared,
+    Exclusive,
+    Unlock,
+}
+
+/// Returns a duplicate of the file descriptor, which is owned by the returned file.
+#[cfg(unix)]
+pub(crate) fn duplicate_file<F: AsRawFd>(file: &F) -> Result<File> {
+    // Safety: the file descriptor is valid as long as `file` is alive.
+    let fd = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 0) };
+    if fd < 0 {
+        return Err(Error::new(ErrorKind::IO, std::io::Error::last_os_error()));
+    }
+    // Safety: `fd` is a new file descriptor, the returned file owns it.
+    Ok(unsafe { File::from_raw_fd(fd) })
+}
+
+/// Reopens the file through `/proc/self/fd` with the same access mode, the returned file has
+/// a new open file description, so its open file description locks are independent of `file`.
+///
+/// There is no fallback if `/proc` is not mounted, a duplicate of the file descriptor would share
+/// the open file description, and dropping its guard would unlock the ranges of the other guards.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) fn reopen_file<F: AsRawFd>(file: &F) -> Result<File> {
+    use std::fs::OpenOptions;
+
+    let fd = file.as_raw_fd();
+    // Safety: the file descriptor is valid as long as `file` is alive.
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 {
+        return Err(Error::new(ErrorKind::IO, std::io::Error::last_os_error()));
+    }
+    let mode = flags & libc::O_ACCMODE;
+    OpenOptions::new()
+        .read(mode != libc::O_WRONLY)
+        .write(mode != libc::O_RDONLY)
+        .open(format!("/proc/self/fd/{}", fd))
+        .map_err(|e| {
+            Error::new_source_msg(
+                ErrorKind::IO,
+                "failed to reopen the file through /proc/self/fd, is /proc mounted?",
+                e,
+            )
+        })
+}
+
+/// Locks the range of the file, returns a guard which owns its own opened file.
+#[cfg(unix)]
+pub(crate) fn lock_range_guard<F: AsRawFd>(
+    file: &F,
+    typ: RangeLockType,
+    offset: u64,
+    len: u64,
+    wait: bool,
+) -> Result<RangeLockGuard> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let file = reopen_file(file)?;
+    // the POSIX record locks belong to the process, a duplicate is enough
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let file = duplicate_file(file)?;
+    fcntl_lock(file.as_raw_fd(), typ, offset, len, wait)?;
+    Ok(RangeLockGuard {
+        file: Some(file),
+        offset,
+        len,
+    })
+}
+
+/// Locks the range of the file, returns an error on non-unix platforms.
+#[cfg(not(unix))]
+pub(crate) fn lock_range_guard<F>(
+    _file: &F,
+    _typ: RangeLockType,
+    _offset: u64,
+    _len: u64,
+    _wait: bool,
+) -> Result<RangeLockGuard> {
+    Err(Error::new(
+        ErrorKind::IO,
+        std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "byte-range locks are only supported on unix platforms",
+        ),
+    ))
+}
+
+/// Applies a byte-range lock with `fcntl`.
+///
+/// Open file description locks are used on Linux, so the locks are associated with
+/// the opened file instead of the process, other unix platforms use the POSIX record locks.
+#[cfg(unix)]
+fn fcntl_lock(fd: RawFd, typ: RangeLockType, offset: u64, len: u64, wait: bool) -> Result<()> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let cmd = if wait {
+        libc::F_OFD_SETLKW
+    } else {
+        libc::F_OFD_SETLK
+    };
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let cmd = if wait { libc::F_SETLKW } else { libc::F_SETLK };
+
+    // Safety: all-zero is a valid `flock`, and `l_pid` must be 0 for open file description locks.
+    let mut fl: libc::flock = unsafe { std::mem::zeroed() };
+    fl.l_type = match typ {
+        RangeLockType::Shared => libc::F_RDLCK,
+        RangeLockType::Exclusive => libc::F_WRLCK,
+        RangeLockType::Unlock => libc::F_UNLCK,
+    } as _;
+    fl.l_whence = libc::SEEK_SET as _;
+    fl.l_start = offset as _;
+    fl.l_len = len as _;
+
+    loop {
+        // Safety: `fl` is a valid `flock`.
+        if unsafe { libc::fcntl(fd, cmd, &fl) } == 0 {
+            return Ok(());
+        }
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(Error::new(ErrorKind::IO, err));
+        }
+    }
+}
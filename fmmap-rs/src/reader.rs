@@ -1,8 +1,66 @@
+use std::fmt::{Debug, Formatter};
+
 cfg_sync!(
     mod sync_impl;
-    pub use sync_impl::{MmapFileReader, MmapFileReaderExt};
+    pub use sync_impl::{MmapFileReader, MmapFileReaderExt, OwnedMmapFileReader};
 );
 
+/// OwnedSlice is the range `[offset, offset + len)` of a mmap file, which owns the mmap file,
+/// so the owned readers and writers do not borrow the mmap.
+pub(crate) struct OwnedSlice<F> {
+    file: F,
+    offset: usize,
+    len: usize,
+}
+
+impl<F> OwnedSlice<F> {
+    #[inline]
+    pub(crate) fn new(file: F, offset: usize, len: usize) -> Self {
+        Self { file, offset, len }
+    }
+
+    /// Returns the owned mmap file.
+    #[inline]
+    pub(crate) fn into_inner(self) -> F {
+        self.file
+    }
+}
+
+impl<F: AsMut<[u8]>> OwnedSlice<F> {
+    /// Writes `src` from the position `pos` of the range, returns how many bytes written.
+    pub(crate) fn write_at(&mut self, pos: u64, src: &[u8]) -> usize {
+        let dst = self.as_mut();
+        let pos = pos.min(dst.len() as u64) as usize;
+        let dst = &mut dst[pos..];
+        let n = dst.len().min(src.len());
+        dst[..n].copy_from_slice(&src[..n]);
+        n
+    }
+}
+
+impl<F: AsRef<[u8]>> AsRef<[u8]> for OwnedSlice<F> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.file.as_ref()[self.offset..self.offset + self.len]
+    }
+}
+
+impl<F: AsMut<[u8]>> AsMut<[u8]> for OwnedSlice<F> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.file.as_mut()[self.offset..self.offset + self.len]
+    }
+}
+
+impl<F> Debug for OwnedSlice<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OwnedSlice")
+            .field("offset", &self.offset)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
 cfg_async! {
     macro_rules! declare_and_impl_basic_reader {
         () => {
@@ -51,16 +109,181 @@ cfg_async! {
             }
         };
     }
+
+    macro_rules! declare_and_impl_owned_reader {
+        () => {
+            /// AsyncOwnedMmapFileReader helps read data from mmap file
+            /// like a normal file.
+            ///
+            /// Unlike [`AsyncMmapFileReader`], it owns the mmap file, so it can be returned from functions
+            /// or moved into spawned tasks, see [`AsyncMmapFile::into_reader`].
+            ///
+            /// [`AsyncMmapFileReader`]: struct.AsyncMmapFileReader.html
+            /// [`AsyncMmapFile::into_reader`]: struct.AsyncMmapFile.html#method.into_reader
+            pub struct AsyncOwnedMmapFileReader<R: AsyncRuntime> {
+                r: std::io::Cursor<OwnedSlice<AsyncMmapFile<R>>>,
+                offset: usize,
+                len: usize,
+            }
+
+            impl<R: AsyncRuntime> AsyncOwnedMmapFileReader<R> {
+                pub(crate) fn new(file: AsyncMmapFile<R>, offset: usize, len: usize) -> Self {
+                    Self {
+                        r: std::io::Cursor::new(OwnedSlice::new(file, offset, len)),
+                        offset,
+                        len
+                    }
+                }
+
+                /// Returns the start offset(related to the mmap) of the reader
+                #[inline]
+                pub fn offset(&self) -> usize {
+                    self.offset
+                }
+
+                /// Returns the length of the reader
+                #[inline]
+                pub fn len(&self) -> usize {
+                    self.len
+                }
+
+                /// Consumes the reader, returns the underlying mmap file.
+                #[inline]
+                pub fn into_inner(self) -> AsyncMmapFile<R> {
+                    self.r.into_inner().into_inner()
+                }
+            }
+
+            // the mmap file is never pinned, the reader only reads it through the cursor
+            impl<R: AsyncRuntime> Unpin for AsyncOwnedMmapFileReader<R> {}
+
+            impl<R: AsyncRuntime> Debug for AsyncOwnedMmapFileReader<R> {
+                fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct("AsyncOwnedMmapFileReader")
+                        .field("offset", &self.offset)
+                        .field("len", &self.len)
+                        .field("reader", &self.r)
+                        .finish()
+                }
+            }
+
+            impl<R: AsyncRuntime> Buf for AsyncOwnedMmapFileReader<R> {
+                fn remaining(&self) -> usize {
+                    self.r.remaining()
+                }
+
+                fn chunk(&self) -> &[u8] {
+                    self.r.chunk()
+                }
+
+                fn advance(&mut self, cnt: usize) {
+                    self.r.advance(cnt)
+                }
+            }
+        };
+    }
+
+    macro_rules! declare_async_mmap_file_reader_ext {
+        () => {
+            /// Extends the async readers with methods for reading variable-length integers and length-prefixed records.
+            ///
+            /// The fixed-width integers can be read by the extension traits of the runtime.
+            #[async_trait]
+            pub trait AsyncMmapFileReaderExt {
+                /// Reads an unsigned LEB128 variable-length integer from the underlying reader.
+                async fn read_uvarint(&mut self) -> std::io::Result<u64>;
+                /// Reads a zigzag encoded signed LEB128 variable-length integer from the underlying reader.
+                async fn read_varint(&mut self) -> std::io::Result<i64>;
+                /// Reads a record prefixed by its length from the underlying reader.
+                /// If there is not enough data for the record, the prefix has already been consumed.
+                async fn read_length_prefixed(&mut self, prefix: LengthPrefix) -> std::io::Result<Vec<u8>>;
+            }
+        };
+    }
+
+    macro_rules! impl_async_mmap_file_reader_ext {
+        ($name: ty $(, $runtime: ident)?) => {
+            #[async_trait]
+            impl $(<$runtime: AsyncRuntime>)? AsyncMmapFileReaderExt for $name {
+                async fn read_uvarint(&mut self) -> std::io::Result<u64> {
+                    let mut decoder = UvarintDecoder::default();
+                    let mut byte = [0; 1];
+                    loop {
+                        std::io::Read::read_exact(&mut self.r, &mut byte)?;
+                        if let Some(val) = decoder.push(byte[0]).map_err(into_io_error)? {
+                            return Ok(val);
+                        }
+                    }
+                }
+
+                async fn read_varint(&mut self) -> std::io::Result<i64> {
+                    self.read_uvarint().await.map(zigzag_decode)
+                }
+
+                async fn read_length_prefixed(&mut self, prefix: LengthPrefix) -> std::io::Result<Vec<u8>> {
+                    let len = match prefix {
+                        LengthPrefix::U32 | LengthPrefix::U32Le => {
+                            let mut buf = [0; 4];
+                            std::io::Read::read_exact(&mut self.r, &mut buf)?;
+                            prefix.decode(&buf).map_err(into_io_error)?.0
+                        }
+                        LengthPrefix::Varint => to_usize(self.read_uvarint().await?).map_err(into_io_error)?,
+                    };
+                    let remaining = (self.len() as u64).saturating_sub(self.r.position()) as usize;
+                    if len > remaining {
+                        return Err(into_io_error(Error::from(ErrorKind::EOF)));
+                    }
+                    let mut buf = vec![0; len];
+                    std::io::Read::read_exact(&mut self.r, &mut buf)?;
+                    Ok(buf)
+                }
+            }
+        };
+    }
+
+    macro_rules! impl_futures_io_for_owned_reader {
+        ($read: path, $buf_read: path, $seek: path) => {
+            impl<R: AsyncRuntime> $read for AsyncOwnedMmapFileReader<R> {
+                fn poll_read(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+                    Poll::Ready(std::io::Read::read(&mut self.get_mut().r, buf))
+                }
+            }
+
+            impl<R: AsyncRuntime> $buf_read for AsyncOwnedMmapFileReader<R> {
+                fn poll_fill_buf(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+                    Poll::Ready(std::io::BufRead::fill_buf(&mut self.get_mut().r))
+                }
+
+                fn consume(self: Pin<&mut Self>, amt: usize) {
+                    std::io::BufRead::consume(&mut self.get_mut().r, amt)
+                }
+            }
+
+            impl<R: AsyncRuntime> $seek for AsyncOwnedMmapFileReader<R> {
+                fn poll_seek(self: Pin<&mut Self>, _cx: &mut Context<'_>, pos: SeekFrom) -> Poll<std::io::Result<u64>> {
+                    Poll::Ready(std::io::Seek::seek(&mut self.get_mut().r, pos))
+                }
+            }
+        };
+    }
 }
 
+cfg_async!(
+    mod async_impl;
+    pub use async_impl::{AsyncMmapFileReader, AsyncMmapFileReaderExt, AsyncOwnedMmapFileReader};
+);
+
 cfg_async_std!(
-    pub(crate) mod async_std_impl;
+    #[cfg(test)]
+    mod async_std_impl;
 );
 
 cfg_smol!(
-    pub(crate) mod smol_impl;
+    #[cfg(test)]
+    mod smol_impl;
 );
 
 cfg_tokio!(
-    pub(crate) mod tokio_impl;
+    #[cfg(test)]
+    mod tokio_impl;
 );
@@ -0,0 +1,137 @@
+use crate::error::{Error, ErrorKind};
+use crate::reader::OwnedSlice;
+use crate::varint::{into_io_error, to_usize, zigzag_decode, UvarintDecoder};
+use crate::{AsyncMmapFile, AsyncRuntime, LengthPrefix};
+use bytes::Buf;
+use pin_project_lite::pin_project;
+use std::fmt::{Debug, Formatter};
+use std::io::Cursor;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use std::io::SeekFrom;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use std::pin::Pin;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use std::task::{Context, Poll};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncSeek, ReadBuf};
+
+declare_and_impl_basic_reader!();
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<'a> AsyncRead for AsyncMmapFileReader<'a> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        self.project().r.poll_read(cx, buf)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<'a> AsyncSeek for AsyncMmapFileReader<'a> {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        self.project().r.start_seek(position)
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        self.project().r.poll_complete(cx)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<'a> AsyncBufRead for AsyncMmapFileReader<'a> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        self.project().r.poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.project().r.consume(amt)
+    }
+}
+
+impl<'a> Buf for AsyncMmapFileReader<'a> {
+    fn remaining(&self) -> usize {
+        self.r.remaining()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.r.chunk()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.r.advance(cnt)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
+impl<'a> futures_io::AsyncRead for AsyncMmapFileReader<'a> {
+    fn poll_read(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(std::io::Read::read(self.project().r.get_mut(), buf))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
+impl<'a> futures_io::AsyncSeek for AsyncMmapFileReader<'a> {
+    fn poll_seek(self: Pin<&mut Self>, _cx: &mut Context<'_>, pos: SeekFrom) -> Poll<std::io::Result<u64>> {
+        Poll::Ready(std::io::Seek::seek(self.project().r.get_mut(), pos))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
+impl<'a> futures_io::AsyncBufRead for AsyncMmapFileReader<'a> {
+    fn poll_fill_buf(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        Poll::Ready(std::io::BufRead::fill_buf(self.project().r.get_mut()))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        std::io::BufRead::consume(self.project().r.get_mut(), amt)
+    }
+}
+
+declare_and_impl_owned_reader!();
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<R: AsyncRuntime> AsyncRead for AsyncOwnedMmapFileReader<R> {
+    fn poll_read(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let n = std::io::Read::read(&mut self.get_mut().r, buf.initialize_unfilled())?;
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<R: AsyncRuntime> AsyncSeek for AsyncOwnedMmapFileReader<R> {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        std::io::Seek::seek(&mut self.get_mut().r, position).map(drop)
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Poll::Ready(Ok(self.r.position()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<R: AsyncRuntime> AsyncBufRead for AsyncOwnedMmapFileReader<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        Poll::Ready(std::io::BufRead::fill_buf(&mut self.get_mut().r))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        std::io::BufRead::consume(&mut self.get_mut().r, amt)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl_futures_io_for_owned_reader!(futures_io::AsyncRead, futures_io::AsyncBufRead, futures_io::AsyncSeek);
+
+declare_async_mmap_file_reader_ext!();
+
+impl_async_mmap_file_reader_ext!(AsyncMmapFileReader<'_>);
+
+impl_async_mmap_file_reader_ext!(AsyncOwnedMmapFileReader<R>, R);
@@ -4,6 +4,10 @@ use std::io::Read;
 use std::mem;
 use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
 use bytes::Buf;
+use crate::error::{Error, ErrorKind};
+use crate::varint::{into_io_error, to_usize, zigzag_decode, LengthPrefix, UvarintDecoder};
+use crate::MmapFile;
+use crate::reader::OwnedSlice;
 
 /// MmapFileReader helps read data from mmap file
 /// like a normal file.
@@ -66,6 +70,10 @@ impl<'a> io::Read for MmapFileReader<'a> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.r.read(buf)
     }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        self.r.read_vectored(bufs)
+    }
 }
 
 impl<'a> Buf for MmapFileReader<'a> {
@@ -82,6 +90,98 @@ impl<'a> Buf for MmapFileReader<'a> {
     }
 }
 
+/// OwnedMmapFileReader helps read data from mmap file
+/// like a normal file.
+///
+/// Unlike [`MmapFileReader`], it owns the mmap file, so it can be returned from functions
+/// or moved to other threads, see [`MmapFile::into_reader`].
+///
+/// [`MmapFileReader`]: struct.MmapFileReader.html
+/// [`MmapFile::into_reader`]: struct.MmapFile.html#method.into_reader
+pub struct OwnedMmapFileReader {
+    r: io::Cursor<OwnedSlice<MmapFile>>,
+    offset: usize,
+    len: usize,
+}
+
+impl OwnedMmapFileReader {
+    pub(crate) fn new(file: MmapFile, offset: usize, len: usize) -> Self {
+        Self {
+            r: io::Cursor::new(OwnedSlice::new(file, offset, len)),
+            offset,
+            len,
+        }
+    }
+
+    /// Returns the start offset(related to the mmap) of the reader
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the length of the reader
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Consumes the reader, returns the underlying mmap file.
+    #[inline]
+    pub fn into_inner(self) -> MmapFile {
+        self.r.into_inner().into_inner()
+    }
+}
+
+impl Debug for OwnedMmapFileReader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OwnedMmapFileReader")
+            .field("offset", &self.offset)
+            .field("len", &self.len)
+            .field("reader", &self.r)
+            .finish()
+    }
+}
+
+impl io::Seek for OwnedMmapFileReader {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.r.seek(pos)
+    }
+}
+
+impl io::BufRead for OwnedMmapFileReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.r.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.r.consume(amt)
+    }
+}
+
+impl io::Read for OwnedMmapFileReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.r.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        self.r.read_vectored(bufs)
+    }
+}
+
+impl Buf for OwnedMmapFileReader {
+    fn remaining(&self) -> usize {
+        self.r.remaining()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.r.chunk()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.r.advance(cnt)
+    }
+}
+
 /// Extends MmapFileReader with methods for reading numbers.
 pub trait MmapFileReaderExt {
     /// Reads a signed 8 bit integer from the underlying reader.
@@ -140,163 +240,206 @@ pub trait MmapFileReaderExt {
     fn read_f64(&mut self) -> io::Result<f64>;
     /// Reads a IEEE754 single-precision (8 bytes, little endian) floating point number from the underlying reader.
     fn read_f64_le(&mut self) -> io::Result<f64>;
+    /// Reads an unsigned LEB128 variable-length integer from the underlying reader.
+    fn read_uvarint(&mut self) -> io::Result<u64>;
+    /// Reads a zigzag encoded signed LEB128 variable-length integer from the underlying reader.
+    fn read_varint(&mut self) -> io::Result<i64>;
+    /// Reads a record prefixed by its length from the underlying reader.
+    /// If there is not enough data for the record, the prefix has already been consumed.
+    fn read_length_prefixed(&mut self, prefix: LengthPrefix) -> io::Result<Vec<u8>>;
 }
 
-impl<'a> MmapFileReaderExt for MmapFileReader<'a> {
-    #[inline]
-    fn read_i8(&mut self) -> io::Result<i8> {
-        self.r.read_i8()
-    }
-
-    #[inline]
-    fn read_i16(&mut self) -> io::Result<i16> {
-        self.r.read_i16::<BigEndian>()
-    }
-
-    #[inline]
-    fn read_i16_le(&mut self) -> io::Result<i16> {
-        self.r.read_i16::<LittleEndian>()
-    }
-
-    #[inline]
-    fn read_i32(&mut self) -> io::Result<i32> {
-        self.r.read_i32::<BigEndian>()
-    }
-
-    #[inline]
-    fn read_i32_le(&mut self) -> io::Result<i32> {
-        self.r.read_i32::<LittleEndian>()
-    }
-
-    #[inline]
-    fn read_i64(&mut self) -> io::Result<i64> {
-        self.r.read_i64::<BigEndian>()
-    }
-
-    #[inline]
-    fn read_i64_le(&mut self) -> io::Result<i64> {
-        self.r.read_i64::<LittleEndian>()
-    }
-
-    #[inline]
-    fn read_isize(&mut self) -> io::Result<isize> {
-        const ISIZE_SIZE: usize = mem::size_of::<isize>();
-        let mut buf: [u8; ISIZE_SIZE] = [0; ISIZE_SIZE];
-        self.r.read_exact(&mut buf)?;
-        Ok(isize::from_be_bytes(buf))
-    }
-
-    #[inline]
-    fn read_isize_le(&mut self) -> io::Result<isize> {
-        const ISIZE_SIZE: usize = mem::size_of::<isize>();
-        let mut buf: [u8; ISIZE_SIZE] = [0; ISIZE_SIZE];
-        self.r.read_exact(&mut buf)?;
-        Ok(isize::from_le_bytes(buf))
-    }
-
-    #[inline]
-    fn read_i128(&mut self) -> io::Result<i128> {
-        const I128_SIZE: usize = mem::size_of::<i128>();
-        let mut buf: [u8; I128_SIZE] = [0; I128_SIZE];
-        self.r.read_exact(&mut buf)?;
-        Ok(i128::from_be_bytes(buf))
-    }
-
-    #[inline]
-    fn read_i128_le(&mut self) -> io::Result<i128> {
-        const I128_SIZE: usize = mem::size_of::<i128>();
-        let mut buf: [u8; I128_SIZE] = [0; I128_SIZE];
-        self.r.read_exact(&mut buf)?;
-        Ok(i128::from_le_bytes(buf))
-    }
-
-    #[inline]
-    fn read_u8(&mut self) -> io::Result<u8> {
-        self.r.read_u8()
-    }
-
-    #[inline]
-    fn read_u16(&mut self) -> io::Result<u16> {
-        self.r.read_u16::<BigEndian>()
-    }
-
-    #[inline]
-    fn read_u16_le(&mut self) -> io::Result<u16> {
-        self.r.read_u16::<LittleEndian>()
-    }
-
-    #[inline]
-    fn read_u32(&mut self) -> io::Result<u32> {
-        self.r.read_u32::<BigEndian>()
-    }
-
-    #[inline]
-    fn read_u32_le(&mut self) -> io::Result<u32> {
-        self.r.read_u32::<LittleEndian>()
-    }
-
-    #[inline]
-    fn read_u64(&mut self) -> io::Result<u64> {
-        self.r.read_u64::<BigEndian>()
-    }
-
-    #[inline]
-    fn read_u64_le(&mut self) -> io::Result<u64> {
-        self.r.read_u64::<LittleEndian>()
-    }
-
-    #[inline]
-    fn read_usize(&mut self) -> io::Result<usize> {
-        const USIZE_SIZE: usize = mem::size_of::<usize>();
-        let mut buf: [u8; USIZE_SIZE] = [0; USIZE_SIZE];
-        self.r.read_exact(&mut buf)?;
-        Ok(usize::from_be_bytes(buf))
-    }
-
-    #[inline]
-    fn read_usize_le(&mut self) -> io::Result<usize> {
-        const USIZE_SIZE: usize = mem::size_of::<usize>();
-        let mut buf: [u8; USIZE_SIZE] = [0; USIZE_SIZE];
-        self.r.read_exact(&mut buf)?;
-        Ok(usize::from_le_bytes(buf))
-    }
-
-    #[inline]
-    fn read_u128(&mut self) -> io::Result<u128> {
-        const U128_SIZE: usize = mem::size_of::<u128>();
-        let mut buf: [u8; U128_SIZE] = [0; U128_SIZE];
-        self.r.read_exact(&mut buf)?;
-        Ok(u128::from_be_bytes(buf))
-    }
-
-    #[inline]
-    fn read_u128_le(&mut self) -> io::Result<u128> {
-        const U128_SIZE: usize = mem::size_of::<u128>();
-        let mut buf: [u8; U128_SIZE] = [0; U128_SIZE];
-        self.r.read_exact(&mut buf)?;
-        Ok(u128::from_le_bytes(buf))
-    }
-
-    #[inline]
-    fn read_f32(&mut self) -> io::Result<f32> {
-        self.r.read_f32::<BigEndian>()
-    }
-
-    #[inline]
-    fn read_f32_le(&mut self) -> io::Result<f32> {
-        self.r.read_f32::<LittleEndian>()
-    }
+macro_rules! impl_mmap_file_reader_ext {
+    ($name: ty) => {
+        impl MmapFileReaderExt for $name {
+            #[inline]
+            fn read_i8(&mut self) -> io::Result<i8> {
+                self.r.read_i8()
+            }
+
+            #[inline]
+            fn read_i16(&mut self) -> io::Result<i16> {
+                self.r.read_i16::<BigEndian>()
+            }
+
+            #[inline]
+            fn read_i16_le(&mut self) -> io::Result<i16> {
+                self.r.read_i16::<LittleEndian>()
+            }
+
+            #[inline]
+            fn read_i32(&mut self) -> io::Result<i32> {
+                self.r.read_i32::<BigEndian>()
+            }
+
+            #[inline]
+            fn read_i32_le(&mut self) -> io::Result<i32> {
+                self.r.read_i32::<LittleEndian>()
+            }
+
+            #[inline]
+            fn read_i64(&mut self) -> io::Result<i64> {
+                self.r.read_i64::<BigEndian>()
+            }
+
+            #[inline]
+            fn read_i64_le(&mut self) -> io::Result<i64> {
+                self.r.read_i64::<LittleEndian>()
+            }
+
+            #[inline]
+            fn read_isize(&mut self) -> io::Result<isize> {
+                const ISIZE_SIZE: usize = mem::size_of::<isize>();
+                let mut buf: [u8; ISIZE_SIZE] = [0; ISIZE_SIZE];
+                self.r.read_exact(&mut buf)?;
+                Ok(isize::from_be_bytes(buf))
+            }
+
+            #[inline]
+            fn read_isize_le(&mut self) -> io::Result<isize> {
+                const ISIZE_SIZE: usize = mem::size_of::<isize>();
+                let mut buf: [u8; ISIZE_SIZE] = [0; ISIZE_SIZE];
+                self.r.read_exact(&mut buf)?;
+                Ok(isize::from_le_bytes(buf))
+            }
+
+            #[inline]
+            fn read_i128(&mut self) -> io::Result<i128> {
+                const I128_SIZE: usize = mem::size_of::<i128>();
+                let mut buf: [u8; I128_SIZE] = [0; I128_SIZE];
+                self.r.read_exact(&mut buf)?;
+                Ok(i128::from_be_bytes(buf))
+            }
+
+            #[inline]
+            fn read_i128_le(&mut self) -> io::Result<i128> {
+                const I128_SIZE: usize = mem::size_of::<i128>();
+                let mut buf: [u8; I128_SIZE] = [0; I128_SIZE];
+                self.r.read_exact(&mut buf)?;
+                Ok(i128::from_le_bytes(buf))
+            }
+
+            #[inline]
+            fn read_u8(&mut self) -> io::Result<u8> {
+                self.r.read_u8()
+            }
+
+            #[inline]
+            fn read_u16(&mut self) -> io::Result<u16> {
+                self.r.read_u16::<BigEndian>()
+            }
+
+            #[inline]
+            fn read_u16_le(&mut self) -> io::Result<u16> {
+                self.r.read_u16::<LittleEndian>()
+            }
+
+            #[inline]
+            fn read_u32(&mut self) -> io::Result<u32> {
+                self.r.read_u32::<BigEndian>()
+            }
+
+            #[inline]
+            fn read_u32_le(&mut self) -> io::Result<u32> {
+                self.r.read_u32::<LittleEndian>()
+            }
+
+            #[inline]
+            fn read_u64(&mut self) -> io::Result<u64> {
+                self.r.read_u64::<BigEndian>()
+            }
+
+            #[inline]
+            fn read_u64_le(&mut self) -> io::Result<u64> {
+                self.r.read_u64::<LittleEndian>()
+            }
+
+            #[inline]
+            fn read_usize(&mut self) -> io::Result<usize> {
+                const USIZE_SIZE: usize = mem::size_of::<usize>();
+                let mut buf: [u8; USIZE_SIZE] = [0; USIZE_SIZE];
+                self.r.read_exact(&mut buf)?;
+                Ok(usize::from_be_bytes(buf))
+            }
+
+            #[inline]
+            fn read_usize_le(&mut self) -> io::Result<usize> {
+                const USIZE_SIZE: usize = mem::size_of::<usize>();
+                let mut buf: [u8; USIZE_SIZE] = [0; USIZE_SIZE];
+                self.r.read_exact(&mut buf)?;
+                Ok(usize::from_le_bytes(buf))
+            }
+
+            #[inline]
+            fn read_u128(&mut self) -> io::Result<u128> {
+                const U128_SIZE: usize = mem::size_of::<u128>();
+                let mut buf: [u8; U128_SIZE] = [0; U128_SIZE];
+                self.r.read_exact(&mut buf)?;
+                Ok(u128::from_be_bytes(buf))
+            }
+
+            #[inline]
+            fn read_u128_le(&mut self) -> io::Result<u128> {
+                const U128_SIZE: usize = mem::size_of::<u128>();
+                let mut buf: [u8; U128_SIZE] = [0; U128_SIZE];
+                self.r.read_exact(&mut buf)?;
+                Ok(u128::from_le_bytes(buf))
+            }
+
+            #[inline]
+            fn read_f32(&mut self) -> io::Result<f32> {
+                self.r.read_f32::<BigEndian>()
+            }
+
+            #[inline]
+            fn read_f32_le(&mut self) -> io::Result<f32> {
+                self.r.read_f32::<LittleEndian>()
+            }
+
+            #[inline]
+            fn read_f64(&mut self) -> io::Result<f64> {
+                self.r.read_f64::<BigEndian>()
+            }
+
+            #[inline]
+            fn read_f64_le(&mut self) -> io::Result<f64> {
+                self.r.read_f64::<LittleEndian>()
+            }
+
+            fn read_uvarint(&mut self) -> io::Result<u64> {
+                let mut decoder = UvarintDecoder::default();
+                loop {
+                    if let Some(val) = decoder.push(self.r.read_u8()?).map_err(into_io_error)? {
+                        return Ok(val);
+                    }
+                }
+            }
+
+            #[inline]
+            fn read_varint(&mut self) -> io::Result<i64> {
+                self.read_uvarint().map(zigzag_decode)
+            }
+
+            fn read_length_prefixed(&mut self, prefix: LengthPrefix) -> io::Result<Vec<u8>> {
+                let len = match prefix {
+                    LengthPrefix::U32 => self.r.read_u32::<BigEndian>()? as usize,
+                    LengthPrefix::U32Le => self.r.read_u32::<LittleEndian>()? as usize,
+                    LengthPrefix::Varint => to_usize(self.read_uvarint()?).map_err(into_io_error)?,
+                };
+                if len > self.r.remaining() {
+                    return Err(into_io_error(Error::from(ErrorKind::EOF)));
+                }
+                let mut buf = vec![0; len];
+                self.r.read_exact(&mut buf)?;
+                Ok(buf)
+            }
+        }
+    };
+}
 
-    #[inline]
-    fn read_f64(&mut self) -> io::Result<f64> {
-        self.r.read_f64::<BigEndian>()
-    }
+impl_mmap_file_reader_ext!(MmapFileReader<'_>);
 
-    #[inline]
-    fn read_f64_le(&mut self) -> io::Result<f64> {
-        self.r.read_f64::<LittleEndian>()
-    }
-}
+impl_mmap_file_reader_ext!(OwnedMmapFileReader);
 
 #[cfg(test)]
 mod tests {
@@ -325,4 +468,67 @@ mod tests {
         let buf = w.chunk();
         assert_eq!(buf.len(), 90);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_owned_reader() {
+        use crate::{MmapFile, MmapFileReaderExt};
+
+        let file = MmapFile::memory_from_vec("test.mem", (0..100).collect());
+        let mut r = file.into_range_reader(10, 50).unwrap();
+        let _ = format!("{:?}", r);
+        assert_eq!(r.len(), 50);
+        assert_eq!(r.offset(), 10);
+        let handle = std::thread::spawn(move || {
+            assert_eq!(r.read_u8().unwrap(), 10);
+            let mut rest = Vec::new();
+            r.read_to_end(&mut rest).unwrap();
+            assert_eq!(rest, (11..60).collect::<Vec<u8>>());
+            r.into_inner()
+        });
+        let file = handle.join().unwrap();
+        assert_eq!(file.len(), 100);
+
+        let mut r = file.into_reader(90).unwrap();
+        assert_eq!(r.remaining(), 10);
+        r.advance(5);
+        assert_eq!(r.chunk(), &[95, 96, 97, 98, 99]);
+
+        assert!(r.into_inner().into_reader(101).is_err());
+    }
+
+    #[test]
+    fn test_reader_varint() {
+        use crate::{LengthPrefix, MmapFileReaderExt};
+
+        let file = MemoryMmapFileMut::from_vec(
+            "test.mem",
+            vec![0xac, 0x02, 0x01, 0x03, b'a', b'b', b'c', 0, 0, 0, 9, b'x'],
+        );
+        let mut r = file.reader(0).unwrap();
+        assert_eq!(r.read_uvarint().unwrap(), 300);
+        assert_eq!(r.read_varint().unwrap(), -1);
+        assert_eq!(r.read_length_prefixed(LengthPrefix::Varint).unwrap(), b"abc");
+        let err = r.read_length_prefixed(LengthPrefix::U32).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_reader_vectored() {
+        use crate::MmapFile;
+        use std::io::IoSliceMut;
+
+        let file = MmapFile::memory_from_vec("test.mem", (0..10).collect());
+        let mut r = file.range_reader(2, 6).unwrap();
+        let (mut a, mut b) = ([0; 4], [0; 4]);
+        let n = r.read_vectored(&mut [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)]).unwrap();
+        assert_eq!(n, 6);
+        assert_eq!(a, [2, 3, 4, 5]);
+        assert_eq!(b, [6, 7, 0, 0]);
+
+        let mut r = file.into_reader(8).unwrap();
+        let (mut a, mut b) = ([0; 1], [0; 4]);
+        let n = r.read_vectored(&mut [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)]).unwrap();
+        assert_eq!(n, 2);
+        assert_eq!((a, b), ([8], [9, 0, 0, 0]));
+    }
+}
@@ -1,53 +1,3 @@
-use bytes::Buf;
-use std::fmt::{Debug, Formatter};
-use std::io::{Cursor, SeekFrom};
-use std::pin::Pin;
-use std::task::{Context, Poll};
-use pin_project_lite::pin_project;
-use tokio::io::{AsyncBufRead, AsyncRead, AsyncSeek, ReadBuf};
-
-declare_and_impl_basic_reader!();
-
-impl<'a> AsyncRead for AsyncMmapFileReader<'a> {
-    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
-        self.project().r.poll_read(cx, buf)
-    }
-}
-
-impl<'a> AsyncSeek for AsyncMmapFileReader<'a> {
-    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
-        self.project().r.start_seek(position)
-    }
-
-    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
-        self.project().r.poll_complete(cx)
-    }
-}
-
-impl<'a> AsyncBufRead for AsyncMmapFileReader<'a> {
-    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
-        self.project().r.poll_fill_buf(cx)
-    }
-
-    fn consume(self: Pin<&mut Self>, amt: usize) {
-        self.project().r.consume(amt)
-    }
-}
-
-impl<'a> Buf for AsyncMmapFileReader<'a> {
-    fn remaining(&self) -> usize {
-        self.r.remaining()
-    }
-
-    fn chunk(&self) -> &[u8] {
-        self.r.chunk()
-    }
-
-    fn advance(&mut self, cnt: usize) {
-        self.r.advance(cnt)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use bytes::Buf;
@@ -76,4 +26,74 @@ mod tests {
         let buf = w.chunk();
         assert_eq!(buf.len(), 90);
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "futures-io")]
+    #[tokio::test]
+    async fn test_reader_futures_io() {
+        use futures_util::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt};
+        use std::io::SeekFrom;
+
+        let file = AsyncMemoryMmapFileMut::from_vec("test.mem", (0..100).collect());
+        let mut r = file.range_reader(10, 50).unwrap();
+        let mut buf = [0; 10];
+        AsyncReadExt::read_exact(&mut r, &mut buf).await.unwrap();
+        assert_eq!(buf, [10, 11, 12, 13, 14, 15, 16, 17, 18, 19]);
+        assert_eq!(AsyncSeekExt::seek(&mut r, SeekFrom::Current(10)).await.unwrap(), 20);
+        assert_eq!(AsyncBufReadExt::fill_buf(&mut r).await.unwrap()[0], 30);
+        AsyncBufReadExt::consume_unpin(&mut r, 10);
+        let mut rest = Vec::new();
+        AsyncReadExt::read_to_end(&mut r, &mut rest).await.unwrap();
+        assert_eq!(rest, (40..60).collect::<Vec<u8>>());
+    }
+
+    #[tokio::test]
+    async fn test_owned_reader() {
+        use crate::tokio::AsyncMmapFile;
+
+        let file = AsyncMmapFile::memory_from_vec("test.mem", (0..100).collect());
+        let mut r = file.into_range_reader(10, 50).unwrap();
+        let _ = format!("{:?}", r);
+        assert_eq!(r.len(), 50);
+        assert_eq!(r.offset(), 10);
+        let handle = tokio::spawn(async move {
+            assert_eq!(r.read_u8().await.unwrap(), 10);
+            assert_eq!(r.fill_buf().await.unwrap()[0], 11);
+            let mut rest = Vec::new();
+            r.read_to_end(&mut rest).await.unwrap();
+            assert_eq!(rest, (11..60).collect::<Vec<u8>>());
+            r.into_inner()
+        });
+        let file = handle.await.unwrap();
+        assert_eq!(file.len(), 100);
+
+        let mut r = file.into_reader(90).unwrap();
+        assert_eq!(r.remaining(), 10);
+        r.advance(5);
+        assert_eq!(r.chunk(), &[95, 96, 97, 98, 99]);
+        assert!(r.into_inner().into_reader(101).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reader_varint() {
+        use crate::tokio::{AsyncMmapFile, AsyncMmapFileReaderExt};
+        use crate::LengthPrefix;
+
+        let file = AsyncMmapFile::memory_from_vec(
+            "test.mem",
+            vec![0xac, 0x02, 0x01, 0x03, b'a', b'b', b'c', 0, 0, 0, 9, b'x'],
+        );
+        let mut r = file.reader(0).unwrap();
+        assert_eq!(r.read_uvarint().await.unwrap(), 300);
+        assert_eq!(r.read_varint().await.unwrap(), -1);
+        assert_eq!(r.read_length_prefixed(LengthPrefix::Varint).await.unwrap(), b"abc");
+        let err = r.read_length_prefixed(LengthPrefix::U32).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        // the payload is not consumed if the prefix is longer than the remaining data
+        let mut buf = [0; 1];
+        r.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"x");
+
+        let mut r = file.into_reader(2).unwrap();
+        assert_eq!(r.read_varint().await.unwrap(), -1);
+    }
+}
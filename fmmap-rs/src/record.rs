@@ -0,0 +1,374 @@
+//! Checksummed records for the mmapped logs.
+//!
+//! Every record is framed as:
+//!
+//! | magic (1 byte) | length (4 bytes, LE) | CRC32C of length and payload (4 bytes, LE) | payload |
+//!
+//! The unused space of the file is zeroed, so reading stops at the first zeroed header.
+use crate::append::{grow_to, DEFAULT_GROW_SIZE};
+use crate::error::{Error, ErrorKind, Result};
+use crate::{MmapFileExt, MmapFileMut, MmapFileMutExt, MmapFileWriterExt};
+use std::io::Write;
+
+/// The first byte of every record.
+const RECORD_MAGIC: u8 = 0xa5;
+
+/// The size of the header of a record, which is the magic, the length and the checksum.
+pub(crate) const RECORD_HEADER_SIZE: usize = 9;
+
+/// Returns the checksum of the record, which also covers the length prefix.
+#[inline]
+fn checksum(len: &[u8], payload: &[u8]) -> u32 {
+    crc32c::crc32c_append(crc32c::crc32c(len), payload)
+}
+
+/// Returns whether there is no data after `end`, i.e. a record which fails the validation
+/// and ends at `end` is the last thing written to the file.
+#[inline]
+fn is_tail(buf: &[u8], end: usize) -> bool {
+    buf.get(end..)
+        .map_or(true, |rest| rest.iter().all(|b| *b == 0))
+}
+
+/// RecordWriter appends checksummed records to a [`MmapFileMut`].
+///
+/// The file grows in chunks of [`grow_size`] bytes when the next record would overflow the mmap,
+/// use [`RecordWriter::recover`] to reopen a log which was not closed properly.
+///
+/// # Examples
+///
+/// ```ignore
+/// use fmmap::{MmapFileMut, RecordReader, RecordWriter};
+///
+/// let file = MmapFileMut::memory_with_capacity("record_writer_test.log", 0);
+/// let mut w = RecordWriter::new(file);
+/// assert_eq!(w.append("some data...".as_bytes()).unwrap(), 0);
+/// w.append("more data...".as_bytes()).unwrap();
+///
+/// let file = w.into_inner();
+/// let records = RecordReader::new(&file).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(records, vec!["some data...".as_bytes(), "more data...".as_bytes()]);
+/// ```
+///
+/// [`MmapFileMut`]: struct.MmapFileMut.html
+/// [`grow_size`]: struct.RecordWriter.html#method.grow_size
+/// [`RecordWriter::recover`]: struct.RecordWriter.html#method.recover
+pub struct RecordWriter {
+    file: MmapFileMut,
+    cursor: usize,
+    grow_size: u64,
+    flush_on_append: bool,
+}
+
+impl RecordWriter {
+    /// Wraps an empty or zeroed mmap file, the records are appended from the start of the file.
+    pub fn new(file: MmapFileMut) -> Self {
+        Self::with_cursor(file, 0)
+    }
+
+    /// Wraps a mmap file which contains records, the valid records are kept,
+    /// and the torn tail left by a crash, e.g. a partially written record, is cut by truncating the file.
+    ///
+    /// # Errors
+    /// If a record before the tail is corrupted, it would return
+    ///  `Err(Error::from(ErrorKind::Corrupted { offset }))`.
+    pub fn recover(mut file: MmapFileMut) -> Result<Self> {
+        let mut reader = RecordReader::new(&file);
+        for record in &mut reader {
+            record?;
+        }
+        let (end, torn) = (reader.offset(), reader.is_torn());
+        if torn {
+            file.truncate(end as u64)?;
+            file.flush()?;
+        }
+        Ok(Self::with_cursor(file, end))
+    }
+
+    fn with_cursor(file: MmapFileMut, cursor: usize) -> Self {
+        Self {
+            file,
+            cursor,
+            grow_size: DEFAULT_GROW_SIZE,
+            flush_on_append: false,
+        }
+    }
+
+    /// Returns the number of bytes the file grows by when the next record would overflow.
+    #[inline]
+    pub fn grow_size(&self) -> u64 {
+        self.grow_size
+    }
+
+    /// Set the number of bytes the file grows by when the next record would overflow.
+    /// If `grow_size` is 0, the file only grows by the exact number of bytes needed.
+    #[inline]
+    pub fn set_grow_size(&mut self, grow_size: u64) {
+        self.grow_size = grow_size;
+    }
+
+    /// Returns whether every record is flushed to disk when it is appended. Default is false.
+    #[inline]
+    pub fn get_flush_on_append(&self) -> bool {
+        self.flush_on_append
+    }
+
+    /// Whether flush every record to disk when it is appended, see [`flush_range`].
+    ///
+    /// [`flush_range`]: trait.MmapFileMutExt.html#tymethod.flush_range
+    #[inline]
+    pub fn set_flush_on_append(&mut self, val: bool) {
+        self.flush_on_append = val;
+    }
+
+    /// Returns the end offset of the records, where the next record is appended.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.cursor
+    }
+
+    /// Appends a record, grows the file if needed. Returns the offset of the record.
+    ///
+    /// # Errors
+    /// If the payload is larger than `u32::MAX` bytes, it would return
+    ///  `Err(Error::from(ErrorKind::OutOfBound(len, u32::MAX)))`.
+    pub fn append(&mut self, payload: &[u8]) -> Result<usize> {
+        if payload.len() > u32::MAX as usize {
+            return Err(Error::from(ErrorKind::OutOfBound(
+                payload.len(),
+                u32::MAX as usize,
+            )));
+        }
+        let size = RECORD_HEADER_SIZE + payload.len();
+        let need = self.cursor + size;
+        if need > self.file.len() {
+            self.file.truncate(grow_to(need as u64, self.grow_size))?;
+        }
+
+        let offset = self.cursor;
+        let len = (payload.len() as u32).to_le_bytes();
+        let mut w = self.file.range_writer(offset, size)?;
+        w.write_u8(RECORD_MAGIC)?;
+        w.write_all(&len)?;
+        w.write_u32_le(checksum(&len, payload))?;
+        w.write_all(payload)?;
+        if self.flush_on_append {
+            self.file.flush_range(offset, size)?;
+        }
+        self.cursor = need;
+        Ok(offset)
+    }
+
+    /// Flushes outstanding memory map modifications to disk.
+    #[inline]
+    pub fn flush(&self) -> Result<()> {
+        self.file.flush()
+    }
+
+    /// Consumes the writer, returns the underlying mmap file.
+    #[inline]
+    pub fn into_inner(self) -> MmapFileMut {
+        self.file
+    }
+}
+
+/// RecordReader iterates the checksummed records written by [`RecordWriter`] without copying.
+///
+/// The iteration stops cleanly at the end of the records, including a torn tail left by a crash,
+/// see [`is_torn`]. A corrupted record before the tail is reported as
+/// `Err(Error::from(ErrorKind::Corrupted { offset }))`, then the iteration stops.
+///
+/// [`RecordWriter`]: struct.RecordWriter.html
+/// [`is_torn`]: struct.RecordReader.html#method.is_torn
+#[derive(Debug)]
+pub struct RecordReader<'a> {
+    buf: &'a [u8],
+    offset: usize,
+    torn: bool,
+    done: bool,
+}
+
+impl<'a> RecordReader<'a> {
+    /// Returns a reader which iterates the records from the start of the mmap file.
+    pub fn new<F: MmapFileExt + ?Sized>(file: &'a F) -> Self {
+        Self::with_offset(file, 0)
+    }
+
+    /// Returns a reader which iterates the records from the offset of the mmap file.
+    pub fn with_offset<F: MmapFileExt + ?Sized>(file: &'a F, offset: usize) -> Self {
+        Self {
+            buf: file.as_slice(),
+            offset,
+            torn: false,
+            done: false,
+        }
+    }
+
+    /// Returns the end offset of the valid records which have been read,
+    /// which is where the next record should be appended once the iteration finishes.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns whether the iteration stopped at a torn tail, e.g. a partially written record.
+    #[inline]
+    pub fn is_torn(&self) -> bool {
+        self.torn
+    }
+
+    fn next_record(&mut self) -> Option<Result<&'a [u8]>> {
+        let buf = self.buf;
+        let offset = self.offset;
+        let header = match buf.get(offset..offset + RECORD_HEADER_SIZE) {
+            Some(header) if header.iter().all(|b| *b == 0) => return None,
+            Some(header) => header,
+            None => {
+                self.torn = !is_tail(buf, offset);
+                return None;
+            }
+        };
+
+        let mut len = [0; 4];
+        len.copy_from_slice(&header[1..5]);
+        let mut crc = [0; 4];
+        crc.copy_from_slice(&header[5..]);
+        let start = offset + RECORD_HEADER_SIZE;
+        let payload = if header[0] == RECORD_MAGIC {
+            buf.get(start..)
+                .and_then(|rest| rest.get(..u32::from_le_bytes(len) as usize))
+                .filter(|payload| checksum(&len, payload) == u32::from_le_bytes(crc))
+        } else {
+            None
+        };
+
+        match payload {
+            Some(payload) => {
+                self.offset = start + payload.len();
+                Some(Ok(payload))
+            }
+            None => {
+                let end = if header[0] == RECORD_MAGIC {
+                    start.saturating_add(u32::from_le_bytes(len) as usize)
+                } else {
+                    start
+                };
+                if is_tail(buf, end) {
+                    self.torn = true;
+                    None
+                } else {
+                    Some(Err(Error::from(ErrorKind::Corrupted { offset })))
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for RecordReader<'a> {
+    type Item = Result<&'a [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let rst = self.next_record();
+        if !matches!(rst, Some(Ok(_))) {
+            self.done = true;
+        }
+        rst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MmapFile;
+    use scopeguard::defer;
+
+    #[test]
+    fn test_record() {
+        let path = "record_test.log";
+        let file = MmapFileMut::create(path).unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        let mut w = RecordWriter::new(file);
+        w.set_grow_size(64);
+        w.set_flush_on_append(true);
+        assert_eq!(w.append(b"hello").unwrap(), 0);
+        assert_eq!(w.append(b"").unwrap(), 14);
+        assert_eq!(w.append(&[7; 100]).unwrap(), 23);
+        assert_eq!(w.offset(), 132);
+        w.into_inner().close(-1).unwrap();
+
+        let file = MmapFile::open(path).unwrap();
+        assert_eq!(file.len(), 192);
+        let mut r = RecordReader::new(&file);
+        assert_eq!(r.next().unwrap().unwrap(), b"hello");
+        assert_eq!(r.next().unwrap().unwrap(), b"");
+        assert_eq!(r.next().unwrap().unwrap(), &[7; 100][..]);
+        assert!(r.next().is_none());
+        assert_eq!(r.offset(), 132);
+        assert!(!r.is_torn());
+
+        let file = MmapFileMut::open(path).unwrap();
+        let mut w = RecordWriter::recover(file).unwrap();
+        assert_eq!(w.append(b"world").unwrap(), 132);
+        let file = w.into_inner();
+        let records = RecordReader::with_offset(&file, 132)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(records, vec![b"world"]);
+    }
+
+    #[test]
+    fn test_record_torn_tail() {
+        let mut w = RecordWriter::new(MmapFileMut::memory_with_capacity("torn.log", 0));
+        w.set_grow_size(0);
+        w.append(b"hello").unwrap();
+        w.append(b"world").unwrap();
+        let mut file = w.into_inner();
+        // a crash in the middle of appending the last record
+        file.truncate(25).unwrap();
+
+        let mut r = RecordReader::new(&file);
+        assert_eq!(r.next().unwrap().unwrap(), b"hello");
+        assert!(r.next().is_none());
+        assert!(r.is_torn());
+        assert_eq!(r.offset(), 14);
+
+        let w = RecordWriter::recover(file).unwrap();
+        assert_eq!(w.offset(), 14);
+        assert_eq!(w.into_inner().len(), 14);
+
+        // a partially written payload in preallocated space
+        let mut w = RecordWriter::new(MmapFileMut::memory_from_vec("torn.log", vec![0; 64]));
+        w.append(b"hello").unwrap();
+        w.append(b"world").unwrap();
+        let mut file = w.into_inner();
+        file.write_all(&[0; 2], 26).unwrap();
+        let mut r = RecordReader::new(&file);
+        assert_eq!(r.next().unwrap().unwrap(), b"hello");
+        assert!(r.next().is_none());
+        assert!(r.is_torn());
+    }
+
+    #[test]
+    fn test_record_corrupted() {
+        let mut w = RecordWriter::new(MmapFileMut::memory_with_capacity("corrupted.log", 0));
+        w.set_grow_size(0);
+        w.append(b"hello").unwrap();
+        w.append(b"world").unwrap();
+        w.append(b"!").unwrap();
+        let mut file = w.into_inner();
+        file.write_u8(b'W', 23).unwrap();
+
+        let mut r = RecordReader::new(&file);
+        assert_eq!(r.next().unwrap().unwrap(), b"hello");
+        let err = r.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Corrupted { offset: 14 });
+        assert!(r.next().is_none());
+        assert_eq!(r.offset(), 14);
+
+        let err = RecordWriter::recover(file).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::Corrupted { offset: 14 });
+    }
+}
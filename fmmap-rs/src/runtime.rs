@@ -0,0 +1,139 @@
+cfg_async! {
+    use std::fs::{Metadata, OpenOptions};
+    use std::io;
+    use std::marker::PhantomData;
+    use std::mem::ManuallyDrop;
+    use std::ops::Deref;
+    #[cfg(unix)]
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+    #[cfg(windows)]
+    use std::os::windows::io::{AsRawHandle, FromRawHandle};
+    use std::path::PathBuf;
+
+    /// AsyncRuntime abstracts the file system and blocking pool operations of an async runtime,
+    /// which are used by the async mmap files.
+    ///
+    /// The async mmap files are generic over the runtime, the implementations for tokio, async-std and smol
+    /// are provided by `fmmap::tokio::TokioRuntime`, `fmmap::async_std::AsyncStdRuntime` and `fmmap::smol::SmolRuntime`,
+    /// and the `fmmap::tokio`, `fmmap::async_std` and `fmmap::smol` modules alias the async mmap files for them.
+    #[async_trait]
+    pub trait AsyncRuntime: Send + Sync + 'static {
+        /// The async file type of the runtime, the mmap is created from its raw fd.
+        #[cfg(unix)]
+        type File: AsRawFd + Send + Sync + 'static;
+
+        /// The async file type of the runtime, the mmap is created from its raw handle.
+        #[cfg(windows)]
+        type File: AsRawHandle + Send + Sync + 'static;
+
+        /// Converts a std file to the async file of the runtime.
+        fn from_std(file: std::fs::File) -> Self::File;
+
+        /// Runs the blocking function on the blocking pool of the runtime.
+        async fn spawn_blocking<F, T>(f: F) -> io::Result<T>
+        where
+            F: FnOnce() -> T + Send + 'static,
+            T: Send + 'static;
+
+        /// Opens the file at `path` with the options.
+        ///
+        /// The default implementation opens the std file on the blocking pool of the runtime.
+        async fn open(path: PathBuf, opts: OpenOptions) -> io::Result<Self::File> {
+            let file = Self::spawn_blocking(move || opts.open(path)).await??;
+            Ok(Self::from_std(file))
+        }
+
+        /// Queries the metadata of the file.
+        ///
+        /// The default implementation queries the metadata of the file descriptor in place.
+        async fn metadata(file: &Self::File) -> io::Result<Metadata> {
+            as_std_file(file).metadata()
+        }
+
+        /// Truncates or extends the file to `size`.
+        async fn set_len(file: &Self::File, size: u64) -> io::Result<()>;
+
+        /// Syncs the data and the metadata of the file to disk.
+        async fn sync_all(file: &Self::File) -> io::Result<()>;
+
+        /// Removes the file at `path`.
+        async fn remove_file(path: PathBuf) -> io::Result<()>;
+    }
+
+    /// A std file borrowed from the file of a runtime, the descriptor is not closed on drop.
+    pub(crate) struct StdFileRef<'a> {
+        file: ManuallyDrop<std::fs::File>,
+        _borrow: PhantomData<&'a ()>,
+    }
+
+    impl Deref for StdFileRef<'_> {
+        type Target = std::fs::File;
+
+        #[inline]
+        fn deref(&self) -> &Self::Target {
+            &self.file
+        }
+    }
+
+    /// Borrows the file of a runtime as a std file, e.g. to lock it by `fs4`.
+    #[cfg(unix)]
+    pub(crate) fn as_std_file<F: AsRawFd + ?Sized>(file: &F) -> StdFileRef<'_> {
+        StdFileRef {
+            // the descriptor is owned by `file`, which outlives the returned file
+            file: ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(file.as_raw_fd()) }),
+            _borrow: PhantomData,
+        }
+    }
+
+    /// Borrows the file of a runtime as a std file, e.g. to lock it by `fs4`.
+    #[cfg(windows)]
+    pub(crate) fn as_std_file<F: AsRawHandle + ?Sized>(file: &F) -> StdFileRef<'_> {
+        StdFileRef {
+            // the handle is owned by `file`, which outlives the returned file
+            file: ManuallyDrop::new(unsafe { std::fs::File::from_raw_handle(file.as_raw_handle()) }),
+            _borrow: PhantomData,
+        }
+    }
+
+    macro_rules! test_async_runtime {
+        ($filename_prefix: literal, $runtime: ty, $attr: meta) => {
+            #[cfg(test)]
+            mod tests {
+                use super::*;
+                use std::fs::OpenOptions;
+                use std::path::PathBuf;
+
+                #[$attr]
+                async fn test_async_runtime() {
+                    let path = PathBuf::from(concat!($filename_prefix, "_runtime_test.txt"));
+                    let mut opts = OpenOptions::new();
+                    opts.create_new(true).read(true).write(true);
+                    let file = <$runtime>::open(path.clone(), opts).await.unwrap();
+                    <$runtime>::set_len(&file, 100).await.unwrap();
+                    <$runtime>::sync_all(&file).await.unwrap();
+                    assert_eq!(std::fs::metadata(&path).unwrap().len(), 100);
+                    assert_eq!(<$runtime>::metadata(&file).await.unwrap().len(), 100);
+                    drop(file);
+
+                    let len = <$runtime>::spawn_blocking(|| 1 + 1).await.unwrap();
+                    assert_eq!(len, 2);
+                    <$runtime>::remove_file(path.clone()).await.unwrap();
+                    assert!(!path.exists());
+                    <$runtime>::remove_file(path).await.unwrap_err();
+                }
+            }
+        };
+    }
+}
+
+cfg_async_std!(
+    pub(crate) mod async_std_impl;
+);
+
+cfg_smol!(
+    pub(crate) mod smol_impl;
+);
+
+cfg_tokio!(
+    pub(crate) mod tokio_impl;
+);
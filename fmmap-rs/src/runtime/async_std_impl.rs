@@ -0,0 +1,52 @@
+use crate::AsyncRuntime;
+use async_std::fs::File;
+use async_trait::async_trait;
+use std::fs::Metadata;
+use std::io;
+use std::path::PathBuf;
+
+/// The async-std runtime, see [`AsyncRuntime`].
+///
+/// [`AsyncRuntime`]: ../trait.AsyncRuntime.html
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AsyncStdRuntime;
+
+#[async_trait]
+impl AsyncRuntime for AsyncStdRuntime {
+    type File = File;
+
+    #[inline]
+    fn from_std(file: std::fs::File) -> Self::File {
+        File::from(file)
+    }
+
+    async fn spawn_blocking<F, T>(f: F) -> io::Result<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        Ok(async_std::task::spawn_blocking(f).await)
+    }
+
+    #[inline]
+    async fn metadata(file: &Self::File) -> io::Result<Metadata> {
+        file.metadata().await
+    }
+
+    #[inline]
+    async fn set_len(file: &Self::File, size: u64) -> io::Result<()> {
+        file.set_len(size).await
+    }
+
+    #[inline]
+    async fn sync_all(file: &Self::File) -> io::Result<()> {
+        file.sync_all().await
+    }
+
+    #[inline]
+    async fn remove_file(path: PathBuf) -> io::Result<()> {
+        async_std::fs::remove_file(path).await
+    }
+}
+
+test_async_runtime!("async_std_async", AsyncStdRuntime, async_std::test);
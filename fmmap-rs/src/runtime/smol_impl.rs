@@ -0,0 +1,52 @@
+use crate::AsyncRuntime;
+use async_trait::async_trait;
+use smol::fs::File;
+use std::fs::Metadata;
+use std::io;
+use std::path::PathBuf;
+
+/// The smol runtime, see [`AsyncRuntime`].
+///
+/// [`AsyncRuntime`]: ../trait.AsyncRuntime.html
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SmolRuntime;
+
+#[async_trait]
+impl AsyncRuntime for SmolRuntime {
+    type File = File;
+
+    #[inline]
+    fn from_std(file: std::fs::File) -> Self::File {
+        File::from(file)
+    }
+
+    async fn spawn_blocking<F, T>(f: F) -> io::Result<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        Ok(smol::unblock(f).await)
+    }
+
+    #[inline]
+    async fn metadata(file: &Self::File) -> io::Result<Metadata> {
+        file.metadata().await
+    }
+
+    #[inline]
+    async fn set_len(file: &Self::File, size: u64) -> io::Result<()> {
+        file.set_len(size).await
+    }
+
+    #[inline]
+    async fn sync_all(file: &Self::File) -> io::Result<()> {
+        file.sync_all().await
+    }
+
+    #[inline]
+    async fn remove_file(path: PathBuf) -> io::Result<()> {
+        smol::fs::remove_file(path).await
+    }
+}
+
+test_async_runtime!("smol_async", SmolRuntime, smol_potat::test);
@@ -0,0 +1,54 @@
+use crate::AsyncRuntime;
+use async_trait::async_trait;
+use std::fs::Metadata;
+use std::io;
+use std::path::PathBuf;
+use tokio::fs::File;
+
+/// The tokio runtime, see [`AsyncRuntime`].
+///
+/// [`AsyncRuntime`]: ../trait.AsyncRuntime.html
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TokioRuntime;
+
+#[async_trait]
+impl AsyncRuntime for TokioRuntime {
+    type File = File;
+
+    #[inline]
+    fn from_std(file: std::fs::File) -> Self::File {
+        File::from_std(file)
+    }
+
+    async fn spawn_blocking<F, T>(f: F) -> io::Result<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        tokio::task::spawn_blocking(f)
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
+    #[inline]
+    async fn metadata(file: &Self::File) -> io::Result<Metadata> {
+        file.metadata().await
+    }
+
+    #[inline]
+    async fn set_len(file: &Self::File, size: u64) -> io::Result<()> {
+        file.set_len(size).await
+    }
+
+    #[inline]
+    async fn sync_all(file: &Self::File) -> io::Result<()> {
+        file.sync_all().await
+    }
+
+    #[inline]
+    async fn remove_file(path: PathBuf) -> io::Result<()> {
+        tokio::fs::remove_file(path).await
+    }
+}
+
+test_async_runtime!("tokio_async", TokioRuntime, tokio::test);
@@ -0,0 +1,173 @@
+cfg_sync!(
+    mod sync_impl;
+    pub use sync_impl::SharedMmapFile;
+);
+
+cfg_async! {
+    macro_rules! declare_and_impl_async_shared_mmap_file {
+        ($filename_prefix: literal, $doc_test_runtime: literal, $path_str: literal) => {
+            /// AsyncSharedMmapFile is a cheap-clone handle to a read-only [`AsyncMmapFile`],
+            /// the mapping is kept alive until all the handles and the [`Bytes`] sliced from it are dropped.
+            ///
+            /// It derefs to [`AsyncMmapFile`], so all the methods of [`AsyncMmapFileExt`] are available,
+            /// and the handles and the [`Bytes`] can be moved across tasks.
+            ///
+            /// # Examples
+            ///
+            #[doc = "```ignore"]
+            #[doc = concat!("use fmmap::", $path_str, "::{AsyncMmapFileMut, AsyncMmapFileMutExt, AsyncOptions, AsyncSharedMmapFile};")]
+            /// # use scopeguard::defer;
+            ///
+            #[doc = concat!("# ", $doc_test_runtime, "::block_on(async {")]
+            #[doc = concat!("let mut file = AsyncMmapFileMut::create_with_options(\"", $filename_prefix, "_shared_mmap_file_test.txt\", AsyncOptions::new().max_size(12)).await.unwrap();")]
+            #[doc = concat!("# defer!(std::fs::remove_file(\"", $filename_prefix, "_shared_mmap_file_test.txt\").unwrap());")]
+            /// file.write_all("some data...".as_bytes(), 0).unwrap();
+            ///
+            /// let file = AsyncSharedMmapFile::from(file.freeze().unwrap());
+            /// let buf = file.slice_owned(5, 4).unwrap();
+            /// drop(file);
+            /// assert_eq!(buf.as_ref(), "data".as_bytes());
+            /// # })
+            #[doc = "```"]
+            ///
+            #[doc = concat!("[`AsyncMmapFile`]: ", $path_str, "/struct.AsyncMmapFile.html")]
+            #[doc = concat!("[`AsyncMmapFileExt`]: ", $path_str, "/trait.AsyncMmapFileExt.html")]
+            /// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
+            #[repr(transparent)]
+            pub struct AsyncSharedMmapFile<R: AsyncRuntime> {
+                inner: Arc<AsyncMmapFile<R>>,
+            }
+
+            impl<R: AsyncRuntime> Clone for AsyncSharedMmapFile<R> {
+                #[inline]
+                fn clone(&self) -> Self {
+                    Self {
+                        inner: self.inner.clone(),
+                    }
+                }
+            }
+
+            impl<R: AsyncRuntime> AsyncSharedMmapFile<R> {
+                /// Wraps an [`AsyncMmapFile`] into a shared handle.
+                ///
+                #[doc = concat!("[`AsyncMmapFile`]: ", $path_str, "/struct.AsyncMmapFile.html")]
+                #[inline]
+                pub fn new(file: AsyncMmapFile<R>) -> Self {
+                    Self {
+                        inner: Arc::new(file),
+                    }
+                }
+
+                /// Returns `len` bytes starting at `offset` as [`Bytes`] without copying,
+                /// the returned [`Bytes`] keeps the mapping alive.
+                ///
+                /// # Errors
+                /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+                ///
+                /// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
+                pub fn slice_owned(&self, offset: usize, len: usize) -> Result<Bytes> {
+                    self.inner.bytes(offset, len)?;
+                    Ok(Bytes::from_owner(self.clone()).slice(offset..offset + len))
+                }
+
+                /// Returns the number of handles to the mmap file,
+                /// including the ones owned by the [`Bytes`] returned by [`slice_owned`].
+                ///
+                /// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
+                #[doc = concat!("[`slice_owned`]: ", $path_str, "/struct.AsyncSharedMmapFile.html#method.slice_owned")]
+                #[inline]
+                pub fn handle_count(&self) -> usize {
+                    Arc::strong_count(&self.inner)
+                }
+
+                /// Returns the inner [`AsyncMmapFile`] if this is the only handle,
+                /// otherwise returns `Err(self)`.
+                ///
+                #[doc = concat!("[`AsyncMmapFile`]: ", $path_str, "/struct.AsyncMmapFile.html")]
+                #[inline]
+                pub fn try_unwrap(self) -> std::result::Result<AsyncMmapFile<R>, Self> {
+                    Arc::try_unwrap(self.inner).map_err(|inner| Self { inner })
+                }
+            }
+
+            impl<R: AsyncRuntime> From<AsyncMmapFile<R>> for AsyncSharedMmapFile<R> {
+                #[inline]
+                fn from(file: AsyncMmapFile<R>) -> Self {
+                    Self::new(file)
+                }
+            }
+
+            impl<R: AsyncRuntime> Deref for AsyncSharedMmapFile<R> {
+                type Target = AsyncMmapFile<R>;
+
+                #[inline]
+                fn deref(&self) -> &Self::Target {
+                    &self.inner
+                }
+            }
+
+            impl<R: AsyncRuntime> AsRef<[u8]> for AsyncSharedMmapFile<R> {
+                #[inline]
+                fn as_ref(&self) -> &[u8] {
+                    self.inner.as_slice()
+                }
+            }
+        };
+    }
+
+    macro_rules! test_async_shared_mmap_file {
+        ($filename_prefix: literal, $runtime: ident, $attr: meta) => {
+            #[cfg(test)]
+            mod tests {
+                use crate::$runtime::{
+                    AsyncMmapFileExt, AsyncMmapFileMut, AsyncMmapFileMutExt, AsyncOptions,
+                    AsyncSharedMmapFile,
+                };
+                use scopeguard::defer;
+
+                #[$attr]
+                async fn test_async_shared_mmap_file() {
+                    let path = concat!($filename_prefix, "_shared_mmap_file_test.txt");
+                    let mut file = AsyncMmapFileMut::create_with_options(path, AsyncOptions::new().max_size(12)).await.unwrap();
+                    defer!(std::fs::remove_file(path).unwrap());
+                    file.write_all(b"some data...", 0).unwrap();
+
+                    let file = AsyncSharedMmapFile::from(file.freeze().unwrap());
+                    let file2 = file.clone();
+                    assert_eq!(file2.len(), 12);
+                    let buf = file.slice_owned(5, 4).unwrap();
+                    assert_eq!(file.handle_count(), 3);
+                    file.slice_owned(10, 4).unwrap_err();
+                    drop(file);
+
+                    let handle = std::thread::spawn(move || buf);
+                    let buf = handle.join().unwrap();
+                    assert_eq!(buf.as_ref(), b"data");
+                    assert_eq!(file2.handle_count(), 2);
+                    drop(buf);
+                    assert!(file2.try_unwrap().is_ok());
+                }
+            }
+        };
+    }
+}
+
+cfg_async!(
+    mod async_impl;
+    pub use async_impl::AsyncSharedMmapFile;
+);
+
+cfg_async_std!(
+    #[cfg(test)]
+    mod async_std_impl;
+);
+
+cfg_smol!(
+    #[cfg(test)]
+    mod smol_impl;
+);
+
+cfg_tokio!(
+    #[cfg(test)]
+    mod tokio_impl;
+);
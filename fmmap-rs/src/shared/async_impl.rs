@@ -0,0 +1,7 @@
+use crate::error::Result;
+use crate::{AsyncMmapFile, AsyncMmapFileExt, AsyncRuntime};
+use bytes::Bytes;
+use std::ops::Deref;
+use std::sync::Arc;
+
+declare_and_impl_async_shared_mmap_file!("tokio_async", "tokio_test", "tokio");
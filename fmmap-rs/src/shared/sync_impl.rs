@@ -0,0 +1,138 @@
+use crate::error::Result;
+use crate::{MmapFile, MmapFileExt};
+use bytes::Bytes;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// SharedMmapFile is a cheap-clone handle to a read-only [`MmapFile`],
+/// the mapping is kept alive until all the handles and the [`Bytes`] sliced from it are dropped.
+///
+/// It derefs to [`MmapFile`], so all the methods of [`MmapFileExt`] are available.
+///
+/// # Examples
+///
+/// ```ignore
+/// use fmmap::{MmapFileMut, MmapFileMutExt, Options, SharedMmapFile};
+/// # use scopeguard::defer;
+///
+/// let mut file = MmapFileMut::create_with_options("shared_mmap_file_test.txt", Options::new().max_size(12)).unwrap();
+/// # defer!(std::fs::remove_file("shared_mmap_file_test.txt").unwrap());
+/// file.write_all("some data...".as_bytes(), 0).unwrap();
+///
+/// let file = SharedMmapFile::from(file.freeze().unwrap());
+/// let buf = file.slice_owned(5, 4).unwrap();
+/// drop(file);
+/// assert_eq!(buf.as_ref(), "data".as_bytes());
+/// ```
+///
+/// [`MmapFile`]: struct.MmapFile.html
+/// [`MmapFileExt`]: trait.MmapFileExt.html
+/// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct SharedMmapFile {
+    inner: Arc<MmapFile>,
+}
+
+impl SharedMmapFile {
+    /// Wraps a [`MmapFile`] into a shared handle.
+    ///
+    /// [`MmapFile`]: struct.MmapFile.html
+    #[inline]
+    pub fn new(file: MmapFile) -> Self {
+        Self {
+            inner: Arc::new(file),
+        }
+    }
+
+    /// Returns `len` bytes starting at `offset` as [`Bytes`] without copying,
+    /// the returned [`Bytes`] keeps the mapping alive.
+    ///
+    /// # Errors
+    /// If there's not enough data, it would return `Err(Error::from(ErrorKind::EOF))`.
+    ///
+    /// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
+    pub fn slice_owned(&self, offset: usize, len: usize) -> Result<Bytes> {
+        self.inner.bytes(offset, len)?;
+        Ok(Bytes::from_owner(self.clone()).slice(offset..offset + len))
+    }
+
+    /// Returns the number of handles to the mmap file,
+    /// including the ones owned by the [`Bytes`] returned by [`slice_owned`].
+    ///
+    /// [`Bytes`]: https://docs.rs/bytes/1.1.0/bytes/struct.Bytes.html
+    /// [`slice_owned`]: struct.SharedMmapFile.html#method.slice_owned
+    #[inline]
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+
+    /// Returns the inner [`MmapFile`] if this is the only handle,
+    /// otherwise returns `Err(self)`.
+    ///
+    /// [`MmapFile`]: struct.MmapFile.html
+    #[inline]
+    pub fn try_unwrap(self) -> std::result::Result<MmapFile, Self> {
+        Arc::try_unwrap(self.inner).map_err(|inner| Self { inner })
+    }
+}
+
+impl From<MmapFile> for SharedMmapFile {
+    #[inline]
+    fn from(file: MmapFile) -> Self {
+        Self::new(file)
+    }
+}
+
+impl Deref for SharedMmapFile {
+    type Target = MmapFile;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl AsRef<[u8]> for SharedMmapFile {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.inner.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MmapFileMut, MmapFileMutExt, Options};
+    use scopeguard::defer;
+
+    #[test]
+    fn test_shared_mmap_file() {
+        let path = "shared_mmap_file_test.txt";
+        let mut file = MmapFileMut::create_with_options(path, Options::new().max_size(12)).unwrap();
+        defer!(std::fs::remove_file(path).unwrap());
+        file.write_all(b"some data...", 0).unwrap();
+
+        let file = SharedMmapFile::from(file.freeze().unwrap());
+        let file2 = file.clone();
+        assert_eq!(file2.len(), 12);
+        let buf = file.slice_owned(5, 4).unwrap();
+        assert_eq!(file.handle_count(), 3);
+        file.slice_owned(10, 4).unwrap_err();
+        drop(file);
+
+        let handle = std::thread::spawn(move || buf);
+        let buf = handle.join().unwrap();
+        assert_eq!(buf.as_ref(), b"data");
+        assert_eq!(file2.handle_count(), 2);
+        drop(buf);
+        assert!(file2.try_unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_shared_memory_mmap_file() {
+        let file = SharedMmapFile::from(MmapFile::memory_from_vec("memory.txt", b"some data...".to_vec()));
+        assert_eq!(file.slice_owned(0, 4).unwrap().as_ref(), b"some");
+        assert_eq!(file.path_string(), "memory.txt");
+    }
+}
@@ -0,0 +1 @@
+test_async_shared_mmap_file!("tokio_async", tokio, tokio::test);
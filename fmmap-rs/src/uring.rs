@@ -0,0 +1,373 @@
+//! The `io_uring` based read path of the async mmap files.
+//!
+//! The ranges which are not resident in memory are read with `IORING_OP_READ` from the file descriptor,
+//! instead of faulting in the pages of the mapping on the executor thread. All the reads share one ring,
+//! which is created on the first read. The completions are signaled by an `eventfd`, which is polled by
+//! the reactor of `async-io`, so it works with all the runtimes.
+use crate::error::{Error, ErrorKind, Result};
+use async_io::Async;
+use io_uring::{opcode, squeue, types, IoUring};
+use std::collections::HashMap;
+use std::fs::File;
+use std::future::poll_fn;
+use std::io::{self, Read};
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::task::{Context, Poll, Waker};
+
+/// The number of entries of the submission queue.
+const RING_ENTRIES: u32 = 64;
+
+/// The maximum number of bytes read by a single `IORING_OP_READ`.
+const MAX_READ_LEN: usize = 1 << 30;
+
+/// The user data of the cancellations, whose completions are ignored.
+const CANCEL_USER_DATA: u64 = u64::MAX;
+
+/// The state of a submitted read.
+enum OpState {
+    /// The read is in flight, with the waker of the future waiting for it.
+    Waiting(Option<Waker>),
+    /// The read has completed with the result.
+    Done(i32),
+    /// The future waiting for the read is dropped, the buffer is freed when the read completes.
+    Abandoned { _buf: Vec<u8> },
+}
+
+struct RingInner {
+    ring: IoUring,
+    ops: HashMap<u64, OpState>,
+    next_id: u64,
+}
+
+impl RingInner {
+    /// Pushes the entry to the submission queue and submits it.
+    ///
+    /// If the submission fails, the entry stays in the queue and is submitted by the next poll.
+    ///
+    /// # Safety
+    /// The buffer of the entry must be alive until the entry completes.
+    unsafe fn push(&mut self, sqe: &squeue::Entry) -> io::Result<()> {
+        if self.ring.submission().push(sqe).is_err() {
+            self.ring.submit()?;
+            self.ring
+                .submission()
+                .push(sqe)
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        }
+        let _ = self.ring.submit();
+        Ok(())
+    }
+
+    /// Moves the completed reads to `Done`, frees the buffers of the abandoned reads,
+    /// and returns the wakers of the completed reads.
+    fn reap(&mut self) -> Vec<Waker> {
+        let RingInner { ring, ops, .. } = self;
+        let mut wakers = Vec::new();
+        for cqe in ring.completion() {
+            let id = cqe.user_data();
+            match ops.get_mut(&id) {
+                Some(OpState::Waiting(waker)) => {
+                    wakers.extend(waker.take());
+                    ops.insert(id, OpState::Done(cqe.result()));
+                }
+                Some(OpState::Abandoned { .. }) => {
+                    ops.remove(&id);
+                }
+                // the cancellations
+                _ => {}
+            }
+        }
+        wakers
+    }
+}
+
+/// The io_uring shared by all the reads of the process.
+struct Ring {
+    inner: Mutex<RingInner>,
+    event: Async<File>,
+}
+
+impl Ring {
+    fn new() -> io::Result<Self> {
+        let ring = IoUring::new(RING_ENTRIES)?;
+        // Safety: eventfd returns a new file descriptor, or -1 on error.
+        let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Safety: `fd` is a new file descriptor, the file owns it.
+        let event = unsafe { File::from_raw_fd(fd) };
+        ring.submitter().register_eventfd(event.as_raw_fd())?;
+        Ok(Self {
+            inner: Mutex::new(RingInner {
+                ring,
+                ops: HashMap::new(),
+                next_id: 0,
+            }),
+            event: Async::new(event)?,
+        })
+    }
+
+    /// Returns the shared ring, or `None` if io_uring is not available, e.g. it is disabled by seccomp.
+    fn get() -> Option<&'static Ring> {
+        static RING: OnceLock<Option<Ring>> = OnceLock::new();
+        RING.get_or_init(|| Ring::new().ok()).as_ref()
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, RingInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Submits the read of `buf` from `offset` of the file, returns the id of the read.
+    ///
+    /// # Safety
+    /// `buf` must be alive until the read completes, or is handed to [`Ring::abandon`].
+    unsafe fn submit(&self, fd: RawFd, buf: &mut [u8], offset: u64) -> io::Result<u64> {
+        let mut inner = self.lock();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let len = buf.len().min(MAX_READ_LEN) as u32;
+        let sqe = opcode::Read::new(types::Fd(fd), buf.as_mut_ptr(), len)
+            .offset(offset)
+            .build()
+            .user_data(id);
+        inner.push(&sqe)?;
+        inner.ops.insert(id, OpState::Waiting(None));
+        Ok(id)
+    }
+
+    /// Polls the read `id` without blocking the executor thread, returns the result of the read.
+    fn poll(&self, id: u64, cx: &mut Context<'_>) -> Poll<io::Result<i32>> {
+        loop {
+            // resets the counter before reaping, so a completion after reaping wakes up the reactor again.
+            let mut counter = [0; 8];
+            match self.event.get_ref().read(&mut counter) {
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+
+            let mut inner = self.lock();
+            let queued = !inner.ring.submission().is_empty() && inner.ring.submit().is_err();
+            let wakers = inner.reap();
+            let rst = match inner.ops.get_mut(&id) {
+                Some(OpState::Waiting(waker)) => {
+                    *waker = Some(cx.waker().clone());
+                    None
+                }
+                _ => match inner.ops.remove(&id) {
+                    Some(OpState::Done(rst)) => Some(rst),
+                    _ => unreachable!("the read is polled after it completes"),
+                },
+            };
+            drop(inner);
+            wakers.into_iter().for_each(Waker::wake);
+
+            if let Some(rst) = rst {
+                return Poll::Ready(Ok(rst));
+            }
+            if queued {
+                // the submission is retried by the next poll
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            match self.event.poll_readable(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Hands the buffer of the dropped read `id` to the ring, which frees it when the read completes,
+    /// and asks the kernel to cancel the read. It never blocks.
+    fn abandon(&self, id: u64, buf: Vec<u8>) {
+        let mut inner = self.lock();
+        let wakers = inner.reap();
+        match inner.ops.remove(&id) {
+            Some(OpState::Waiting(_)) => {
+                inner.ops.insert(id, OpState::Abandoned { _buf: buf });
+                let sqe = opcode::AsyncCancel::new(id)
+                    .build()
+                    .user_data(CANCEL_USER_DATA);
+                // Safety: the cancellation has no buffer, best-effort, the read completes anyway.
+                let _ = unsafe { inner.push(&sqe) };
+            }
+            // the read has completed, the buffer is not used by the kernel anymore
+            _ => drop(buf),
+        }
+        drop(inner);
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// An `IORING_OP_READ` into an owned buffer.
+///
+/// If the future which owns the read is dropped while the read is in flight,
+/// the buffer is handed to the ring, see the `Drop` implementation.
+struct UringRead {
+    ring: &'static Ring,
+    id: Option<u64>,
+    buf: Vec<u8>,
+}
+
+impl UringRead {
+    /// Reads `buf[filled..]` from `offset` of the file, returns the number of bytes read.
+    async fn read(&mut self, fd: RawFd, filled: usize, offset: u64) -> io::Result<usize> {
+        let ring = self.ring;
+        // Safety: the buffer is alive until the read completes, or is abandoned on drop.
+        let id = unsafe { ring.submit(fd, &mut self.buf[filled..], offset)? };
+        self.id = Some(id);
+        let rst = poll_fn(|cx| ring.poll(id, cx)).await;
+        self.id = None;
+        match rst? {
+            rst if rst < 0 => Err(io::Error::from_raw_os_error(-rst)),
+            rst => Ok(rst as usize),
+        }
+    }
+}
+
+impl Drop for UringRead {
+    fn drop(&mut self) {
+        // the kernel may still write to the buffer, so the ring keeps it until the read completes.
+        if let Some(id) = self.id.take() {
+            self.ring.abandon(id, mem::take(&mut self.buf));
+        }
+    }
+}
+
+/// Returns whether all the pages spanned by `buf` are resident in memory.
+#[inline]
+fn is_resident(buf: &[u8]) -> bool {
+    match crate::advice::residency(buf) {
+        Ok((resident, total)) => resident == total,
+        Err(_) => false,
+    }
+}
+
+/// Reads exactly `dst.len()` bytes from `offset` of `buf`, which is the mapping of the file `fd`.
+///
+/// The data is copied from the mapping if the range is resident in memory, otherwise it is read
+/// with io_uring. If io_uring is not available, e.g. it is disabled by seccomp, the data is
+/// copied from the mapping as well.
+pub(crate) async fn read_into(buf: &[u8], fd: RawFd, dst: &mut [u8], offset: usize) -> Result<()> {
+    let src = match buf.get(offset..).and_then(|src| src.get(..dst.len())) {
+        Some(src) => src,
+        None => return Err(Error::from(ErrorKind::EOF)),
+    };
+    if is_resident(src) {
+        dst.copy_from_slice(src);
+        return Ok(());
+    }
+
+    let ring = match Ring::get() {
+        Some(ring) => ring,
+        None => {
+            dst.copy_from_slice(src);
+            return Ok(());
+        }
+    };
+    let mut op = UringRead {
+        ring,
+        id: None,
+        buf: vec![0; dst.len()],
+    };
+    let mut filled = 0;
+    while filled < dst.len() {
+        match op
+            .read(fd, filled, (offset + filled) as u64)
+            .await
+            .map_err(|e| Error::new(ErrorKind::IO, e))?
+        {
+            0 => return Err(Error::from(ErrorKind::EOF)),
+            n => filled += n,
+        }
+    }
+    dst.copy_from_slice(&op.buf);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::task::noop_waker;
+    use std::future::Future;
+    use std::os::unix::io::AsRawFd;
+
+    fn evicted_file(path: &str, data: &[u8]) -> File {
+        std::fs::write(path, data).unwrap();
+        let file = File::open(path).unwrap();
+        file.sync_all().unwrap();
+        unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+        file
+    }
+
+    #[test]
+    fn test_uring_read_shared_ring() {
+        let path = "uring_shared_ring_test.txt";
+        let data = (0..16384).map(|i| i as u8).collect::<Vec<_>>();
+        let file = evicted_file(path, &data);
+        scopeguard::defer!(std::fs::remove_file(path).unwrap());
+        let mmap = unsafe { memmapix::Mmap::map(&file) }.unwrap();
+        let fd = file.as_raw_fd();
+
+        let (mut a, mut b) = (vec![0; 4096], vec![0; 4096]);
+        async_io::block_on(async {
+            let (ra, rb) = futures_util::future::join(
+                read_into(&mmap, fd, &mut a, 0),
+                read_into(&mmap, fd, &mut b, 8192),
+            )
+            .await;
+            ra.unwrap();
+            rb.unwrap();
+        });
+        assert_eq!(a, &data[..4096]);
+        assert_eq!(b, &data[8192..12288]);
+        if let Some(ring) = Ring::get() {
+            assert!(std::ptr::eq(ring, Ring::get().unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_uring_read_dropped() {
+        let ring = match Ring::get() {
+            Some(ring) => ring,
+            // io_uring is not available, e.g. it is disabled by seccomp, there is nothing to abandon
+            None => return,
+        };
+        let path = "uring_dropped_test.txt";
+        let data = vec![1; 1 << 20];
+        let file = evicted_file(path, &data);
+        scopeguard::defer!(std::fs::remove_file(path).unwrap());
+        let mmap = unsafe { memmapix::Mmap::map(&file) }.unwrap();
+        let fd = file.as_raw_fd();
+
+        // the dropped read does not wait for the completion
+        let mut dst = vec![0; 1 << 20];
+        let mut fut = Box::pin(read_into(&mmap, fd, &mut dst, 0));
+        let waker = noop_waker();
+        let _ = fut.as_mut().poll(&mut Context::from_waker(&waker));
+        drop(fut);
+
+        // the abandoned buffer is freed when the ring is polled after the read completes
+        let mut buf = vec![0; 4096];
+        async_io::block_on(read_into(&mmap, fd, &mut buf, 4096)).unwrap();
+        assert_eq!(buf, &data[..4096]);
+        let abandoned = |ring: &Ring| {
+            let mut inner = ring.lock();
+            inner.reap();
+            inner
+                .ops
+                .values()
+                .any(|op| matches!(op, OpState::Abandoned { .. }))
+        };
+        let start = std::time::Instant::now();
+        while abandoned(ring) {
+            assert!(start.elapsed() < std::time::Duration::from_secs(10));
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+    }
+}
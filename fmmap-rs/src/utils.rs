@@ -4,20 +4,66 @@ use std::io;
 // use std::ops::{Bound, RangeBounds};
 use std::path::Path;
 
+use std::fs::OpenOptions;
+
+#[inline]
+fn read_only_options() -> OpenOptions {
+    let mut opts = OpenOptions::new();
+    opts.read(true);
+    opts
+}
+
+#[inline]
+fn exist_file_options(append: bool) -> OpenOptions {
+    let mut opts = OpenOptions::new();
+    opts.read(true).write(true).append(append);
+    opts
+}
+
+#[cfg(any(feature = "sync", feature = "tokio", feature = "smol", feature = "async-std"))]
+#[inline]
+fn truncate_options() -> OpenOptions {
+    let mut opts = OpenOptions::new();
+    opts.read(true).write(true).truncate(true);
+    opts
+}
+
+#[inline]
+fn open_or_create_options() -> OpenOptions {
+    let mut opts = OpenOptions::new();
+    opts.create(true).read(true).write(true);
+    opts
+}
+
+#[inline]
+fn create_options() -> OpenOptions {
+    let mut opts = OpenOptions::new();
+    opts.create_new(true).read(true).write(true).append(true);
+    opts
+}
+
+#[inline]
+fn check_dir(path: &Path) -> Result<()> {
+    if !path.is_dir() {
+        #[cfg(feature = "nightly")]
+        return Err(Error::new(
+            ErrorKind::IO,
+            io::Error::from(io::ErrorKind::NotADirectory),
+        ));
+
+        #[cfg(not(feature = "nightly"))]
+        return Err(Error::from(ErrorKind::NotADirectory));
+    }
+    Ok(())
+}
+
 cfg_sync! {
-    use std::fs::{File, OpenOptions};
+    use std::fs::File;
 
     /// Sync directory
     pub fn sync_dir<P: AsRef<Path>>(path: P) -> Result<()> {
         let path = path.as_ref();
-        if !path.is_dir() {
-            #[cfg(feature = "nightly")]
-            return Err(Error::new(ErrorKind::IO, io::Error::from(io::ErrorKind::NotADirectory)));
-
-            #[cfg(not(feature = "nightly"))]
-            return Err(Error::from(ErrorKind::NotADirectory));
-        }
-
+        check_dir(path)?;
         File::open(path)
             .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))?
             .sync_all()
@@ -27,145 +73,171 @@ cfg_sync! {
     /// Sync parent
     pub fn sync_parent<P: AsRef<Path>>(path: P) -> Result<()> {
         let path = path.as_ref().canonicalize().map_err(|e| Error::new(ErrorKind::IO, e))?;
-        let path = path.parent().unwrap();
-        if !path.is_dir() {
-            #[cfg(feature = "nightly")]
-            return Err(Error::new(ErrorKind::IO, io::Error::from(io::ErrorKind::NotADirectory)));
-            #[cfg(not(feature = "nightly"))]
-            return Err(Error::from(ErrorKind::NotADirectory));
+        sync_dir(path.parent().unwrap())
+    }
+
+    /// Sync directory at `path` relative to `dir`
+    #[cfg(unix)]
+    #[cfg_attr(docsrs, doc(cfg(unix)))]
+    pub fn sync_dir_at<P: AsRef<Path>>(dir: &crate::Dir, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if path.as_os_str().is_empty() {
+            return dir.sync_all();
         }
-        File::open(path)
-            .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))?
+        dir.open_file_at(path, rustix::fs::OFlags::RDONLY | rustix::fs::OFlags::DIRECTORY)?
             .sync_all()
-            .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))
+            .map_err(|e| Error::new_source_msg(ErrorKind::SyncDirFailed, path.to_string_lossy(), e))
+    }
+
+    /// Sync parent of `path` relative to `dir`
+    #[cfg(unix)]
+    #[cfg_attr(docsrs, doc(cfg(unix)))]
+    pub fn sync_parent_at<P: AsRef<Path>>(dir: &crate::Dir, path: P) -> Result<()> {
+        sync_dir_at(dir, path.as_ref().parent().unwrap_or_else(|| Path::new("")))
     }
 
     /// Open a read-only file
     pub fn open_read_only_file<P: AsRef<Path>>(path: P) -> Result<File> {
-        OpenOptions::new()
-            .read(true)
+        read_only_options()
             .open(path)
             .map_err(|e| Error::new(ErrorKind::IO, e))
     }
 
     /// Open an existing file in write mode, all writes will overwrite the original file
     pub fn open_exist_file<P: AsRef<Path>>(path: P) -> Result<File> {
-        OpenOptions::new()
-            .read(true)
-            .write(true)
-            .append(false)
+        exist_file_options(false)
             .open(path)
             .map_err(|e| Error::new(ErrorKind::IO, e))
     }
 
     /// Open an existing file in write mode, all writes will append to the file
     pub fn open_exist_file_with_append<P: AsRef<Path>>(path: P) -> Result<File> {
-        OpenOptions::new()
-            .read(true)
-            .write(true)
-            .append(true)
+        exist_file_options(true)
             .open(path)
             .map_err(|e| Error::new(ErrorKind::IO, e))
     }
 
     /// Open an existing file and truncate it
     pub fn open_file_with_truncate<P: AsRef<Path>>(path: P) -> Result<File> {
-        OpenOptions::new()
-            .read(true)
-            .write(true)
-            .truncate(true)
+        truncate_options()
             .open(path)
             .map_err(|e| Error::new(ErrorKind::IO, e))
     }
 
     /// Open or create a file
     pub fn open_or_create_file<P: AsRef<Path>>(path: P) -> Result<File> {
-        OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
+        open_or_create_options()
             .open(path)
             .map_err(|e| Error::new(ErrorKind::IO, e))
     }
 
     /// Create a new file
     pub fn create_file<P: AsRef<Path>>(path: P) -> Result<File> {
-        OpenOptions::new()
-            .create_new(true)
-            .read(true)
-            .write(true)
-            .append(true)
+        create_options()
             .open(path)
             .map_err(|e| Error::new(ErrorKind::IO, e))
     }
 }
 
 cfg_async! {
+    use crate::AsyncRuntime;
+
+    /// Opens the file with the options on the runtime.
+    async fn open_file_in<R: AsyncRuntime>(path: &Path, opts: OpenOptions) -> Result<R::File> {
+        R::open(path.to_path_buf(), opts)
+            .await
+            .map_err(|e| Error::new(ErrorKind::IO, e))
+    }
+
+    /// Opens a read-only file on the runtime.
+    pub(crate) async fn open_read_only_file_in<R: AsyncRuntime>(path: &Path) -> Result<R::File> {
+        open_file_in::<R>(path, read_only_options()).await
+    }
+
+    /// Opens an existing file in append mode on the runtime.
+    pub(crate) async fn open_exist_file_with_append_in<R: AsyncRuntime>(path: &Path) -> Result<R::File> {
+        open_file_in::<R>(path, exist_file_options(true)).await
+    }
+
+    /// Opens or creates a file on the runtime.
+    pub(crate) async fn open_or_create_file_in<R: AsyncRuntime>(path: &Path) -> Result<R::File> {
+        open_file_in::<R>(path, open_or_create_options()).await
+    }
+
+    /// Creates a new file on the runtime.
+    pub(crate) async fn create_file_in<R: AsyncRuntime>(path: &Path) -> Result<R::File> {
+        open_file_in::<R>(path, create_options()).await
+    }
+
+    /// Syncs the directory on the runtime.
+    pub(crate) async fn sync_dir_in<R: AsyncRuntime>(path: &Path) -> Result<()> {
+        check_dir(path)?;
+        let dir = R::open(path.to_path_buf(), read_only_options())
+            .await
+            .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))?;
+        R::sync_all(&dir)
+            .await
+            .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))
+    }
+
+    /// Syncs the parent directory on the runtime.
+    pub(crate) async fn sync_parent_in<R: AsyncRuntime>(path: &Path) -> Result<()> {
+        let path = path.canonicalize().map_err(|e| Error::new(ErrorKind::IO, e))?;
+        sync_dir_in::<R>(path.parent().unwrap()).await
+    }
+
+    /// Runs the blocking function on the blocking pool of the runtime.
+    pub(crate) async fn unblock_in<R, F, T>(f: F) -> Result<T>
+    where
+        R: AsyncRuntime,
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        R::spawn_blocking(f)
+            .await
+            .map_err(|e| Error::new(ErrorKind::IO, e))
+    }
+
     macro_rules! impl_async_file_utils {
-        ($file: ident, $open_options: ident) => {
+        ($runtime: ty) => {
+            /// Sync directory
+            pub async fn sync_dir_async<P: AsRef<Path>>(path: P) -> Result<()> {
+                super::sync_dir_in::<$runtime>(path.as_ref().as_ref()).await
+            }
+
+            /// Sync parent directory
+            pub async fn sync_parent_async<P: AsRef<Path>>(path: P) -> Result<()> {
+                super::sync_parent_in::<$runtime>(path.as_ref().as_ref()).await
+            }
+
             /// Open a read-only file
-            pub async fn open_read_only_file_async<P: AsRef<Path>>(path: P) -> Result<$file> {
-                <$open_options>::new()
-                    .read(true)
-                    .open(path)
-                    .await
-                    .map_err(|e| Error::new(ErrorKind::IO, e))
+            pub async fn open_read_only_file_async<P: AsRef<Path>>(path: P) -> Result<File> {
+                super::open_read_only_file_in::<$runtime>(path.as_ref().as_ref()).await
             }
 
             /// Open an existing file in write mode, all writes will overwrite the original file
-            pub async fn open_exist_file_async<P: AsRef<Path>>(path: P) -> Result<$file> {
-                <$open_options>::new()
-                    .read(true)
-                    .write(true)
-                    .append(false)
-                    .open(path)
-                    .await
-                    .map_err(|e| Error::new(ErrorKind::IO, e))
+            pub async fn open_exist_file_async<P: AsRef<Path>>(path: P) -> Result<File> {
+                super::open_file_in::<$runtime>(path.as_ref().as_ref(), super::exist_file_options(false)).await
             }
 
             /// Open an existing file in write mode, all writes will append to the file
-            pub async fn open_exist_file_with_append_async<P: AsRef<Path>>(path: P) -> Result<$file> {
-                <$open_options>::new()
-                    .read(true)
-                    .write(true)
-                    .append(true)
-                    .open(path)
-                    .await
-                    .map_err(|e| Error::new(ErrorKind::IO, e))
+            pub async fn open_exist_file_with_append_async<P: AsRef<Path>>(path: P) -> Result<File> {
+                super::open_exist_file_with_append_in::<$runtime>(path.as_ref().as_ref()).await
             }
 
             /// Open an existing file and truncate it
-            pub async fn open_file_with_truncate_async<P: AsRef<Path>>(path: P) -> Result<$file> {
-                <$open_options>::new()
-                    .read(true)
-                    .write(true)
-                    .truncate(true)
-                    .open(path)
-                    .await
-                    .map_err(|e| Error::new(ErrorKind::IO, e))
+            pub async fn open_file_with_truncate_async<P: AsRef<Path>>(path: P) -> Result<File> {
+                super::open_file_in::<$runtime>(path.as_ref().as_ref(), super::truncate_options()).await
             }
 
             /// Open or create a file
-            pub async fn open_or_create_file_async<P: AsRef<Path>>(path: P) -> Result<$file> {
-                <$open_options>::new()
-                    .create(true)
-                    .read(true)
-                    .write(true)
-                    .open(path)
-                    .await
-                    .map_err(|e| Error::new(ErrorKind::IO, e))
+            pub async fn open_or_create_file_async<P: AsRef<Path>>(path: P) -> Result<File> {
+                super::open_or_create_file_in::<$runtime>(path.as_ref().as_ref()).await
             }
 
             /// Create a new file
-            pub async fn create_file_async<P: AsRef<Path>>(path: P) -> Result<$file> {
-                <$open_options>::new()
-                    .create_new(true)
-                    .read(true)
-                    .write(true)
-                    .append(true)
-                    .open(path)
-                    .await
-                    .map_err(|e| Error::new(ErrorKind::IO, e))
+            pub async fn create_file_async<P: AsRef<Path>>(path: P) -> Result<File> {
+                super::create_file_in::<$runtime>(path.as_ref().as_ref()).await
             }
         };
     }
@@ -174,156 +246,36 @@ cfg_async! {
 cfg_smol! {
     /// file open utils for smol
     pub mod smol {
-        use smol::fs::{File, OpenOptions};
-        use crate::error::{Error, ErrorKind, Result};
-        #[cfg(feature = "nightly")]
-        use std::io;
+        use crate::error::Result;
+        use crate::smol::SmolRuntime;
+        use smol::fs::File;
         use std::path::Path;
 
-        /// Sync directory
-        pub async fn sync_dir_async<P: AsRef<Path>>(path: P) -> Result<()> {
-            let path = path.as_ref();
-            if !path.is_dir() {
-                #[cfg(feature = "nightly")]
-                return Err(Error::new(ErrorKind::IO, io::Error::from(io::ErrorKind::NotADirectory)));
-
-                #[cfg(not(feature = "nightly"))]
-                return Err(Error::from(ErrorKind::NotADirectory));
-            }
-
-            File::open(path)
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))?
-                .sync_all()
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))
-        }
-
-        /// Sync parent directory
-        pub async fn sync_parent_async<P: AsRef<Path>>(path: P) -> Result<()> {
-            let path = path.as_ref().canonicalize().map_err(|e| Error::new(ErrorKind::IO, e))?;
-            let path = path.parent().unwrap();
-            if !path.is_dir() {
-                #[cfg(feature = "nightly")]
-                return Err(Error::new(ErrorKind::IO, io::Error::from(io::ErrorKind::NotADirectory)));
-
-                #[cfg(not(feature = "nightly"))]
-                return Err(Error::from(ErrorKind::NotADirectory));
-            }
-
-            File::open(path)
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))?
-                .sync_all()
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))
-        }
-
-        impl_async_file_utils!(File, OpenOptions);
+        impl_async_file_utils!(SmolRuntime);
     }
 }
 
 cfg_tokio! {
     /// file open utils for tokio
     pub mod tokio {
-        use tokio::fs::{File, OpenOptions};
-        use crate::error::{Error, ErrorKind, Result};
-        #[cfg(feature = "nightly")]
-        use std::io;
+        use crate::error::Result;
+        use crate::tokio::TokioRuntime;
         use std::path::Path;
+        use tokio::fs::File;
 
-        /// Sync directory
-        pub async fn sync_dir_async<P: AsRef<Path>>(path: P) -> Result<()> {
-            let path = path.as_ref();
-            if !path.is_dir() {
-                #[cfg(feature = "nightly")]
-                return Err(Error::new(ErrorKind::IO, io::Error::from(io::ErrorKind::NotADirectory)));
-
-                #[cfg(not(feature = "nightly"))]
-                return Err(Error::from(ErrorKind::NotADirectory));
-            }
-
-            File::open(path)
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))?
-                .sync_all()
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))
-        }
-
-        /// Sync parent directory
-        pub async fn sync_parent_async<P: AsRef<Path>>(path: P) -> Result<()> {
-            let path = path.as_ref().canonicalize().map_err(|e| Error::new(ErrorKind::IO, e))?;
-            let path = path.parent().unwrap();
-            if !path.is_dir() {
-                #[cfg(feature = "nightly")]
-                return Err(Error::new(ErrorKind::IO, io::Error::from(io::ErrorKind::NotADirectory)));
-
-                #[cfg(not(feature = "nightly"))]
-                return Err(Error::from(ErrorKind::NotADirectory));
-            }
-
-            File::open(path)
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))?
-                .sync_all()
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))
-        }
-
-        impl_async_file_utils!(File, OpenOptions);
+        impl_async_file_utils!(TokioRuntime);
     }
 }
 
 cfg_async_std! {
     /// file open utils for async-std
     pub mod async_std {
-        use async_std::fs::{File, OpenOptions};
-        use crate::error::{Error, ErrorKind, Result};
-        #[cfg(feature = "nightly")]
-        use std::io;
+        use crate::error::Result;
+        use crate::async_std::AsyncStdRuntime;
+        use async_std::fs::File;
         use async_std::path::Path;
 
-        /// Sync directory
-        pub async fn sync_dir_async<P: AsRef<Path>>(path: P) -> Result<()> {
-            let path = path.as_ref();
-            if !path.is_dir().await {
-                #[cfg(feature = "nightly")]
-                return Err(Error::new(ErrorKind::IO, io::Error::from(io::ErrorKind::NotADirectory)));
-
-                #[cfg(not(feature = "nightly"))]
-                return Err(Error::from(ErrorKind::NotADirectory));
-            }
-
-            File::open(path)
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))?
-                .sync_all()
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))
-        }
-
-        /// Sync parent directory
-        pub async fn sync_parent_async<P: AsRef<Path>>(path: P) -> Result<()> {
-            let path = path.as_ref().canonicalize().await.map_err(|e| Error::new(ErrorKind::IO, e))?;
-            let path = path.parent().unwrap();
-            if !path.is_dir().await {
-                #[cfg(feature = "nightly")]
-                return Err(Error::new(ErrorKind::IO, io::Error::from(io::ErrorKind::NotADirectory)));
-
-                #[cfg(not(feature = "nightly"))]
-                return Err(Error::from(ErrorKind::NotADirectory));
-            }
-
-            File::open(path)
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))?
-                .sync_all()
-                .await
-                .map_err(|e| Error::new_source_msg(ErrorKind::OpenFailed, path.to_string_lossy(), e))
-        }
-
-        impl_async_file_utils!(File, OpenOptions);
+        impl_async_file_utils!(AsyncStdRuntime);
     }
 }
 
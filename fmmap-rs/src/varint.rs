@@ -0,0 +1,213 @@
+//! LEB128 variable-length integers, zigzag encoding of the signed integers,
+//! and the length prefixes of the length-prefixed records.
+use crate::error::{Error, ErrorKind, Result};
+use std::io;
+
+/// The maximum length of a LEB128 encoded 64-bit integer.
+pub(crate) const MAX_VARINT_LEN: usize = 10;
+
+/// The length prefix of a length-prefixed record, which is followed by the record bytes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LengthPrefix {
+    /// An unsigned 32 bit integer in big-endian byte order.
+    U32,
+    /// An unsigned 32 bit integer in little-endian byte order.
+    U32Le,
+    /// An unsigned LEB128 variable-length integer.
+    Varint,
+}
+
+impl LengthPrefix {
+    /// Encodes `len` into `buf`, returns the number of bytes of the prefix.
+    pub(crate) fn encode(&self, len: usize, buf: &mut [u8; MAX_VARINT_LEN]) -> Result<usize> {
+        match self {
+            LengthPrefix::U32 | LengthPrefix::U32Le => {
+                if len > u32::MAX as usize {
+                    return Err(Error::from(ErrorKind::OutOfBound(len, u32::MAX as usize)));
+                }
+                let bytes = if *self == LengthPrefix::U32 {
+                    (len as u32).to_be_bytes()
+                } else {
+                    (len as u32).to_le_bytes()
+                };
+                buf[..4].copy_from_slice(&bytes);
+                Ok(4)
+            }
+            LengthPrefix::Varint => Ok(encode_uvarint(len as u64, buf)),
+        }
+    }
+
+    /// Decodes the prefix from the start of `buf`, returns the record length and the number of bytes of the prefix.
+    pub(crate) fn decode(&self, buf: &[u8]) -> Result<(usize, usize)> {
+        match self {
+            LengthPrefix::U32 | LengthPrefix::U32Le => {
+                let mut bytes = [0; 4];
+                match buf.get(..4) {
+                    Some(src) => bytes.copy_from_slice(src),
+                    None => return Err(Error::from(ErrorKind::EOF)),
+                }
+                let len = if *self == LengthPrefix::U32 {
+                    u32::from_be_bytes(bytes)
+                } else {
+                    u32::from_le_bytes(bytes)
+                };
+                Ok((len as usize, 4))
+            }
+            LengthPrefix::Varint => {
+                let (len, n) = decode_uvarint(buf)?;
+                Ok((to_usize(len)?, n))
+            }
+        }
+    }
+}
+
+/// Decodes an unsigned LEB128 integer byte by byte.
+#[derive(Default)]
+pub(crate) struct UvarintDecoder {
+    val: u64,
+    shift: u32,
+}
+
+impl UvarintDecoder {
+    /// Feeds the next byte, returns the integer if it is the last byte.
+    pub(crate) fn push(&mut self, byte: u8) -> Result<Option<u64>> {
+        // the 10th byte can only hold the highest bit of a 64-bit integer
+        if self.shift == 63 && byte > 1 {
+            return Err(Error::from(ErrorKind::InvalidVarint));
+        }
+        self.val |= ((byte & 0x7f) as u64) << self.shift;
+        if byte & 0x80 == 0 {
+            return Ok(Some(self.val));
+        }
+        self.shift += 7;
+        Ok(None)
+    }
+}
+
+/// Encodes `val` as an unsigned LEB128 integer into `buf`, returns the number of bytes written.
+pub(crate) fn encode_uvarint(mut val: u64, buf: &mut [u8; MAX_VARINT_LEN]) -> usize {
+    let mut n = 0;
+    while val >= 0x80 {
+        buf[n] = (val as u8) | 0x80;
+        val >>= 7;
+        n += 1;
+    }
+    buf[n] = val as u8;
+    n + 1
+}
+
+/// Decodes an unsigned LEB128 integer from the start of `buf`, returns the integer and the number of bytes read.
+pub(crate) fn decode_uvarint(buf: &[u8]) -> Result<(u64, usize)> {
+    let mut decoder = UvarintDecoder::default();
+    for (idx, byte) in buf.iter().take(MAX_VARINT_LEN).enumerate() {
+        if let Some(val) = decoder.push(*byte)? {
+            return Ok((val, idx + 1));
+        }
+    }
+    if buf.len() < MAX_VARINT_LEN {
+        Err(Error::from(ErrorKind::EOF))
+    } else {
+        Err(Error::from(ErrorKind::InvalidVarint))
+    }
+}
+
+/// Maps a signed integer to an unsigned integer, so the integers with small absolute values
+/// have short LEB128 encodings, e.g. `0 => 0, -1 => 1, 1 => 2, -2 => 3`.
+#[inline]
+pub(crate) fn zigzag_encode(val: i64) -> u64 {
+    ((val << 1) ^ (val >> 63)) as u64
+}
+
+/// The inverse of [`zigzag_encode`].
+#[inline]
+pub(crate) fn zigzag_decode(val: u64) -> i64 {
+    ((val >> 1) as i64) ^ -((val & 1) as i64)
+}
+
+/// Converts a decoded length to `usize`.
+#[inline]
+pub(crate) fn to_usize(len: u64) -> Result<usize> {
+    if len > usize::MAX as u64 {
+        Err(Error::from(ErrorKind::OutOfBound(usize::MAX, usize::MAX)))
+    } else {
+        Ok(len as usize)
+    }
+}
+
+/// Converts the codec errors to [`io::Error`] for the readers and writers.
+pub(crate) fn into_io_error(err: Error) -> io::Error {
+    match err.kind() {
+        ErrorKind::EOF => io::Error::new(io::ErrorKind::UnexpectedEof, err),
+        _ => io::Error::new(io::ErrorKind::InvalidData, err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uvarint() {
+        let mut buf = [0; MAX_VARINT_LEN];
+        for (val, len) in [
+            (0, 1),
+            (127, 1),
+            (128, 2),
+            (300, 2),
+            (u32::MAX as u64, 5),
+            (u64::MAX, 10),
+        ] {
+            assert_eq!(encode_uvarint(val, &mut buf), len);
+            assert_eq!(decode_uvarint(&buf[..len]).unwrap(), (val, len));
+            assert_eq!(
+                decode_uvarint(&buf[..len - 1]).unwrap_err().kind(),
+                ErrorKind::EOF
+            );
+        }
+        assert_eq!(encode_uvarint(300, &mut buf), 2);
+        assert_eq!(&buf[..2], &[0xac, 0x02]);
+
+        // overflows a 64-bit integer
+        let overflow = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        assert_eq!(
+            decode_uvarint(&overflow).unwrap_err().kind(),
+            ErrorKind::InvalidVarint
+        );
+        assert_eq!(
+            decode_uvarint(&[0x80; 11]).unwrap_err().kind(),
+            ErrorKind::InvalidVarint
+        );
+    }
+
+    #[test]
+    fn test_zigzag() {
+        for (val, encoded) in [
+            (0, 0),
+            (-1, 1),
+            (1, 2),
+            (-2, 3),
+            (i64::MAX, u64::MAX - 1),
+            (i64::MIN, u64::MAX),
+        ] {
+            assert_eq!(zigzag_encode(val), encoded);
+            assert_eq!(zigzag_decode(encoded), val);
+        }
+    }
+
+    #[test]
+    fn test_length_prefix() {
+        let mut buf = [0; MAX_VARINT_LEN];
+        assert_eq!(LengthPrefix::U32.encode(258, &mut buf).unwrap(), 4);
+        assert_eq!(&buf[..4], &[0, 0, 1, 2]);
+        assert_eq!(LengthPrefix::U32.decode(&buf).unwrap(), (258, 4));
+        assert_eq!(LengthPrefix::U32Le.encode(258, &mut buf).unwrap(), 4);
+        assert_eq!(&buf[..4], &[2, 1, 0, 0]);
+        assert_eq!(LengthPrefix::U32Le.decode(&buf).unwrap(), (258, 4));
+        assert_eq!(LengthPrefix::Varint.encode(258, &mut buf).unwrap(), 2);
+        assert_eq!(LengthPrefix::Varint.decode(&buf).unwrap(), (258, 2));
+        assert_eq!(
+            LengthPrefix::U32.decode(&buf[..3]).unwrap_err().kind(),
+            ErrorKind::EOF
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::{Error, ErrorKind, Result};
+#[cfg(feature = "bytemuck")]
+use bytemuck::Pod;
+use std::mem;
+use std::sync::atomic::AtomicU32;
+#[cfg(target_has_atomic = "64")]
+use std::sync::atomic::AtomicU64;
+
+/// Returns the end of `size` bytes starting from `offset` in a buffer of `buf_len` bytes at `ptr`,
+/// checking the bounds and that `ptr + offset` is aligned to `align`.
+#[inline]
+fn checked_range(
+    buf_len: usize,
+    ptr: *const u8,
+    offset: usize,
+    size: Option<usize>,
+    align: usize,
+) -> Result<usize> {
+    let end = size
+        .and_then(|sz| offset.checked_add(sz))
+        .ok_or_else(|| Error::from(ErrorKind::EOF))?;
+    if end > buf_len {
+        return Err(Error::from(ErrorKind::EOF));
+    }
+
+    if (ptr as usize).wrapping_add(offset) % align != 0 {
+        return Err(Error::from(ErrorKind::Misaligned(offset, align)));
+    }
+    Ok(end)
+}
+
+#[cfg(feature = "bytemuck")]
+#[inline]
+pub(crate) fn view<T: Pod>(buf: &[u8], offset: usize) -> Result<&T> {
+    view_slice(buf, offset, 1).map(|s| &s[0])
+}
+
+#[cfg(feature = "bytemuck")]
+#[inline]
+pub(crate) fn view_slice<T: Pod>(buf: &[u8], offset: usize, count: usize) -> Result<&[T]> {
+    let align = mem::align_of::<T>();
+    let size = mem::size_of::<T>().checked_mul(count);
+    let end = checked_range(buf.len(), buf.as_ptr(), offset, size, align)?;
+    bytemuck::try_cast_slice(&buf[offset..end])
+        .map_err(|_| Error::from(ErrorKind::Misaligned(offset, align)))
+}
+
+#[cfg(feature = "bytemuck")]
+#[inline]
+pub(crate) fn view_mut<T: Pod>(buf: &mut [u8], offset: usize) -> Result<&mut T> {
+    view_slice_mut(buf, offset, 1).map(|s| &mut s[0])
+}
+
+#[cfg(feature = "bytemuck")]
+#[inline]
+pub(crate) fn view_slice_mut<T: Pod>(
+    buf: &mut [u8],
+    offset: usize,
+    count: usize,
+) -> Result<&mut [T]> {
+    let align = mem::align_of::<T>();
+    let size = mem::size_of::<T>().checked_mul(count);
+    let end = checked_range(buf.len(), buf.as_ptr(), offset, size, align)?;
+    bytemuck::try_cast_slice_mut(&mut buf[offset..end])
+        .map_err(|_| Error::from(ErrorKind::Misaligned(offset, align)))
+}
+
+#[inline]
+pub(crate) fn atomic_u32(buf: &mut [u8], offset: usize) -> Result<&AtomicU32> {
+    let align = mem::align_of::<AtomicU32>();
+    let size = Some(mem::size_of::<AtomicU32>());
+    checked_range(buf.len(), buf.as_ptr(), offset, size, align)?;
+    // Safety: the range is in bounds, aligned and writable, and AtomicU32 has the same
+    // in-memory representation as u32, all the bit patterns are valid.
+    Ok(unsafe { &*(buf.as_mut_ptr().add(offset) as *const AtomicU32) })
+}
+
+#[cfg(target_has_atomic = "64")]
+#[inline]
+pub(crate) fn atomic_u64(buf: &mut [u8], offset: usize) -> Result<&AtomicU64> {
+    let align = mem::align_of::<AtomicU64>();
+    let size = Some(mem::size_of::<AtomicU64>());
+    checked_range(buf.len(), buf.as_ptr(), offset, size, align)?;
+    // Safety: the range is in bounds, aligned and writable, and AtomicU64 has the same
+    // in-memory representation as u64, all the bit patterns are valid.
+    Ok(unsafe { &*(buf.as_mut_ptr().add(offset) as *const AtomicU64) })
+}
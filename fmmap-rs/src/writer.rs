@@ -1,6 +1,6 @@
 cfg_sync!(
     mod sync_impl;
-    pub use sync_impl::{MmapFileWriter, MmapFileWriterExt};
+    pub use sync_impl::{MmapFileWriter, MmapFileWriterExt, OwnedMmapFileWriter};
 );
 
 cfg_async! {
@@ -48,6 +48,11 @@ cfg_async! {
                 pub fn len(&self) -> usize {
                     self.len
                 }
+
+                /// Writes the whole `buf` from the current position.
+                fn write_all_buf(&mut self, buf: &[u8]) -> std::io::Result<()> {
+                    std::io::Write::write_all(&mut self.w, buf)
+                }
             }
 
             impl Debug for AsyncMmapFileWriter<'_> {
@@ -61,14 +66,205 @@ cfg_async! {
             }
         };
     }
+
+    macro_rules! declare_and_impl_owned_writer {
+        () => {
+            /// AsyncOwnedMmapFileWriter helps read or write data from mmap file
+            /// like a normal file.
+            ///
+            /// Unlike [`AsyncMmapFileWriter`], it owns the mmap file, so it can be returned from functions
+            /// or moved into spawned tasks, see [`AsyncMmapFileMut::into_writer`].
+            ///
+            /// # Notes
+            /// If you use a writer to write data to mmap, there is no guarantee all
+            /// data will be durably stored. So you need to call [`flush`]/[`flush_range`]/[`flush_async`]/[`flush_async_range`] in [`AsyncMmapFileMutExt`]
+            /// on the file returned by [`into_inner`] to guarantee all data will be durably stored.
+            ///
+            /// [`AsyncMmapFileWriter`]: struct.AsyncMmapFileWriter.html
+            /// [`AsyncMmapFileMut::into_writer`]: struct.AsyncMmapFileMut.html#method.into_writer
+            /// [`flush`]: trait.AsyncMmapFileMutExt.html#methods.flush
+            /// [`flush_range`]: trait.AsyncMmapFileMutExt.html#methods.flush_range
+            /// [`flush_async`]: trait.AsyncMmapFileMutExt.html#methods.flush_async
+            /// [`flush_async_range`]: trait.AsyncMmapFileMutExt.html#methods.flush_async_range
+            /// [`AsyncMmapFileMutExt`]: trait.AsyncMmapFileMutExt.html
+            /// [`into_inner`]: struct.AsyncOwnedMmapFileWriter.html#method.into_inner
+            pub struct AsyncOwnedMmapFileWriter<R: AsyncRuntime> {
+                w: std::io::Cursor<OwnedSlice<AsyncMmapFileMut<R>>>,
+                offset: usize,
+                len: usize,
+            }
+
+            impl<R: AsyncRuntime> AsyncOwnedMmapFileWriter<R> {
+                pub(crate) fn new(file: AsyncMmapFileMut<R>, offset: usize, len: usize) -> Self {
+                    Self {
+                        w: std::io::Cursor::new(OwnedSlice::new(file, offset, len)),
+                        offset,
+                        len
+                    }
+                }
+
+                /// Returns the start offset(related to the mmap) of the writer
+                #[inline]
+                pub fn offset(&self) -> usize {
+                    self.offset
+                }
+
+                /// Returns the length of the writer
+                #[inline]
+                pub fn len(&self) -> usize {
+                    self.len
+                }
+
+                /// Consumes the writer, returns the underlying mmap file.
+                #[inline]
+                pub fn into_inner(self) -> AsyncMmapFileMut<R> {
+                    self.w.into_inner().into_inner()
+                }
+
+                /// Writes `buf` from the current position, returns how many bytes written.
+                fn write_buf(&mut self, buf: &[u8]) -> usize {
+                    let pos = self.w.position();
+                    let n = self.w.get_mut().write_at(pos, buf);
+                    self.w.set_position(pos + n as u64);
+                    n
+                }
+
+                /// Writes the whole `buf` from the current position, the writer never writes out of its range.
+                fn write_all_buf(&mut self, buf: &[u8]) -> std::io::Result<()> {
+                    if self.write_buf(buf) < buf.len() {
+                        return Err(std::io::Error::new(std::io::ErrorKind::WriteZero, "failed to write whole buffer"));
+                    }
+                    Ok(())
+                }
+            }
+
+            // the mmap file is never pinned, the writer only writes it through the cursor
+            impl<R: AsyncRuntime> Unpin for AsyncOwnedMmapFileWriter<R> {}
+
+            impl<R: AsyncRuntime> Debug for AsyncOwnedMmapFileWriter<R> {
+                fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct("AsyncOwnedMmapFileWriter")
+                        .field("offset", &self.offset)
+                        .field("len", &self.len)
+                        .field("writer", &self.w)
+                        .finish()
+                }
+            }
+
+            impl<R: AsyncRuntime> Buf for AsyncOwnedMmapFileWriter<R> {
+                fn remaining(&self) -> usize {
+                    self.w.remaining()
+                }
+
+                fn chunk(&self) -> &[u8] {
+                    self.w.chunk()
+                }
+
+                fn advance(&mut self, cnt: usize) {
+                    self.w.advance(cnt)
+                }
+            }
+        };
+    }
+
+    macro_rules! declare_async_mmap_file_writer_ext {
+        () => {
+            /// Extends the async writers with methods for writing variable-length integers and length-prefixed records.
+            ///
+            /// The fixed-width integers can be written by the extension traits of the runtime.
+            #[async_trait]
+            pub trait AsyncMmapFileWriterExt {
+                /// Writes an unsigned LEB128 variable-length integer to the underlying writer
+                async fn write_uvarint(&mut self, n: u64) -> std::io::Result<()>;
+                /// Writes a zigzag encoded signed LEB128 variable-length integer to the underlying writer
+                async fn write_varint(&mut self, n: i64) -> std::io::Result<()>;
+                /// Writes `src` prefixed by its length to the underlying writer.
+                async fn write_length_prefixed(&mut self, src: &[u8], prefix: LengthPrefix) -> std::io::Result<()>;
+            }
+        };
+    }
+
+    macro_rules! impl_async_mmap_file_writer_ext {
+        ($name: ty $(, $runtime: ident)?) => {
+            #[async_trait]
+            impl $(<$runtime: AsyncRuntime>)? AsyncMmapFileWriterExt for $name {
+                async fn write_uvarint(&mut self, n: u64) -> std::io::Result<()> {
+                    let mut buf = [0; MAX_VARINT_LEN];
+                    let len = encode_uvarint(n, &mut buf);
+                    self.write_all_buf(&buf[..len])
+                }
+
+                async fn write_varint(&mut self, n: i64) -> std::io::Result<()> {
+                    self.write_uvarint(zigzag_encode(n)).await
+                }
+
+                async fn write_length_prefixed(&mut self, src: &[u8], prefix: LengthPrefix) -> std::io::Result<()> {
+                    let mut buf = [0; MAX_VARINT_LEN];
+                    let len = prefix.encode(src.len(), &mut buf).map_err(into_io_error)?;
+                    self.write_all_buf(&buf[..len])?;
+                    self.write_all_buf(src)
+                }
+            }
+        };
+    }
+
+    macro_rules! impl_futures_io_for_owned_writer {
+        ($read: path, $buf_read: path, $seek: path, $write: path) => {
+            impl<R: AsyncRuntime> $read for AsyncOwnedMmapFileWriter<R> {
+                fn poll_read(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+                    Poll::Ready(std::io::Read::read(&mut self.get_mut().w, buf))
+                }
+            }
+
+            impl<R: AsyncRuntime> $buf_read for AsyncOwnedMmapFileWriter<R> {
+                fn poll_fill_buf(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+                    Poll::Ready(std::io::BufRead::fill_buf(&mut self.get_mut().w))
+                }
+
+                fn consume(self: Pin<&mut Self>, amt: usize) {
+                    std::io::BufRead::consume(&mut self.get_mut().w, amt)
+                }
+            }
+
+            impl<R: AsyncRuntime> $seek for AsyncOwnedMmapFileWriter<R> {
+                fn poll_seek(self: Pin<&mut Self>, _cx: &mut Context<'_>, pos: SeekFrom) -> Poll<std::io::Result<u64>> {
+                    Poll::Ready(std::io::Seek::seek(&mut self.get_mut().w, pos))
+                }
+            }
+
+            impl<R: AsyncRuntime> $write for AsyncOwnedMmapFileWriter<R> {
+                fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+                    Poll::Ready(Ok(self.get_mut().write_buf(buf)))
+                }
+
+                fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                    Poll::Ready(Ok(()))
+                }
+
+                fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                    Poll::Ready(Ok(()))
+                }
+            }
+        };
+    }
 }
 
+cfg_async!(
+    mod async_impl;
+    pub use async_impl::{AsyncMmapFileWriter, AsyncMmapFileWriterExt, AsyncOwnedMmapFileWriter};
+);
+
 cfg_async_std!(
-    pub mod async_std_impl;
+    #[cfg(test)]
+    mod async_std_impl;
 );
+
 cfg_smol!(
-    pub mod smol_impl;
+    #[cfg(test)]
+    mod smol_impl;
 );
+
 cfg_tokio!(
-    pub mod tokio_impl;
+    #[cfg(test)]
+    mod tokio_impl;
 );
@@ -0,0 +1,186 @@
+use crate::reader::OwnedSlice;
+use crate::varint::{encode_uvarint, into_io_error, zigzag_encode, MAX_VARINT_LEN};
+use crate::{AsyncMmapFileMut, AsyncRuntime, LengthPrefix};
+use async_trait::async_trait;
+use bytes::Buf;
+use pin_project_lite::pin_project;
+use std::fmt::{Debug, Formatter};
+use std::io::Cursor;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use std::io::SeekFrom;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use std::pin::Pin;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use std::task::{Context, Poll};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+
+declare_and_impl_basic_writer!();
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<'a> AsyncRead for AsyncMmapFileWriter<'a> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        self.project().w.poll_read(cx, buf)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<'a> AsyncBufRead for AsyncMmapFileWriter<'a> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        self.project().w.poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.project().w.consume(amt)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<'a> AsyncSeek for AsyncMmapFileWriter<'a> {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        self.project().w.start_seek(position)
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        self.project().w.poll_complete(cx)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<'a> AsyncWrite for AsyncMmapFileWriter<'a> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        self.project().w.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.project().w.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.project().w.poll_shutdown(cx)
+    }
+}
+
+impl<'a> Buf for AsyncMmapFileWriter<'a> {
+    fn remaining(&self) -> usize {
+        self.w.remaining()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.w.chunk()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.w.advance(cnt)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
+impl<'a> futures_io::AsyncRead for AsyncMmapFileWriter<'a> {
+    fn poll_read(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(std::io::Read::read(self.project().w.get_mut(), buf))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
+impl<'a> futures_io::AsyncBufRead for AsyncMmapFileWriter<'a> {
+    fn poll_fill_buf(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        Poll::Ready(std::io::BufRead::fill_buf(self.project().w.get_mut()))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        std::io::BufRead::consume(self.project().w.get_mut(), amt)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
+impl<'a> futures_io::AsyncSeek for AsyncMmapFileWriter<'a> {
+    fn poll_seek(self: Pin<&mut Self>, _cx: &mut Context<'_>, pos: SeekFrom) -> Poll<std::io::Result<u64>> {
+        Poll::Ready(std::io::Seek::seek(self.project().w.get_mut(), pos))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-io")))]
+impl<'a> futures_io::AsyncWrite for AsyncMmapFileWriter<'a> {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(std::io::Write::write(self.project().w.get_mut(), buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(std::io::Write::flush(self.project().w.get_mut()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+declare_and_impl_owned_writer!();
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<R: AsyncRuntime> AsyncRead for AsyncOwnedMmapFileWriter<R> {
+    fn poll_read(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let n = std::io::Read::read(&mut self.get_mut().w, buf.initialize_unfilled())?;
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<R: AsyncRuntime> AsyncBufRead for AsyncOwnedMmapFileWriter<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        Poll::Ready(std::io::BufRead::fill_buf(&mut self.get_mut().w))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        std::io::BufRead::consume(&mut self.get_mut().w, amt)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<R: AsyncRuntime> AsyncSeek for AsyncOwnedMmapFileWriter<R> {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        std::io::Seek::seek(&mut self.get_mut().w, position).map(drop)
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Poll::Ready(Ok(self.w.position()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<R: AsyncRuntime> AsyncWrite for AsyncOwnedMmapFileWriter<R> {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(Ok(self.get_mut().write_buf(buf)))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl_futures_io_for_owned_writer!(futures_io::AsyncRead, futures_io::AsyncBufRead, futures_io::AsyncSeek, futures_io::AsyncWrite);
+
+declare_async_mmap_file_writer_ext!();
+
+impl_async_mmap_file_writer_ext!(AsyncMmapFileWriter<'_>);
+
+impl_async_mmap_file_writer_ext!(AsyncOwnedMmapFileWriter<R>, R);
+
@@ -1,47 +1,3 @@
-use std::fmt::{Debug, Formatter};
-use std::pin::Pin;
-use std::task::{Context, Poll};
-use async_std::io::{Cursor, Read, BufRead, Seek, SeekFrom, Write};
-use pin_project_lite::pin_project;
-
-declare_and_impl_basic_writer!();
-
-impl<'a> Read for AsyncMmapFileWriter<'a> {
-    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
-        self.project().w.poll_read(cx, buf)
-    }
-}
-
-impl<'a> BufRead for AsyncMmapFileWriter<'a> {
-    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
-        self.project().w.poll_fill_buf(cx)
-    }
-
-    fn consume(self: Pin<&mut Self>, amt: usize) {
-        self.project().w.consume(amt)
-    }
-}
-
-impl<'a> Seek for AsyncMmapFileWriter<'a> {
-    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<std::io::Result<u64>> {
-        self.project().w.poll_seek(cx, pos)
-    }
-}
-
-impl<'a> Write for AsyncMmapFileWriter<'a> {
-    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
-        self.project().w.poll_write(cx, buf)
-    }
-
-    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
-        self.project().w.poll_flush(cx)
-    }
-
-    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
-        self.project().w.poll_close(cx)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use futures_util::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
@@ -61,5 +17,41 @@ mod tests {
         w.fill_buf().await.unwrap();
         w.close().await.unwrap();
     }
-}
 
+    #[async_std::test]
+    async fn test_owned_writer() {
+        use crate::async_std::{AsyncMmapFileExt, AsyncMmapFileMut};
+        use futures_util::AsyncSeekExt;
+        use std::io::SeekFrom;
+
+        let file = AsyncMmapFileMut::memory_from_vec("test.mem", vec![0; 100]);
+        let mut w = file.into_range_writer(10, 20).unwrap();
+        let handle = async_std::task::spawn(async move {
+            w.write_all(b"abcd").await.unwrap();
+            w.seek(SeekFrom::Start(0)).await.unwrap();
+            let mut buf = [0; 4];
+            w.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"abcd");
+            w.close().await.unwrap();
+            w.into_inner()
+        });
+        let file = handle.await;
+        assert_eq!(file.slice(10, 4), b"abcd");
+    }
+
+    #[async_std::test]
+    async fn test_writer_varint() {
+        use crate::async_std::{AsyncMmapFileExt, AsyncMmapFileMut, AsyncMmapFileReaderExt, AsyncMmapFileWriterExt};
+        use crate::LengthPrefix;
+
+        let file = AsyncMmapFileMut::memory_from_vec("test.mem", vec![0; 12]);
+        let mut w = file.into_writer(0).unwrap();
+        w.write_varint(i64::MIN).await.unwrap();
+        w.write_length_prefixed(b"a", LengthPrefix::Varint).await.unwrap();
+        let file = w.into_inner().freeze().unwrap();
+        assert_eq!(file.read_varint(0).unwrap(), (i64::MIN, 10));
+        let mut r = file.into_reader(0).unwrap();
+        assert_eq!(r.read_varint().await.unwrap(), i64::MIN);
+        assert_eq!(r.read_length_prefixed(LengthPrefix::Varint).await.unwrap(), b"a");
+    }
+}
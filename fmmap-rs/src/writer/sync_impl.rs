@@ -3,6 +3,9 @@ use std::io;
 use std::io::Write;
 use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
 use bytes::Buf;
+use crate::varint::{encode_uvarint, into_io_error, zigzag_encode, LengthPrefix, MAX_VARINT_LEN};
+use crate::MmapFileMut;
+use crate::reader::OwnedSlice;
 
 
 /// MmapFileWriter helps read or write data from mmap file
@@ -60,6 +63,10 @@ impl<'a> io::Read for MmapFileWriter<'a> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.w.read(buf)
     }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        self.w.read_vectored(bufs)
+    }
 }
 
 impl<'a> io::BufRead for MmapFileWriter<'a> {
@@ -77,6 +84,10 @@ impl<'a> io::Write for MmapFileWriter<'a> {
         self.w.write(buf)
     }
 
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        self.w.write_vectored(bufs)
+    }
+
     fn flush(&mut self) -> io::Result<()> {
         self.w.flush()
     }
@@ -102,6 +113,134 @@ impl<'a> Buf for MmapFileWriter<'a> {
     }
 }
 
+/// OwnedMmapFileWriter helps read or write data from mmap file
+/// like a normal file.
+///
+/// Unlike [`MmapFileWriter`], it owns the mmap file, so it can be returned from functions
+/// or moved to other threads, see [`MmapFileMut::into_writer`].
+///
+/// # Notes
+/// If you use a writer to write data to mmap, there is no guarantee all
+/// data will be durably stored. So you need to call [`flush`]/[`flush_range`]/[`flush_async`]/[`flush_async_range`] in [`MmapFileMutExt`]
+/// on the file returned by [`into_inner`] to guarantee all data will be durably stored.
+///
+/// [`MmapFileWriter`]: struct.MmapFileWriter.html
+/// [`MmapFileMut::into_writer`]: struct.MmapFileMut.html#method.into_writer
+/// [`flush`]: trait.MmapFileMutExt.html#methods.flush
+/// [`flush_range`]: trait.MmapFileMutExt.html#methods.flush_range
+/// [`flush_async`]: trait.MmapFileMutExt.html#methods.flush_async
+/// [`flush_async_range`]: trait.MmapFileMutExt.html#methods.flush_async_range
+/// [`MmapFileMutExt`]: trait.MmapFileMutExt.html
+/// [`into_inner`]: struct.OwnedMmapFileWriter.html#method.into_inner
+pub struct OwnedMmapFileWriter {
+    w: io::Cursor<OwnedSlice<MmapFileMut>>,
+    offset: usize,
+    len: usize,
+}
+
+impl OwnedMmapFileWriter {
+    pub(crate) fn new(file: MmapFileMut, offset: usize, len: usize) -> Self {
+        Self {
+            w: io::Cursor::new(OwnedSlice::new(file, offset, len)),
+            offset,
+            len,
+        }
+    }
+
+    /// Returns the start offset(related to the mmap) of the writer
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the length of the writer
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Consumes the writer, returns the underlying mmap file.
+    #[inline]
+    pub fn into_inner(self) -> MmapFileMut {
+        self.w.into_inner().into_inner()
+    }
+}
+
+impl Debug for OwnedMmapFileWriter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OwnedMmapFileWriter")
+            .field("offset", &self.offset)
+            .field("len", &self.len)
+            .field("writer", &self.w)
+            .finish()
+    }
+}
+
+impl io::Read for OwnedMmapFileWriter {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.w.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        self.w.read_vectored(bufs)
+    }
+}
+
+impl io::BufRead for OwnedMmapFileWriter {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.w.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.w.consume(amt)
+    }
+}
+
+impl io::Write for OwnedMmapFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let pos = self.w.position();
+        let n = self.w.get_mut().write_at(pos, buf);
+        self.w.set_position(pos + n as u64);
+        Ok(n)
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let mut n = 0;
+        for buf in bufs {
+            let written = self.write(buf)?;
+            n += written;
+            if written < buf.len() {
+                break;
+            }
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Seek for OwnedMmapFileWriter {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.w.seek(pos)
+    }
+}
+
+impl Buf for OwnedMmapFileWriter {
+    fn remaining(&self) -> usize {
+        self.w.remaining()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.w.chunk()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.w.advance(cnt)
+    }
+}
+
 /// Extends MmapFileWriter with methods for writing numbers.
 pub trait MmapFileWriterExt {
     /// Writes a signed 8 bit integer to the underlying writer.
@@ -160,139 +299,176 @@ pub trait MmapFileWriterExt {
     fn write_f64(&mut self, n: f64) -> io::Result<()>;
     /// Writes a IEEE754 single-precision (8 bytes, little endian) floating point number to the underlying writer
     fn write_f64_le(&mut self, n: f64) -> io::Result<()>;
+    /// Writes an unsigned LEB128 variable-length integer to the underlying writer
+    fn write_uvarint(&mut self, n: u64) -> io::Result<()>;
+    /// Writes a zigzag encoded signed LEB128 variable-length integer to the underlying writer
+    fn write_varint(&mut self, n: i64) -> io::Result<()>;
+    /// Writes `src` prefixed by its length to the underlying writer.
+    /// Nothing is written if there is not enough space for the prefix and `src`.
+    fn write_length_prefixed(&mut self, src: &[u8], prefix: LengthPrefix) -> io::Result<()>;
 }
 
-impl<'a> MmapFileWriterExt for MmapFileWriter<'a> {
-    #[inline]
-    fn write_i8(&mut self, n: i8) -> io::Result<()> {
-        self.w.write_i8(n)
-    }
-
-    #[inline]
-    fn write_i16(&mut self, n: i16) -> io::Result<()> {
-        self.w.write_i16::<BigEndian>(n)
-    }
-
-    #[inline]
-    fn write_i16_le(&mut self, n: i16) -> io::Result<()> {
-        self.w.write_i16::<LittleEndian>(n)
-    }
-
-    #[inline]
-    fn write_i32(&mut self, n: i32) -> io::Result<()> {
-        self.w.write_i32::<BigEndian>(n)
-    }
-
-    #[inline]
-    fn write_i32_le(&mut self, n: i32) -> io::Result<()> {
-        self.w.write_i32::<LittleEndian>(n)
-    }
-
-    #[inline]
-    fn write_i64(&mut self, n: i64) -> io::Result<()> {
-        self.w.write_i64::<BigEndian>(n)
-    }
-
-    #[inline]
-    fn write_i64_le(&mut self, n: i64) -> io::Result<()> {
-        self.w.write_i64::<LittleEndian>(n)
-    }
-
-    #[inline]
-    fn write_isize(&mut self, n: isize) -> io::Result<()> {
-        self.w.write_all(n.to_be_bytes().as_ref())
-    }
-
-    #[inline]
-    fn write_isize_le(&mut self, n: isize) -> io::Result<()> {
-        self.w.write_all(n.to_le_bytes().as_ref())
-    }
-
-    #[inline]
-    fn write_i128(&mut self, n: i128) -> io::Result<()> {
-        self.w.write_all(n.to_be_bytes().as_ref())
-    }
-
-    #[inline]
-    fn write_i128_le(&mut self, n: i128) -> io::Result<()> {
-        self.w.write_all(n.to_le_bytes().as_ref())
-    }
-
-    #[inline]
-    fn write_u8(&mut self, n: u8) -> io::Result<()> {
-        self.w.write_u8(n)
-    }
-
-    #[inline]
-    fn write_u16(&mut self, n: u16) -> io::Result<()> {
-        self.w.write_u16::<BigEndian>(n)
-    }
-
-    #[inline]
-    fn write_u16_le(&mut self, n: u16) -> io::Result<()> {
-        self.w.write_u16::<LittleEndian>(n)
-    }
-
-    #[inline]
-    fn write_u32(&mut self, n: u32) -> io::Result<()> {
-        self.w.write_u32::<BigEndian>(n)
-    }
-
-    #[inline]
-    fn write_u32_le(&mut self, n: u32) -> io::Result<()> {
-        self.w.write_u32::<LittleEndian>(n)
-    }
-
-    #[inline]
-    fn write_u64(&mut self, n: u64) -> io::Result<()> {
-        self.w.write_u64::<BigEndian>(n)
-    }
-
-    #[inline]
-    fn write_u64_le(&mut self, n: u64) -> io::Result<()> {
-        self.w.write_u64::<LittleEndian>(n)
-    }
-
-    #[inline]
-    fn write_usize(&mut self, n: usize) -> io::Result<()> {
-        self.w.write_all(n.to_be_bytes().as_ref())
-    }
-
-    #[inline]
-    fn write_usize_le(&mut self, n: usize) -> io::Result<()> {
-        self.w.write_all(n.to_le_bytes().as_ref())
-    }
-
-    #[inline]
-    fn write_u128(&mut self, n: u128) -> io::Result<()> {
-        self.w.write_all(n.to_be_bytes().as_ref())
-    }
-
-    #[inline]
-    fn write_u128_le(&mut self, n: u128) -> io::Result<()> {
-        self.w.write_all(n.to_le_bytes().as_ref())
-    }
-
-    #[inline]
-    fn write_f32(&mut self, n: f32) -> io::Result<()> {
-        self.w.write_f32::<BigEndian>(n)
-    }
-
-    #[inline]
-    fn write_f32_le(&mut self, n: f32) -> io::Result<()> {
-        self.w.write_f32::<LittleEndian>(n)
-    }
+macro_rules! impl_mmap_file_writer_ext {
+    ($name: ty) => {
+        impl MmapFileWriterExt for $name {
+            #[inline]
+            fn write_i8(&mut self, n: i8) -> io::Result<()> {
+                WriteBytesExt::write_i8(self, n)
+            }
+
+            #[inline]
+            fn write_i16(&mut self, n: i16) -> io::Result<()> {
+                WriteBytesExt::write_i16::<BigEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_i16_le(&mut self, n: i16) -> io::Result<()> {
+                WriteBytesExt::write_i16::<LittleEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_i32(&mut self, n: i32) -> io::Result<()> {
+                WriteBytesExt::write_i32::<BigEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_i32_le(&mut self, n: i32) -> io::Result<()> {
+                WriteBytesExt::write_i32::<LittleEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_i64(&mut self, n: i64) -> io::Result<()> {
+                WriteBytesExt::write_i64::<BigEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_i64_le(&mut self, n: i64) -> io::Result<()> {
+                WriteBytesExt::write_i64::<LittleEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_isize(&mut self, n: isize) -> io::Result<()> {
+                Write::write_all(self, n.to_be_bytes().as_ref())
+            }
+
+            #[inline]
+            fn write_isize_le(&mut self, n: isize) -> io::Result<()> {
+                Write::write_all(self, n.to_le_bytes().as_ref())
+            }
+
+            #[inline]
+            fn write_i128(&mut self, n: i128) -> io::Result<()> {
+                Write::write_all(self, n.to_be_bytes().as_ref())
+            }
+
+            #[inline]
+            fn write_i128_le(&mut self, n: i128) -> io::Result<()> {
+                Write::write_all(self, n.to_le_bytes().as_ref())
+            }
+
+            #[inline]
+            fn write_u8(&mut self, n: u8) -> io::Result<()> {
+                WriteBytesExt::write_u8(self, n)
+            }
+
+            #[inline]
+            fn write_u16(&mut self, n: u16) -> io::Result<()> {
+                WriteBytesExt::write_u16::<BigEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_u16_le(&mut self, n: u16) -> io::Result<()> {
+                WriteBytesExt::write_u16::<LittleEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_u32(&mut self, n: u32) -> io::Result<()> {
+                WriteBytesExt::write_u32::<BigEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_u32_le(&mut self, n: u32) -> io::Result<()> {
+                WriteBytesExt::write_u32::<LittleEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_u64(&mut self, n: u64) -> io::Result<()> {
+                WriteBytesExt::write_u64::<BigEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_u64_le(&mut self, n: u64) -> io::Result<()> {
+                WriteBytesExt::write_u64::<LittleEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_usize(&mut self, n: usize) -> io::Result<()> {
+                Write::write_all(self, n.to_be_bytes().as_ref())
+            }
+
+            #[inline]
+            fn write_usize_le(&mut self, n: usize) -> io::Result<()> {
+                Write::write_all(self, n.to_le_bytes().as_ref())
+            }
+
+            #[inline]
+            fn write_u128(&mut self, n: u128) -> io::Result<()> {
+                Write::write_all(self, n.to_be_bytes().as_ref())
+            }
+
+            #[inline]
+            fn write_u128_le(&mut self, n: u128) -> io::Result<()> {
+                Write::write_all(self, n.to_le_bytes().as_ref())
+            }
+
+            #[inline]
+            fn write_f32(&mut self, n: f32) -> io::Result<()> {
+                WriteBytesExt::write_f32::<BigEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_f32_le(&mut self, n: f32) -> io::Result<()> {
+                WriteBytesExt::write_f32::<LittleEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_f64(&mut self, n: f64) -> io::Result<()> {
+                WriteBytesExt::write_f64::<BigEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_f64_le(&mut self, n: f64) -> io::Result<()> {
+                WriteBytesExt::write_f64::<LittleEndian>(self, n)
+            }
+
+            #[inline]
+            fn write_uvarint(&mut self, n: u64) -> io::Result<()> {
+                let mut buf = [0; MAX_VARINT_LEN];
+                let len = encode_uvarint(n, &mut buf);
+                Write::write_all(self, &buf[..len])
+            }
+
+            #[inline]
+            fn write_varint(&mut self, n: i64) -> io::Result<()> {
+                self.write_uvarint(zigzag_encode(n))
+            }
+
+            fn write_length_prefixed(&mut self, src: &[u8], prefix: LengthPrefix) -> io::Result<()> {
+                let mut buf = [0; MAX_VARINT_LEN];
+                let len = prefix.encode(src.len(), &mut buf).map_err(into_io_error)?;
+                if len + src.len() > self.w.remaining() {
+                    return Err(io::Error::from(io::ErrorKind::WriteZero));
+                }
+                Write::write_all(self, &buf[..len])?;
+                Write::write_all(self, src)
+            }
+        }
+    };
+}
 
-    #[inline]
-    fn write_f64(&mut self, n: f64) -> io::Result<()> {
-        self.w.write_f64::<BigEndian>(n)
-    }
+impl_mmap_file_writer_ext!(MmapFileWriter<'_>);
 
-    #[inline]
-    fn write_f64_le(&mut self, n: f64) -> io::Result<()> {
-        self.w.write_f64::<LittleEndian>(n)
-    }
-}
+impl_mmap_file_writer_ext!(OwnedMmapFileWriter);
 
 #[cfg(test)]
 mod tests {
@@ -321,4 +497,68 @@ mod tests {
         let buf = w.chunk();
         assert_eq!(buf.len(), 90);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_owned_writer() {
+        use crate::{MmapFileExt, MmapFileMut, MmapFileWriterExt};
+        use std::io::{Seek, SeekFrom, Write};
+
+        let file = MmapFileMut::memory_from_vec("test.mem", vec![0; 100]);
+        let mut w = file.into_range_writer(10, 20).unwrap();
+        let _ = format!("{:?}", w);
+        assert_eq!(w.len(), 20);
+        assert_eq!(w.offset(), 10);
+        let handle = std::thread::spawn(move || {
+            w.write_u16(0x0102).unwrap();
+            w.write_all(b"abcd").unwrap();
+            w.seek(SeekFrom::End(-2)).unwrap();
+            // the writer never writes out of its range
+            assert_eq!(w.write(b"xyz").unwrap(), 2);
+            assert_eq!(w.write(b"z").unwrap(), 0);
+            w.into_inner()
+        });
+        let file = handle.join().unwrap();
+        assert_eq!(file.slice(10, 6), &[1, 2, b'a', b'b', b'c', b'd']);
+        assert_eq!(file.slice(28, 3), &[b'x', b'y', 0]);
+
+        let mut w = file.into_writer(95).unwrap();
+        assert_eq!(w.remaining(), 5);
+        let mut buf = [1; 5];
+        w.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [0; 5]);
+        assert!(w.into_inner().into_writer(100).is_err());
+    }
+
+    #[test]
+    fn test_writer_varint() {
+        use crate::{LengthPrefix, MmapFileExt, MmapFileWriterExt};
+
+        let mut file = MemoryMmapFileMut::from_vec("test.mem", vec![0; 12]);
+        let mut w = file.writer(0).unwrap();
+        w.write_uvarint(300).unwrap();
+        w.write_varint(-1).unwrap();
+        w.write_length_prefixed(b"abc", LengthPrefix::U32Le).unwrap();
+        let err = w.write_length_prefixed(b"abc", LengthPrefix::Varint).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+        assert_eq!(file.slice(0, 10), &[0xac, 0x02, 0x01, 3, 0, 0, 0, b'a', b'b', b'c']);
+        assert_eq!(file.slice(10, 2), &[0, 0]);
+    }
+
+    #[test]
+    fn test_writer_vectored() {
+        use crate::{MmapFileExt, MmapFileMut};
+        use std::io::{IoSlice, Write};
+
+        let bufs = [IoSlice::new(b"abc"), IoSlice::new(b"defg")];
+        let mut file = MmapFileMut::memory_from_vec("test.mem", vec![0; 10]);
+        let mut w = file.range_writer(1, 5).unwrap();
+        assert_eq!(w.write_vectored(&bufs).unwrap(), 5);
+        assert_eq!(w.write_vectored(&bufs).unwrap(), 0);
+        assert_eq!(file.as_slice(), b"\0abcde\0\0\0\0");
+
+        let mut w = file.into_writer(4).unwrap();
+        assert_eq!(w.write_vectored(&bufs).unwrap(), 6);
+        let file = w.into_inner();
+        assert_eq!(file.as_slice(), b"\0abcabcdef");
+    }
+}
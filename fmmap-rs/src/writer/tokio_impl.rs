@@ -1,68 +1,3 @@
-use std::fmt::{Debug, Formatter};
-use std::io::{Error, SeekFrom, Cursor};
-use std::pin::Pin;
-use std::task::{Context, Poll};
-use bytes::Buf;
-use tokio::io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
-use pin_project_lite::pin_project;
-
-declare_and_impl_basic_writer!();
-
-impl<'a> AsyncRead for AsyncMmapFileWriter<'a> {
-    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
-        self.project().w.poll_read(cx, buf)
-    }
-}
-
-impl<'a> AsyncBufRead for AsyncMmapFileWriter<'a> {
-    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
-        self.project().w.poll_fill_buf(cx)
-    }
-
-    fn consume(self: Pin<&mut Self>, amt: usize) {
-        self.project().w.consume(amt)
-    }
-}
-
-impl<'a> AsyncSeek for AsyncMmapFileWriter<'a> {
-    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
-        self.project().w.start_seek(position)
-    }
-
-    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
-        self.project().w.poll_complete(cx)
-    }
-}
-
-impl<'a> AsyncWrite for AsyncMmapFileWriter<'a> {
-    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
-        self.project().w.poll_write(cx, buf)
-    }
-
-    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
-        self.project().w.poll_flush(cx)
-    }
-
-    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
-        self.project().w.poll_shutdown(cx)
-
-    }
-}
-
-impl<'a> Buf for AsyncMmapFileWriter<'a> {
-    fn remaining(&self) -> usize {
-        self.w.remaining()
-    }
-
-    fn chunk(&self) -> &[u8] {
-        self.w.chunk()
-    }
-
-    fn advance(&mut self, cnt: usize) {
-        self.w.advance(cnt)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use bytes::Buf;
@@ -91,4 +26,68 @@ mod tests {
         let buf = w.chunk();
         assert_eq!(buf.len(), 90);
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "futures-io")]
+    #[tokio::test]
+    async fn test_writer_futures_io() {
+        use crate::tokio::AsyncMmapFileExt;
+        use futures_util::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+        use std::io::SeekFrom;
+
+        let mut file = AsyncMemoryMmapFileMut::from_vec("test.mem", vec![0; 100]);
+        let mut w = file.range_writer(10, 50).unwrap();
+        AsyncWriteExt::write_all(&mut w, &[1; 20]).await.unwrap();
+        AsyncWriteExt::flush(&mut w).await.unwrap();
+        assert_eq!(AsyncSeekExt::seek(&mut w, SeekFrom::Start(15)).await.unwrap(), 15);
+        let mut buf = [0; 10];
+        AsyncReadExt::read_exact(&mut w, &mut buf).await.unwrap();
+        assert_eq!(buf, [1, 1, 1, 1, 1, 0, 0, 0, 0, 0]);
+        AsyncWriteExt::write_all(&mut w, &[2; 30]).await.unwrap_err();
+        AsyncWriteExt::close(&mut w).await.unwrap();
+        assert_eq!(&file.as_slice()[10..30], &[1; 20]);
+        assert_eq!(&file.as_slice()[35..60], &[2; 25]);
+    }
+
+    #[tokio::test]
+    async fn test_owned_writer() {
+        use crate::tokio::{AsyncMmapFileExt, AsyncMmapFileMut};
+        use std::io::SeekFrom;
+        use tokio::io::AsyncSeekExt;
+
+        let file = AsyncMmapFileMut::memory_from_vec("test.mem", vec![0; 100]);
+        let mut w = file.into_range_writer(10, 20).unwrap();
+        let _ = format!("{:?}", w);
+        assert_eq!(w.len(), 20);
+        assert_eq!(w.offset(), 10);
+        let handle = tokio::spawn(async move {
+            w.write_u16(0x0102).await.unwrap();
+            w.write_all(b"abcd").await.unwrap();
+            w.seek(SeekFrom::End(-2)).await.unwrap();
+            // the writer never writes out of its range
+            assert_eq!(w.write(b"xyz").await.unwrap(), 2);
+            assert_eq!(w.write(b"z").await.unwrap(), 0);
+            w.shutdown().await.unwrap();
+            w.into_inner()
+        });
+        let file = handle.await.unwrap();
+        assert_eq!(file.slice(10, 6), &[1, 2, b'a', b'b', b'c', b'd']);
+        assert_eq!(file.slice(28, 3), &[b'x', b'y', 0]);
+
+        let w = file.into_writer(95).unwrap();
+        assert_eq!(w.remaining(), 5);
+        assert!(w.into_inner().into_writer(100).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_writer_varint() {
+        use crate::tokio::{AsyncMmapFileExt, AsyncMmapFileWriterExt};
+        use crate::LengthPrefix;
+
+        let mut file = AsyncMemoryMmapFileMut::from_vec("test.mem", vec![0; 12]);
+        let mut w = file.writer(0).unwrap();
+        w.write_uvarint(300).await.unwrap();
+        w.write_varint(-1).await.unwrap();
+        w.write_length_prefixed(b"abc", LengthPrefix::U32Le).await.unwrap();
+        assert_eq!(file.slice(0, 10), &[0xac, 0x02, 0x01, 3, 0, 0, 0, b'a', b'b', b'c']);
+    }
+}
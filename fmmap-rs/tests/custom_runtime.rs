@@ -0,0 +1,96 @@
+#![cfg(all(unix, feature = "async"))]
+
+use async_trait::async_trait;
+use fmmap::{AsyncMmapFile, AsyncMmapFileExt, AsyncMmapFileMut, AsyncMmapFileMutExt, AsyncOptions, AsyncRuntime, MetaDataExt};
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::PathBuf;
+
+/// A toy runtime which runs every file operation inline, on the thread polling the future.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct InlineRuntime;
+
+/// The file of the toy runtime.
+struct InlineFile {
+    file: std::fs::File,
+}
+
+impl AsRawFd for InlineFile {
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+#[async_trait]
+impl AsyncRuntime for InlineRuntime {
+    type File = InlineFile;
+
+    fn from_std(file: std::fs::File) -> Self::File {
+        InlineFile { file }
+    }
+
+    async fn spawn_blocking<F, T>(f: F) -> io::Result<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        Ok(f())
+    }
+
+    async fn set_len(file: &Self::File, size: u64) -> io::Result<()> {
+        file.file.set_len(size)
+    }
+
+    async fn sync_all(file: &Self::File) -> io::Result<()> {
+        file.file.sync_all()
+    }
+
+    async fn remove_file(path: PathBuf) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+}
+
+#[test]
+fn test_custom_runtime() {
+    let dir = tempdir::TempDir::new("custom_runtime").unwrap();
+    let path = dir.path().join("custom_runtime.txt");
+    let copy = dir.path().join("custom_runtime_copy.txt");
+
+    tokio_test::block_on(async {
+        let opts = AsyncOptions::<InlineRuntime>::new().max_size(100);
+        let mut file = AsyncMmapFileMut::<InlineRuntime>::create_with_options(&path, opts).await.unwrap();
+        assert_eq!(file.len(), 100);
+        file.truncate(12).await.unwrap();
+        assert_eq!(file.metadata().await.unwrap().len(), 12);
+        file.write_all(b"hello, world", 0).unwrap();
+        file.flush().unwrap();
+        file.lock_exclusive().unwrap();
+        file.unlock().unwrap();
+        file.write_all_to_new_file(&copy).await.unwrap();
+        drop(file);
+
+        let file = AsyncMmapFileMut::<InlineRuntime>::open_exist(&path).await.unwrap();
+        assert_eq!(file.as_slice(), b"hello, world");
+        file.drop_remove().await.unwrap();
+        assert!(!path.exists());
+
+        let file = AsyncMmapFile::<InlineRuntime>::open(&copy).await.unwrap();
+        assert_eq!(file.as_slice(), b"hello, world");
+    });
+}
+
+#[tokio::test]
+async fn test_custom_runtime_spawn() {
+    let dir = tempdir::TempDir::new("custom_runtime_spawn").unwrap();
+    let path = dir.path().join("custom_runtime_spawn.txt");
+
+    // the futures of the async mmap files are `Send`, so they can be spawned on a multi-threaded runtime
+    let handle = tokio::spawn(async move {
+        let mut file = AsyncMmapFileMut::<InlineRuntime>::create(&path).await?;
+        file.truncate(10).await?;
+        file.write_all(b"0123456789", 0)?;
+        file.flush()?;
+        file.drop_remove().await
+    });
+    handle.await.unwrap().unwrap();
+}